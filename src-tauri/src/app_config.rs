@@ -0,0 +1,59 @@
+//! App-wide settings that don't belong to any profile
+//!
+//! Stored in ~/.config/unvcpfl/config.toml. Missing or unreadable files give
+//! the defaults, so every setting has to be optional.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Bumped when stored data needs upgrading; see run_migrations
+const CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppConfig {
+    /// Last CONFIG_VERSION the stored data was migrated to
+    #[serde(default)]
+    pub config_version: u32,
+    /// Steam account (userdata/<id>) whose config is read; None = most recently used
+    #[serde(default)]
+    pub steam_user: Option<String>,
+}
+
+fn app_config_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("unvcpfl")
+        .join("config.toml")
+}
+
+pub fn get_app_config() -> AppConfig {
+    fs::read_to_string(app_config_path())
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_app_config(config: &AppConfig) -> Result<(), String> {
+    let path = app_config_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let content = toml::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize app config: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write app config: {}", e))
+}
+
+/// Upgrade stored data from older versions, once per CONFIG_VERSION. A failed
+/// step leaves the version alone so it is retried on the next start.
+pub fn run_migrations() -> Result<(), String> {
+    let mut config = get_app_config();
+    if config.config_version >= CONFIG_VERSION {
+        return Ok(());
+    }
+    // 1: monitor configs stored as Hyprland-format strings
+    crate::screen::migrate_monitor_configs()?;
+
+    config.config_version = CONFIG_VERSION;
+    save_app_config(&config)
+}
@@ -0,0 +1,70 @@
+//! Audio output helpers for per-game sink switching
+//!
+//! Per-game sink switching happens in the wrapper script; this module feeds the
+//! UI and switches sinks for dock profiles.
+
+use std::process::Command;
+
+/// List available audio sinks by name (via `pactl list short sinks`)
+///
+/// Works on both PulseAudio and PipeWire (through pipewire-pulse).
+pub fn list_audio_sinks() -> Vec<String> {
+    Command::new("pactl")
+        .args(["list", "short", "sinks"])
+        .output()
+        .ok()
+        .and_then(|output| {
+            if output.status.success() {
+                String::from_utf8(output.stdout).ok()
+            } else {
+                None
+            }
+        })
+        .map(|s| parse_short_sinks(&s))
+        .unwrap_or_default()
+}
+
+/// Make a sink the default output. pactl takes sink names (wpctl wants node
+/// IDs) and works on PipeWire through pipewire-pulse.
+pub fn set_default_sink(name: &str) -> Result<(), String> {
+    let succeeded = Command::new("pactl")
+        .args(["set-default-sink", name])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+
+    if succeeded {
+        Ok(())
+    } else {
+        Err(format!("Failed to switch audio output to {}", name))
+    }
+}
+
+/// Extract sink names from `pactl list short sinks` output
+/// Format: "<id>\t<name>\t<module>\t<sample spec>\t<state>"
+fn parse_short_sinks(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|l| l.split('\t').nth(1))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_short_sinks() {
+        let output = "48\talsa_output.pci-0000_01_00.1.hdmi-stereo\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED\n\
+                      52\talsa_output.usb-headset.analog-stereo\tPipeWire\ts16le 2ch 48000Hz\tRUNNING\n";
+        assert_eq!(
+            parse_short_sinks(output),
+            vec![
+                "alsa_output.pci-0000_01_00.1.hdmi-stereo",
+                "alsa_output.usb-headset.analog-stereo",
+            ]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Full backup and restore of the app config for moving to a new machine
+//!
+//! Archives the whole `~/.config/unvcpfl` tree (profiles, history, layouts,
+//! generated vkBasalt/Hyprland configs) with the system `tar`. Game saves and
+//! configs found via PCGamingWiki can be archived the same way.
+
+use crate::game_settings;
+use crate::shader_cache::dir_size;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Top-level directory name inside the archive
+const ARCHIVE_ROOT: &str = "unvcpfl";
+
+/// Free space a save backup must leave on top of its estimated size
+const SAVE_BACKUP_MARGIN: u64 = 64 * 1024 * 1024;
+
+/// Outcome of restoring a backup archive
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub restored: usize,
+    pub skipped: Vec<String>,  // Machine-specific entries left out
+    pub warnings: Vec<String>, // Machine-specific entries restored anyway
+}
+
+fn config_parent_dir() -> PathBuf {
+    dirs::config_dir().unwrap_or_else(|| PathBuf::from("~/.config"))
+}
+
+/// Files that describe this machine's monitors or installed versions rather
+/// than user preferences, and are likely wrong on another machine
+fn is_machine_specific(entry: &str) -> bool {
+    let path = entry.strip_prefix(&format!("{}/", ARCHIVE_ROOT)).unwrap_or(entry);
+    path.starts_with("layouts/") // Monitor connector names and modes
+        || path == "hyprland-rules.conf"
+        || path == "dock.toml" // Connector names and sinks of this machine
+        || path == "dock_disabled.toml"
+        || path == "gpu_schedule.toml" // Power limits of this GPU
+        || path.ends_with(".lock.json") // Driver/Proton versions of the old machine
+}
+
+fn run_tar(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("tar")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run tar: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "tar failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// First entry of a `tar -tv` listing that isn't a regular file or directory
+/// (symlinks, hard links, devices, FIFOs), by its mode column
+fn special_entry(verbose_listing: &str) -> Option<&str> {
+    verbose_listing
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .find(|l| !l.starts_with('-') && !l.starts_with('d'))
+}
+
+/// Archive the whole config directory into a .tar.gz at `dest`
+pub fn create_full_backup(dest: &Path) -> Result<(), String> {
+    let parent = config_parent_dir();
+    if !parent.join(ARCHIVE_ROOT).is_dir() {
+        return Err("Nothing to back up: config directory doesn't exist".to_string());
+    }
+
+    run_tar(&[
+        "-czf",
+        &dest.to_string_lossy(),
+        "-C",
+        &parent.to_string_lossy(),
+        ARCHIVE_ROOT,
+    ])
+    .map(|_| ())
+}
+
+/// Unpack a backup made by `create_full_backup` over the current config.
+/// Machine-specific files are skipped when `skip_machine_specific` is set,
+/// otherwise restored and listed as warnings.
+pub fn restore_full_backup(src: &Path, skip_machine_specific: bool) -> Result<RestoreReport, String> {
+    let src = src.to_string_lossy().to_string();
+    let entries: Vec<String> = run_tar(&["-tzf", &src])?
+        .lines()
+        .map(|l| l.trim_start_matches("./").to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    // Refuse archives that would write outside the config directory
+    if let Some(bad) = entries.iter().find(|e| {
+        !(e.as_str() == ARCHIVE_ROOT || e.starts_with(&format!("{}/", ARCHIVE_ROOT)))
+            || e.split('/').any(|part| part == "..")
+    }) {
+        return Err(format!("Not an unvcpfl backup: unexpected entry '{}'", bad));
+    }
+
+    // Only plain files and directories: a symlink or device would let a crafted
+    // archive write through to anywhere the user can
+    if let Some(bad) = special_entry(&run_tar(&["-tvzf", &src])?) {
+        return Err(format!("Not an unvcpfl backup: unsupported entry '{}'", bad));
+    }
+
+    let machine_specific: Vec<String> = entries
+        .iter()
+        .filter(|e| !e.ends_with('/') && is_machine_specific(e))
+        .cloned()
+        .collect();
+    let files = entries.iter().filter(|e| !e.ends_with('/')).count();
+
+    let parent = config_parent_dir().to_string_lossy().to_string();
+    let mut args = vec!["-xzf".to_string(), src, "-C".to_string(), parent];
+    if skip_machine_specific {
+        args.extend(machine_specific.iter().map(|e| format!("--exclude={}", e)));
+    }
+    run_tar(&args.iter().map(String::as_str).collect::<Vec<_>>())?;
+
+    let (skipped, warnings) = if skip_machine_specific {
+        (machine_specific, Vec::new())
+    } else {
+        let warnings = machine_specific
+            .into_iter()
+            .map(|e| format!("{}: from the old machine, check it still matches this one", e))
+            .collect();
+        (Vec::new(), warnings)
+    };
+
+    Ok(RestoreReport {
+        restored: files - skipped.len(),
+        skipped,
+        warnings,
+    })
+}
+
+/// Existing save and config locations of a game, without duplicates
+async fn game_save_paths(steam_appid: u32) -> Vec<PathBuf> {
+    let paths = game_settings::fetch_pcgamingwiki_paths(steam_appid).await;
+    let mut resolved: Vec<PathBuf> = paths
+        .save_paths
+        .iter()
+        .chain(&paths.config_paths)
+        .filter(|p| p.exists)
+        .map(|p| PathBuf::from(&p.resolved_path))
+        .collect();
+    resolved.sort();
+    resolved.dedup();
+    // A path inside another one is archived with it
+    let outer = resolved.clone();
+    resolved.retain(|p| !outer.iter().any(|o| o != p && p.starts_with(o)));
+    resolved
+}
+
+fn path_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        dir_size(path)
+    } else {
+        path.metadata().map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+/// Uncompressed size of a game's saves and configs, in bytes
+pub async fn estimate_save_backup_size(steam_appid: u32) -> u64 {
+    game_save_paths(steam_appid)
+        .await
+        .iter()
+        .map(|p| path_size(p))
+        .sum()
+}
+
+/// Available bytes from `df -B1 --output=avail`
+fn parse_df_avail(output: &str) -> Option<u64> {
+    output.lines().nth(1)?.trim().parse().ok()
+}
+
+fn free_space(dir: &Path) -> Result<u64, String> {
+    let output = Command::new("df")
+        .args(["-B1", "--output=avail"])
+        .arg(dir)
+        .output()
+        .map_err(|e| format!("Failed to run df: {}", e))?;
+    parse_df_avail(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| format!("Could not read free space of {}", dir.display()))
+}
+
+fn check_free_space(free: u64, estimate: u64) -> Result<(), String> {
+    const MIB: u64 = 1024 * 1024;
+    let needed = estimate + SAVE_BACKUP_MARGIN;
+    if free < needed {
+        return Err(format!(
+            "Not enough space for the save backup: {} MiB free, {} MiB needed",
+            free / MIB,
+            needed.div_ceil(MIB)
+        ));
+    }
+    Ok(())
+}
+
+/// Archive a game's saves and configs into a .tar.gz at `dest`, after
+/// making sure the destination has room for them uncompressed
+pub async fn backup_game_saves(steam_appid: u32, dest: &Path) -> Result<(), String> {
+    let paths = game_save_paths(steam_appid).await;
+    if paths.is_empty() {
+        return Err("Nothing to back up: no save or config locations found".to_string());
+    }
+
+    let dest_dir = dest
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let estimate = paths.iter().map(|p| path_size(p)).sum();
+    check_free_space(free_space(dest_dir)?, estimate)?;
+
+    // Stored relative to / so a restore can put them back in place
+    let mut args = vec![
+        "-czf".to_string(),
+        dest.to_string_lossy().to_string(),
+        "-C".to_string(),
+        "/".to_string(),
+    ];
+    args.extend(
+        paths
+            .iter()
+            .map(|p| p.to_string_lossy().trim_start_matches('/').to_string()),
+    );
+    run_tar(&args.iter().map(String::as_str).collect::<Vec<_>>()).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_machine_specific_entries() {
+        assert!(is_machine_specific("unvcpfl/layouts/desk.toml"));
+        assert!(is_machine_specific("unvcpfl/hyprland-rules.conf"));
+        assert!(is_machine_specific("unvcpfl/profiles/cyberpunk.lock.json"));
+        assert!(!is_machine_specific("unvcpfl/profiles/cyberpunk.toml"));
+        assert!(!is_machine_specific("unvcpfl/vkbasalt/cyberpunk.conf"));
+    }
+
+    #[test]
+    fn test_symlink_entries_refused() {
+        let dir = std::env::temp_dir().join(format!("unvcpfl-backup-test-{}", std::process::id()));
+        let root = dir.join(ARCHIVE_ROOT);
+        std::fs::create_dir_all(root.join("profiles")).unwrap();
+        std::fs::write(root.join("profiles/cyberpunk.toml"), "name = \"Cyberpunk\"\n").unwrap();
+        let archive = dir.join("backup.tar.gz").to_string_lossy().to_string();
+        let listing = |archive: &str| run_tar(&["-tvzf", archive]).unwrap();
+
+        run_tar(&["-czf", &archive, "-C", &dir.to_string_lossy(), ARCHIVE_ROOT]).unwrap();
+        assert_eq!(special_entry(&listing(&archive)), None);
+
+        std::os::unix::fs::symlink("/etc", root.join("profiles/etc")).unwrap();
+        run_tar(&["-czf", &archive, "-C", &dir.to_string_lossy(), ARCHIVE_ROOT]).unwrap();
+        let bad = special_entry(&listing(&archive)).map(str::to_string);
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(bad.unwrap().contains("unvcpfl/profiles/etc -> /etc"));
+    }
+
+    #[test]
+    fn test_save_backup_free_space() {
+        let df = "       Avail\n  1073741824\n";
+        assert_eq!(parse_df_avail(df), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_df_avail("Avail\n"), None);
+
+        assert!(check_free_space(1024 * 1024 * 1024, 100 * 1024 * 1024).is_ok());
+        // The margin counts too
+        assert!(check_free_space(100 * 1024 * 1024, 50 * 1024 * 1024).is_err());
+    }
+}
@@ -3,15 +3,32 @@
 //! This binary provides reliable TOML profile parsing for the bash wrapper script.
 //! Usage:
 //!   unvcpfl-cli env <profile_file>        Outputs shell export commands
+//!   unvcpfl-cli env-for-exe <exe_name>    Outputs exports for the profile matching an executable
+//!   unvcpfl-cli profile-for-exe <exe>     Outputs the path of the profile matching an executable
 //!   unvcpfl-cli wrappers <profile_file>   Outputs wrapper command prefix
 //!   unvcpfl-cli lact-profile <profile>    Outputs LACT profile name
 //!   unvcpfl-cli lact-restore <profile>    Outputs "true" or "false"
+//!   unvcpfl-cli audio-sink <profile>      Outputs target audio sink name
+//!   unvcpfl-cli audio-restore <profile>   Outputs "true" or "false"
+//!   unvcpfl-cli monitor [--fields a,b] [--line] [--watch N]
+//!                                         Outputs GPU stats for status bars
+//!   unvcpfl-cli sway-inhibit-idle <cmd>   Runs a command with Sway's idle inhibited
 
 use clap::{Parser, Subcommand};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
+
+// Shared with the GUI; the CLI only needs GpuMonitor::get_info
+#[path = "../nvidia.rs"]
+#[allow(dead_code)]
+mod nvidia;
+
+// Same idle inhibitor wrappers as the app
+#[path = "../idle_inhibit.rs"]
+mod idle_inhibit;
 
 #[derive(Parser)]
 #[command(name = "unvcpfl-cli")]
@@ -25,12 +42,45 @@ struct Cli {
 enum Commands {
     /// Output shell export commands for environment variables
     Env { profile_file: PathBuf },
+    /// Output shell export commands for the profile matching an executable
+    EnvForExe { exe_name: String },
+    /// Output the path of the profile matching an executable
+    ProfileForExe { exe_name: String },
     /// Output wrapper command prefix
     Wrappers { profile_file: PathBuf },
     /// Output LACT profile name
     LactProfile { profile_file: PathBuf },
     /// Output whether to restore LACT profile ("true" or "false")
     LactRestore { profile_file: PathBuf },
+    /// Output target audio sink name
+    AudioSink { profile_file: PathBuf },
+    /// Output whether to restore the previous audio sink ("true" or "false")
+    AudioRestore { profile_file: PathBuf },
+    /// Output the Hyprland workspace window rule for the game, if any
+    ScreenWorkspace { profile_file: PathBuf },
+    /// Output the Hyprland tearing (immediate) window rule for the game, if any
+    ScreenTearing { profile_file: PathBuf },
+    /// Output the wlsunset command holding the profile's night mode colors, if any
+    ScreenNight { profile_file: PathBuf },
+    /// Output the GPUPowerMizerMode value to apply (0 adaptive, 1 max, 2 auto)
+    PowermizerMode { profile_file: PathBuf },
+    /// Output GPU stats as JSON, or as one line for waybar/polybar
+    Monitor {
+        /// Comma-separated fields to include, e.g. temperature,power_draw
+        #[arg(long, value_delimiter = ',')]
+        fields: Vec<String>,
+        /// Print a formatted line instead of JSON
+        #[arg(long)]
+        line: bool,
+        /// Print again every N seconds
+        #[arg(long, value_name = "N")]
+        watch: Option<u64>,
+    },
+    /// Run a command with Sway's idle inhibited until it exits (swayidle ignores logind)
+    SwayInhibitIdle {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        command: Vec<String>,
+    },
 }
 
 // Minimal profile structs for CLI parsing (mirrors main profiles.rs)
@@ -60,6 +110,47 @@ struct DxvkSettings {
     nvapi: bool,
     #[serde(default)]
     async_compile: bool,
+    #[serde(default)]
+    hud_fps: bool,
+    #[serde(default)]
+    hud_frametimes: bool,
+    #[serde(default)]
+    hud_gpuload: bool,
+    #[serde(default)]
+    hud_memory: bool,
+    #[serde(default)]
+    hud_devinfo: bool,
+    #[serde(default)]
+    hud_version: bool,
+    #[serde(default)]
+    hud_api: bool,
+    #[serde(default)]
+    hud_compiler: bool,
+}
+
+impl DxvkSettings {
+    // Same composition as DxvkSettings::hud_value in profiles.rs
+    fn hud_value(&self) -> Option<String> {
+        if let Some(hud) = self.hud.as_ref().filter(|h| !h.trim().is_empty()) {
+            return Some(hud.clone());
+        }
+
+        let items: Vec<&str> = [
+            (self.hud_devinfo, "devinfo"),
+            (self.hud_fps, "fps"),
+            (self.hud_frametimes, "frametimes"),
+            (self.hud_gpuload, "gpuload"),
+            (self.hud_memory, "memory"),
+            (self.hud_api, "api"),
+            (self.hud_version, "version"),
+            (self.hud_compiler, "compiler"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, item)| enabled.then_some(item))
+        .collect();
+
+        (!items.is_empty()).then(|| items.join(","))
+    }
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -89,6 +180,18 @@ struct NvidiaSettings {
     prime: bool,
     #[serde(default)]
     smooth_motion: bool,
+    #[serde(default)]
+    max_frames_allowed: Option<u32>,
+    #[serde(default)]
+    shader_disk_cache: Option<bool>,
+    #[serde(default)]
+    shader_disk_cache_path: Option<String>,
+    #[serde(default)]
+    powermizer_mode: Option<String>,
+    #[serde(default)]
+    gsync_allowed: Option<bool>,
+    #[serde(default)]
+    offload_target: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -101,6 +204,9 @@ struct ProtonSettings {
     enable_hdr: bool,
     #[serde(default)]
     integer_scaling: bool,
+    force_display_server: Option<String>,
+    #[serde(default)]
+    ld_preload: Vec<PathBuf>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -126,6 +232,7 @@ struct GamescopeSettings {
     dsr_width: Option<u32>,
     dsr_height: Option<u32>,
     upscale_filter: Option<String>,
+    scaling_mode: Option<String>,
     fsr_sharpness: Option<u8>,
     #[serde(default = "default_true")]
     fullscreen: bool,
@@ -134,10 +241,13 @@ struct GamescopeSettings {
     #[serde(default)]
     vrr: bool,
     framelimit: Option<u32>,
+    unfocused_framelimit: Option<u32>,
     #[serde(default)]
     mangoapp: bool,
     #[serde(default)]
     hdr: bool,
+    #[serde(default)]
+    extra_args: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -165,12 +275,60 @@ struct WrapperSettings {
     lact_profile: Option<String>,
     #[serde(default = "default_true")]
     lact_restore_after_exit: bool,
+    #[serde(default)]
+    inhibit_idle: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct VkBasaltSettings {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    effects: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct CpuSettings {
+    nice: Option<i32>,
+    ionice_class: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AudioSettings {
+    target_sink: Option<String>,
+    #[serde(default = "default_true")]
+    restore_sink_after_exit: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            target_sink: None,
+            restore_sink_after_exit: true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct ScreenSettings {
+    target_monitor: Option<String>,
+    target_workspace: Option<String>,
+    #[serde(default)]
+    night_mode: bool,
+    night_temperature: Option<u32>,
+    night_gamma: Option<f32>,
+    #[serde(default)]
+    allow_tearing: bool,
 }
 
 #[derive(Debug, Deserialize)]
 struct GameProfile {
     name: String,
     #[serde(default)]
+    is_template: bool,
+    executable_match: Option<String>,
+    steam_appid: Option<u32>,
+    #[serde(default)]
     dlss: DlssSettings,
     #[serde(default)]
     dxvk: DxvkSettings,
@@ -183,6 +341,14 @@ struct GameProfile {
     #[serde(default)]
     wrappers: WrapperSettings,
     #[serde(default)]
+    screen: ScreenSettings,
+    #[serde(default)]
+    audio: AudioSettings,
+    #[serde(default)]
+    vkbasalt: VkBasaltSettings,
+    #[serde(default)]
+    cpu: CpuSettings,
+    #[serde(default)]
     custom_env: HashMap<String, String>,
 }
 
@@ -190,6 +356,59 @@ fn default_true() -> bool {
     true
 }
 
+fn profiles_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("unvcpfl")
+        .join("profiles")
+}
+
+/// Path of the profile whose executable_match equals exe_name,
+/// falling back to the global profile if one exists
+fn find_profile_path_by_executable(exe_name: &str) -> Option<PathBuf> {
+    let dir = profiles_dir();
+
+    // Same tie-break as ProfileManager::get_profile_by_executable:
+    // non-templates, then most recently modified, then name
+    let matched = fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|e| e == "toml").unwrap_or(false))
+        .filter_map(|path| {
+            let profile = toml::from_str::<GameProfile>(&fs::read_to_string(&path).ok()?).ok()?;
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+            Some((profile, modified, path))
+        })
+        .filter(|(p, _, _)| p.executable_match.as_deref() == Some(exe_name))
+        .min_by(|(a, a_time, _), (b, b_time, _)| {
+            a.is_template
+                .cmp(&b.is_template)
+                .then_with(|| b_time.cmp(a_time))
+                .then_with(|| a.name.cmp(&b.name))
+        })
+        .map(|(_, _, path)| path);
+
+    matched.or_else(|| Some(dir.join("global_settings.toml")).filter(|path| path.is_file()))
+}
+
+/// Find the profile whose executable_match equals exe_name,
+/// falling back to the global profile if one exists
+fn find_profile_by_executable(exe_name: &str) -> Option<GameProfile> {
+    let path = find_profile_path_by_executable(exe_name)?;
+    toml::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn print_env(profile: &GameProfile) {
+    let env_vars = build_env_vars(profile);
+    for (key, value) in env_vars {
+        println!("export {}=\"{}\"", key, value.replace('"', "\\\""));
+    }
+}
+
 fn build_env_vars(profile: &GameProfile) -> HashMap<String, String> {
     let mut env = HashMap::new();
 
@@ -223,8 +442,8 @@ fn build_env_vars(profile: &GameProfile) -> HashMap<String, String> {
     }
 
     // DXVK settings
-    if let Some(hud) = &profile.dxvk.hud {
-        env.insert("DXVK_HUD".to_string(), hud.clone());
+    if let Some(hud) = profile.dxvk.hud_value() {
+        env.insert("DXVK_HUD".to_string(), hud);
     }
     if profile.dxvk.nvapi {
         env.insert("DXVK_ENABLE_NVAPI".to_string(), "1".to_string());
@@ -265,14 +484,45 @@ fn build_env_vars(profile: &GameProfile) -> HashMap<String, String> {
         let val = if vsync == "on" { "1" } else { "0" };
         env.insert("__GL_SYNC_TO_VBLANK".to_string(), val.to_string());
     }
+    if profile.screen.allow_tearing && profile.nvidia.vsync.is_none() {
+        env.insert("__GL_SYNC_TO_VBLANK".to_string(), "0".to_string());
+    }
     if profile.nvidia.prime {
         env.insert("__NV_PRIME_RENDER_OFFLOAD".to_string(), "1".to_string());
         env.insert("__VK_LAYER_NV_optimus".to_string(), "NVIDIA_only".to_string());
         env.insert("__GLX_VENDOR_LIBRARY_NAME".to_string(), "nvidia".to_string());
     }
+    match profile.nvidia.offload_target.as_deref() {
+        Some("igpu") => {
+            env.insert("__NV_PRIME_RENDER_OFFLOAD".to_string(), "0".to_string());
+            env.insert("__VK_LAYER_NV_optimus".to_string(), "non_NVIDIA_only".to_string());
+            env.insert("__GLX_VENDOR_LIBRARY_NAME".to_string(), "mesa".to_string());
+            env.insert("DRI_PRIME".to_string(), "0".to_string());
+        }
+        Some("nvidia") => {
+            env.insert("__NV_PRIME_RENDER_OFFLOAD".to_string(), "1".to_string());
+            env.insert("__VK_LAYER_NV_optimus".to_string(), "NVIDIA_only".to_string());
+            env.insert("__GLX_VENDOR_LIBRARY_NAME".to_string(), "nvidia".to_string());
+        }
+        _ => {}
+    }
     if profile.nvidia.smooth_motion {
         env.insert("NVPRESENT_ENABLE_SMOOTH_MOTION".to_string(), "1".to_string());
     }
+    if let Some(frames) = profile.nvidia.max_frames_allowed.filter(|f| (1..=4).contains(f)) {
+        env.insert("__GL_MaxFramesAllowed".to_string(), frames.to_string());
+    }
+    if let Some(enabled) = profile.nvidia.shader_disk_cache {
+        let val = if enabled { "1" } else { "0" };
+        env.insert("__GL_SHADER_DISK_CACHE".to_string(), val.to_string());
+    }
+    if let Some(allowed) = profile.nvidia.gsync_allowed {
+        let val = if allowed { "1" } else { "0" };
+        env.insert("__GL_GSYNC_ALLOWED".to_string(), val.to_string());
+    }
+    if let Some(path) = &profile.nvidia.shader_disk_cache_path {
+        env.insert("__GL_SHADER_DISK_CACHE_PATH".to_string(), path.clone());
+    }
 
     // Proton settings
     if let Some(verb) = &profile.proton.verb {
@@ -289,6 +539,39 @@ fn build_env_vars(profile: &GameProfile) -> HashMap<String, String> {
     if profile.proton.enable_wayland {
         env.insert("PROTON_ENABLE_WAYLAND".to_string(), "1".to_string());
     }
+    match profile.proton.force_display_server.as_deref() {
+        Some("x11") => {
+            env.insert("SDL_VIDEODRIVER".to_string(), "x11".to_string());
+            env.insert("PROTON_ENABLE_WAYLAND".to_string(), "0".to_string());
+            env.insert("WAYLAND_DISPLAY".to_string(), String::new());
+            env.insert("DISPLAY".to_string(), std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string()));
+        }
+        Some("wayland") => {
+            if let Ok(wayland_display) = std::env::var("WAYLAND_DISPLAY") {
+                env.insert("SDL_VIDEODRIVER".to_string(), "wayland".to_string());
+                env.insert("PROTON_ENABLE_WAYLAND".to_string(), "1".to_string());
+                env.insert("WAYLAND_DISPLAY".to_string(), wayland_display);
+            }
+        }
+        _ => {}
+    }
+    // Same as profiles.rs: skip missing libraries, keep the inherited preload last
+    let mut preload: Vec<String> = profile
+        .proton
+        .ld_preload
+        .iter()
+        .filter(|lib| lib.is_file())
+        .map(|lib| lib.to_string_lossy().to_string())
+        .collect();
+    if !preload.is_empty() {
+        let inherited = std::env::var("LD_PRELOAD").unwrap_or_default();
+        for entry in inherited.split([':', ' ']) {
+            if !entry.is_empty() && !preload.iter().any(|e| e == entry) {
+                preload.push(entry.to_string());
+            }
+        }
+        env.insert("LD_PRELOAD".to_string(), preload.join(":"));
+    }
     if profile.proton.enable_hdr {
         env.insert("PROTON_ENABLE_HDR".to_string(), "1".to_string());
     }
@@ -314,6 +597,17 @@ fn build_env_vars(profile: &GameProfile) -> HashMap<String, String> {
         }
     }
 
+    // vkBasalt (config file is written by the app on profile save, only with effects)
+    if profile.vkbasalt.enabled && !profile.vkbasalt.effects.is_empty() {
+        let config = dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("~/.config"))
+            .join("unvcpfl")
+            .join("vkbasalt")
+            .join(format!("{}.conf", profile.name.to_lowercase().replace(' ', "_")));
+        env.insert("ENABLE_VKBASALT".to_string(), "1".to_string());
+        env.insert("VKBASALT_CONFIG_FILE".to_string(), config.to_string_lossy().to_string());
+    }
+
     // Custom env
     for (key, value) in &profile.custom_env {
         env.insert(key.clone(), value.clone());
@@ -322,21 +616,40 @@ fn build_env_vars(profile: &GameProfile) -> HashMap<String, String> {
     env
 }
 
+/// Windows the game opens after launch only get the inhibitor on the next refresh
+const SWAY_INHIBIT_REFRESH: Duration = Duration::from_secs(30);
+
+/// Run `command` with idle inhibited on the Sway views, re-applied while it
+/// runs so the game's own windows are covered too; returns its exit code.
+/// Only the views inhibited here are released afterwards.
+fn run_sway_inhibited(command: &[String]) -> i32 {
+    let mut child = match std::process::Command::new(&command[0]).args(&command[1..]).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Failed to run {}: {}", command[0], e);
+            return 127;
+        }
+    };
+
+    let mut inhibited = Vec::new();
+    let status = loop {
+        inhibited.extend(idle_inhibit::sway_inhibit_views().unwrap_or_default());
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => std::thread::sleep(SWAY_INHIBIT_REFRESH),
+            Err(_) => break child.wait().ok(),
+        }
+    };
+    idle_inhibit::sway_release_views(&inhibited).ok();
+    status.and_then(|s| s.code()).unwrap_or(1)
+}
+
 fn build_wrappers(profile: &GameProfile) -> Vec<String> {
     let mut wrappers = Vec::new();
 
-    if profile.wrappers.mangohud.enabled {
-        wrappers.push("mangohud".to_string());
-    }
-    if profile.wrappers.gamemode {
-        wrappers.push("gamemoderun".to_string());
-    }
-    if profile.wrappers.game_performance {
-        wrappers.push("game-performance".to_string());
-    }
-    if profile.wrappers.dlss_swapper {
-        wrappers.push("dlss-swapper".to_string());
-    }
+    // Same nesting as normalize_wrapper_order in profiles.rs:
+    // gamescope (outermost), mangohud, game-performance, dlss-swapper, gamemoderun, nice, ionice
+    let mangohud_in_gamescope = profile.wrappers.gamescope.enabled && profile.wrappers.mangohud.enabled;
 
     // Gamescope
     if profile.wrappers.gamescope.enabled {
@@ -364,29 +677,119 @@ fn build_wrappers(profile: &GameProfile) -> Vec<String> {
         if gs.vrr {
             gs_args.push("--adaptive-sync".to_string());
         }
-        if let Some(fps) = gs.framelimit {
+        // -r caps the nested refresh, -o the rate while unfocused
+        if let Some(fps) = gs.framelimit.filter(|f| (1..=1000).contains(f)) {
             gs_args.push(format!("-r {}", fps));
         }
-        if gs.mangoapp {
+        if let Some(fps) = gs.unfocused_framelimit.filter(|f| (1..=1000).contains(f)) {
+            gs_args.push(format!("-o {}", fps));
+        }
+        // MangoHud can't hook into gamescope's nested session; use mangoapp instead
+        if gs.mangoapp || mangohud_in_gamescope {
             gs_args.push("--mangoapp".to_string());
         }
         if gs.hdr {
             gs_args.push("--hdr-enabled".to_string());
         }
+        if profile.screen.allow_tearing {
+            gs_args.push("--immediate-flips".to_string());
+        }
         if let Some(filter) = &gs.upscale_filter {
             gs_args.push(format!("-U {}", filter));
         }
+        // Same modes as GAMESCOPE_SCALING_MODES in profiles.rs
+        if let Some(mode) = gs
+            .scaling_mode
+            .as_deref()
+            .filter(|m| ["auto", "integer", "fit", "fill", "stretch"].contains(m))
+        {
+            gs_args.push(format!("-S {}", mode));
+        }
         if let Some(sharpness) = gs.fsr_sharpness {
             gs_args.push(format!("--fsr-sharpness {}", sharpness));
         }
+        // A stray `--` would cut off the game command
+        gs_args.extend(gs.extra_args.iter().filter(|arg| arg.trim() != "--").cloned());
         gs_args.push("--".to_string());
 
         wrappers.push(gs_args.join(" "));
     }
 
+    if profile.wrappers.mangohud.enabled && !mangohud_in_gamescope {
+        wrappers.push("mangohud".to_string());
+    }
+    if profile.wrappers.game_performance {
+        wrappers.push("game-performance".to_string());
+    }
+    if profile.wrappers.dlss_swapper {
+        wrappers.push("dlss-swapper".to_string());
+    }
+    if profile.wrappers.inhibit_idle {
+        wrappers.push(idle_inhibit::InhibitMethod::detect().wrapper().join(" "));
+    }
+    if profile.wrappers.gamemode {
+        wrappers.push("gamemoderun".to_string());
+    }
+    if let Some(nice) = profile.cpu.nice.filter(|n| (-20..=19).contains(n)) {
+        wrappers.push(format!("nice -n {}", nice));
+    }
+    if let Some(class) = profile.cpu.ionice_class.filter(|c| *c <= 3) {
+        wrappers.push(format!("ionice -t -c {}", class));
+    }
+
     wrappers
 }
 
+/// Human-readable value of one GpuInfo field for --line output
+fn format_gpu_field(field: &str, value: &serde_json::Value) -> String {
+    const MIB: f64 = 1024.0 * 1024.0;
+    let number = value.as_f64();
+    match (field, number) {
+        ("temperature", Some(n)) => format!("{:.0}°C", n),
+        ("power_draw" | "power_limit", Some(n)) => format!("{:.0}W", n),
+        ("utilization" | "encoder_utilization" | "decoder_utilization" | "fan_speed", Some(n)) => {
+            format!("{:.0}%", n)
+        }
+        ("memory_used" | "memory_total", Some(n)) => format!("{:.0} MiB", n / MIB),
+        ("clock_graphics" | "clock_memory", Some(n)) => format!("{:.0} MHz", n),
+        (_, _) => match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Null => "-".to_string(),
+            other => other.to_string(),
+        },
+    }
+}
+
+/// Print one GPU sample, restricted to `fields` when given
+fn print_gpu_info(monitor: &nvidia::GpuMonitor, fields: &[String], line: bool) -> Result<(), String> {
+    let info = serde_json::to_value(monitor.get_info(0)?).map_err(|e| e.to_string())?;
+    let all = info.as_object().ok_or("GPU info is not an object")?;
+
+    let mut selected = serde_json::Map::new();
+    if fields.is_empty() {
+        selected = all.clone();
+    } else {
+        for field in fields {
+            let value = all.get(field).ok_or_else(|| {
+                let known: Vec<&str> = all.keys().map(String::as_str).collect();
+                format!("Unknown field '{}' (available: {})", field, known.join(", "))
+            })?;
+            selected.insert(field.clone(), value.clone());
+        }
+    }
+
+    if line {
+        let parts: Vec<String> = selected
+            .iter()
+            .map(|(field, value)| format_gpu_field(field, value))
+            .collect();
+        println!("{}", parts.join(" | "));
+    } else {
+        println!("{}", serde_json::Value::Object(selected));
+    }
+    Ok(())
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -407,9 +810,16 @@ fn main() {
                 }
             };
 
-            let env_vars = build_env_vars(&profile);
-            for (key, value) in env_vars {
-                println!("export {}=\"{}\"", key, value.replace('"', "\\\""));
+            print_env(&profile);
+        }
+        Commands::EnvForExe { exe_name } => {
+            if let Some(profile) = find_profile_by_executable(&exe_name) {
+                print_env(&profile);
+            }
+        }
+        Commands::ProfileForExe { exe_name } => {
+            if let Some(path) = find_profile_path_by_executable(&exe_name) {
+                println!("{}", path.display());
             }
         }
         Commands::Wrappers { profile_file } => {
@@ -457,5 +867,145 @@ fn main() {
 
             println!("{}", profile.wrappers.lact_restore_after_exit);
         }
+        Commands::AudioSink { profile_file } => {
+            let content = match fs::read_to_string(&profile_file) {
+                Ok(c) => c,
+                Err(_) => return,
+            };
+            let profile: GameProfile = match toml::from_str(&content) {
+                Ok(p) => p,
+                Err(_) => return,
+            };
+
+            if let Some(sink) = profile.audio.target_sink {
+                println!("{}", sink);
+            }
+        }
+        Commands::ScreenWorkspace { profile_file } => {
+            let content = match fs::read_to_string(&profile_file) {
+                Ok(c) => c,
+                Err(_) => return,
+            };
+            let profile: GameProfile = match toml::from_str(&content) {
+                Ok(p) => p,
+                Err(_) => return,
+            };
+
+            // Same window class as screen.rs: Proton games are "steam_app_<appid>"
+            let class = profile
+                .steam_appid
+                .map(|id| format!("steam_app_{}", id))
+                .or(profile.executable_match);
+            if let (Some(workspace), Some(class)) = (profile.screen.target_workspace, class) {
+                println!("workspace {},class:^({})$", workspace, class);
+            }
+        }
+        Commands::ScreenTearing { profile_file } => {
+            let content = match fs::read_to_string(&profile_file) {
+                Ok(c) => c,
+                Err(_) => return,
+            };
+            let profile: GameProfile = match toml::from_str(&content) {
+                Ok(p) => p,
+                Err(_) => return,
+            };
+
+            let class = profile
+                .steam_appid
+                .map(|id| format!("steam_app_{}", id))
+                .or(profile.executable_match);
+            if let (true, Some(class)) = (profile.screen.allow_tearing, class) {
+                println!("immediate,class:^({})$", class);
+            }
+        }
+        Commands::ScreenNight { profile_file } => {
+            let content = match fs::read_to_string(&profile_file) {
+                Ok(c) => c,
+                Err(_) => return,
+            };
+            let profile: GameProfile = match toml::from_str(&content) {
+                Ok(p) => p,
+                Err(_) => return,
+            };
+
+            // Same fixed day == night trick as screen.rs night_mode_cmd
+            let screen = profile.screen;
+            if screen.night_mode {
+                let low = screen.night_temperature.unwrap_or(4000).clamp(1000, 6499);
+                let gamma = screen.night_gamma.unwrap_or(1.0);
+                // Same bounds as screen.rs GAMMA_RANGE
+                if !(0.5..=2.0).contains(&gamma) {
+                    eprintln!("Night mode gamma {} is out of range (0.5 to 2.0)", gamma);
+                    return;
+                }
+                let mut cmd = format!("wlsunset -t {} -T {} -g {} -l 0 -L 0", low, low + 1, gamma);
+                if let Some(output) = screen.target_monitor {
+                    cmd.push_str(&format!(" -o {}", output));
+                }
+                println!("{}", cmd);
+            }
+        }
+        Commands::PowermizerMode { profile_file } => {
+            let content = match fs::read_to_string(&profile_file) {
+                Ok(c) => c,
+                Err(_) => return,
+            };
+            let profile: GameProfile = match toml::from_str(&content) {
+                Ok(p) => p,
+                Err(_) => return,
+            };
+
+            let value = match profile.nvidia.powermizer_mode.as_deref() {
+                Some("adaptive") => 0,
+                Some("max") => 1,
+                Some("auto") => 2,
+                _ => return,
+            };
+            println!("{}", value);
+        }
+        Commands::AudioRestore { profile_file } => {
+            let content = match fs::read_to_string(&profile_file) {
+                Ok(c) => c,
+                Err(_) => {
+                    println!("true");
+                    return;
+                }
+            };
+            let profile: GameProfile = match toml::from_str(&content) {
+                Ok(p) => p,
+                Err(_) => {
+                    println!("true");
+                    return;
+                }
+            };
+
+            println!("{}", profile.audio.restore_sink_after_exit);
+        }
+        Commands::Monitor { fields, line, watch } => {
+            let monitor = match nvidia::GpuMonitor::new() {
+                Ok(m) => m,
+                Err(e) => {
+                    eprintln!("Error initializing NVML: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            loop {
+                if let Err(e) = print_gpu_info(&monitor, &fields, line) {
+                    eprintln!("Error reading GPU info: {}", e);
+                    // Unknown fields won't fix themselves; a lost GPU may come back
+                    if watch.is_none() || e.starts_with("Unknown field") {
+                        std::process::exit(1);
+                    }
+                }
+                match watch {
+                    Some(secs) => std::thread::sleep(Duration::from_secs(secs.max(1))),
+                    None => break,
+                }
+            }
+        }
+        Commands::SwayInhibitIdle { command } => {
+            std::process::exit(run_sway_inhibited(&command));
+        }
     }
 }
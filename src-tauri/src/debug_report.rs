@@ -0,0 +1,249 @@
+//! Markdown system report to paste into bug reports
+//!
+//! Collects what maintainers would otherwise ask for one issue comment at a
+//! time. Home paths are redacted and profiles are listed without their names
+//! before anything is returned.
+
+use crate::games::{Game, GameDetector, GameSource};
+use crate::profiles::GameProfile;
+use crate::screen::{self, Compositor};
+use crate::system;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::process::Command;
+
+/// Launch wrappers and helpers profiles can call out to
+const REPORTED_TOOLS: &[&str] = &[
+    "gamescope",
+    "mangohud",
+    "gamemoderun",
+    "game-performance",
+    "lact",
+    "nvidia-settings",
+    "wlsunset",
+    "protontricks",
+];
+
+/// GPU details read up front, so the GPU state lock isn't held while the
+/// rest of the report shells out and scans for games
+pub struct GpuReport {
+    pub name: String,
+    pub nvml_version: Option<String>,
+}
+
+/// Replace the home directory, and `/home/<user>` path segments outside it,
+/// so reports can be posted publicly. The username elsewhere is left alone.
+fn redact(text: &str, home: Option<&str>, user: Option<&str>) -> String {
+    let text = match home {
+        Some(home) if !home.is_empty() => text.replace(home, "~"),
+        _ => text.to_string(),
+    };
+    let Some(user) = user.filter(|u| !u.is_empty()) else {
+        return text;
+    };
+    // Only a whole path segment: /home/alice/ but not /home/alice-mods
+    let pattern = format!(r"/home/{}([^\w.-]|$)", regex::escape(user));
+    match Regex::new(&pattern) {
+        Ok(re) => re.replace_all(&text, "/home/<user>${1}").into_owned(),
+        Err(_) => text,
+    }
+}
+
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn compositor_version(compositor: Compositor) -> Option<String> {
+    match compositor {
+        Compositor::Hyprland => command_stdout("hyprctl", &["version", "-j"])
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            .and_then(|v| v["tag"].as_str().map(String::from)),
+        Compositor::Sway => command_stdout("swaymsg", &["-t", "get_version", "-r"])
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            .and_then(|v| v["human_readable"].as_str().map(String::from)),
+        Compositor::Kde => command_stdout("plasmashell", &["--version"]),
+        Compositor::Gnome => command_stdout("gnome-shell", &["--version"]),
+        _ => None,
+    }
+}
+
+fn source_name(source: &GameSource) -> &'static str {
+    match source {
+        GameSource::Steam => "Steam",
+        GameSource::Lutris => "Lutris",
+        GameSource::Heroic => "Heroic",
+        GameSource::Faugus => "Faugus",
+        GameSource::Wine => "Wine prefixes",
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// A profile as listed in the report: numbered instead of named, since names
+/// are user-chosen and can say more than the user wants posted
+fn profile_line(index: usize, profile: &GameProfile) -> String {
+    let mut line = format!("profile {}", index + 1);
+    if profile.is_template {
+        line.push_str(" (template)");
+    }
+    if let Some(appid) = profile.steam_appid {
+        line.push_str(&format!(", Steam app {}", appid));
+        // Bleeding-edge behaves differently enough to matter for bug reports
+        if let Some(branch) = GameDetector::get_proton_experimental_branch(appid) {
+            line.push_str(&format!(", Proton Experimental {}", branch));
+        }
+    } else if profile.executable_match.is_some() {
+        line.push_str(", bound by executable");
+    }
+    line
+}
+
+/// Build the report around already-detected games and profiles
+fn format_report(gpu: Option<&GpuReport>, games: &[Game], profiles: &[GameProfile]) -> String {
+    let info = system::get_system_info();
+    let compositor = screen::detect_compositor();
+    let mut out = String::new();
+
+    writeln!(out, "## uNVCPfL debug report").ok();
+    writeln!(out).ok();
+    writeln!(out, "- App version: {}", env!("CARGO_PKG_VERSION")).ok();
+    writeln!(out, "- Kernel: {}", info.kernel_version).ok();
+    writeln!(
+        out,
+        "- NVIDIA driver: {}",
+        info.nvidia_driver_version.as_deref().unwrap_or("not loaded")
+    )
+    .ok();
+    writeln!(
+        out,
+        "- Kernel module: {}",
+        info.nvidia_kernel_module.as_deref().unwrap_or("unknown")
+    )
+    .ok();
+    writeln!(out, "- Modules loaded: {}", info.nvidia_modules_loaded.join(", ")).ok();
+    match gpu {
+        Some(gpu) => {
+            writeln!(out, "- GPU: {}", gpu.name).ok();
+            writeln!(
+                out,
+                "- NVML: {}",
+                gpu.nvml_version.as_deref().unwrap_or("unknown")
+            )
+            .ok();
+        }
+        None => {
+            writeln!(out, "- GPU: NVML unavailable").ok();
+        }
+    }
+    writeln!(out, "- ntsync: {}", yes_no(info.ntsync_available)).ok();
+    writeln!(out, "- MangoHud Vulkan layer: {}", yes_no(info.mangohud_layer_installed)).ok();
+    writeln!(out, "- NVIDIA Vulkan ICD first: {}", yes_no(info.nvidia_icd_first)).ok();
+    writeln!(out, "- Steam Deck / SteamOS: {}", yes_no(info.steam_deck)).ok();
+    writeln!(
+        out,
+        "- Compositor: {} {}",
+        screen::compositor_name(compositor),
+        compositor_version(compositor).unwrap_or_default()
+    )
+    .ok();
+    writeln!(
+        out,
+        "- Session: {}",
+        std::env::var("XDG_SESSION_TYPE").unwrap_or_else(|_| "unknown".to_string())
+    )
+    .ok();
+
+    let mut launchers: BTreeMap<&str, usize> = BTreeMap::new();
+    for game in games {
+        *launchers.entry(source_name(&game.source)).or_default() += 1;
+    }
+    writeln!(out, "\n### Launchers\n").ok();
+    if launchers.is_empty() {
+        writeln!(out, "- none detected").ok();
+    }
+    for (launcher, count) in &launchers {
+        writeln!(out, "- {}: {} games", launcher, count).ok();
+    }
+
+    writeln!(out, "\n### Vulkan ICDs (load order)\n").ok();
+    if info.vulkan_icds.is_empty() {
+        writeln!(out, "- none found").ok();
+    }
+    for icd in &info.vulkan_icds {
+        writeln!(out, "- `{}`", icd).ok();
+    }
+
+    writeln!(out, "\n### Wrapper tools\n").ok();
+    for tool in REPORTED_TOOLS {
+        writeln!(out, "- {}: {}", tool, yes_no(system::is_tool_available(tool))).ok();
+    }
+
+    writeln!(out, "\n### Profiles ({})\n", profiles.len()).ok();
+    for (i, profile) in profiles.iter().enumerate() {
+        writeln!(out, "- {}", profile_line(i, profile)).ok();
+    }
+
+    writeln!(out, "\n### Shell env conflicts\n").ok();
+    let conflicts = system::scan_shell_env_conflicts();
+    if conflicts.is_empty() {
+        writeln!(out, "- none").ok();
+    }
+    for conflict in conflicts {
+        writeln!(out, "- `{}`", conflict).ok();
+    }
+
+    let home = dirs::home_dir().map(|h| h.to_string_lossy().to_string());
+    let user = std::env::var("USER").ok();
+    redact(&out, home.as_deref(), user.as_deref())
+}
+
+/// Markdown report of driver, compositor, launchers, profiles and tools
+pub fn generate_debug_report(gpu: Option<&GpuReport>, profiles: &[GameProfile]) -> String {
+    let games = GameDetector::detect_all_games();
+    format_report(gpu, &games, profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_home_and_user() {
+        let text = "- `/home/alice/.bashrc:3: DXVK_HUD=fps`\n- Alice's alice-mods";
+        assert_eq!(
+            redact(text, Some("/home/alice"), Some("alice")),
+            "- `~/.bashrc:3: DXVK_HUD=fps`\n- Alice's alice-mods"
+        );
+        // HOME elsewhere (e.g. /var/home): /home/<user> paths are still caught
+        assert_eq!(
+            redact("/home/alice/Games and /home/alice", Some("/var/home/alice"), Some("alice")),
+            "/home/<user>/Games and /home/<user>"
+        );
+        assert_eq!(redact("/home/alice-mods", None, Some("alice")), "/home/alice-mods");
+        assert_eq!(redact("/home/a/x a b", None, Some("a")), "/home/<user>/x a b");
+    }
+
+    #[test]
+    fn test_profile_line_omits_name() {
+        let profile = GameProfile {
+            name: "Alice's secret save edit".to_string(),
+            is_template: true,
+            executable_match: Some("game.exe".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(profile_line(2, &profile), "profile 3 (template), bound by executable");
+    }
+}
@@ -0,0 +1,80 @@
+//! Desktop "game mode" integration
+//!
+//! Keeps the screen from blanking or dimming during a game session by taking
+//! an idle inhibitor the running compositor/idle daemon actually honors.
+
+use crate::idle_inhibit::{sway_inhibit_views, sway_release_views, InhibitMethod};
+use crate::screen::{detect_compositor, Compositor};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command};
+use std::sync::Mutex;
+
+/// Long-running inhibitor held while desktop game mode is toggled on manually
+static INHIBITOR: Mutex<Option<Child>> = Mutex::new(None);
+
+/// Sway views desktop game mode inhibited, so turning it off leaves the rest alone
+static SWAY_INHIBITED: Mutex<Vec<i64>> = Mutex::new(Vec::new());
+
+/// Wrapper that holds an idle inhibitor for the lifetime of the wrapped command
+pub fn idle_inhibit_wrapper(compositor: Compositor) -> Vec<String> {
+    let method = match compositor {
+        Compositor::Sway => InhibitMethod::Sway,
+        Compositor::Gnome => InhibitMethod::Gnome,
+        _ => InhibitMethod::Logind,
+    };
+    method.wrapper()
+}
+
+/// Turn desktop game mode (idle/blanking inhibition) on or off for the session
+pub fn set_desktop_gamemode(enabled: bool) -> Result<(), String> {
+    let compositor = detect_compositor();
+
+    if compositor == Compositor::Sway {
+        let mut inhibited = SWAY_INHIBITED.lock().map_err(|e| e.to_string())?;
+        sway_release_views(&inhibited)?;
+        inhibited.clear();
+        if enabled {
+            *inhibited = sway_inhibit_views()?;
+        }
+        return Ok(());
+    }
+
+    let mut inhibitor = INHIBITOR.lock().map_err(|e| e.to_string())?;
+
+    // Release any existing inhibitor first so toggling on twice doesn't leak one
+    if let Some(mut child) = inhibitor.take() {
+        // Kill the whole group so the `sleep` doesn't outlive the inhibitor
+        Command::new("kill")
+            .args(["-TERM", &format!("-{}", child.id())])
+            .status()
+            .ok();
+        child.wait().ok();
+    }
+
+    if enabled {
+        let mut cmd = idle_inhibit_wrapper(compositor);
+        cmd.extend(["sleep".to_string(), "infinity".to_string()]);
+        let child = Command::new(&cmd[0])
+            .args(&cmd[1..])
+            .process_group(0)
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", cmd[0], e))?;
+        *inhibitor = Some(child);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inhibit_wrapper_survives_word_splitting() {
+        for compositor in [Compositor::Gnome, Compositor::Sway, Compositor::Hyprland] {
+            let wrapper = idle_inhibit_wrapper(compositor);
+            let resplit: Vec<&str> = wrapper.iter().flat_map(|a| a.split_whitespace()).collect();
+            assert_eq!(resplit, wrapper);
+        }
+    }
+}
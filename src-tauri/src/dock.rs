@@ -0,0 +1,201 @@
+//! Docked/undocked screen and audio setup for handhelds and laptops
+//!
+//! A dock profile reuses the per-game ScreenSettings and AudioSettings, but is
+//! applied to the desktop as a whole: e.g. move to the TV and turn off the
+//! built-in panel when docked, and bring the panel back when undocked.
+
+use crate::audio;
+use crate::profiles::{AudioSettings, GameProfile, ScreenSettings};
+use crate::screen::{self, MonitorConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Connector prefixes of built-in panels
+const INTERNAL_CONNECTORS: &[&str] = &["eDP", "LVDS", "DSI"];
+
+/// Screen and audio settings for one dock state
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DockProfile {
+    #[serde(default)]
+    pub screen: ScreenSettings,
+    #[serde(default)]
+    pub audio: AudioSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DockProfiles {
+    #[serde(default)]
+    pub docked: DockProfile,
+    #[serde(default)]
+    pub undocked: DockProfile,
+}
+
+fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("unvcpfl")
+}
+
+fn dock_profiles_path() -> PathBuf {
+    config_dir().join("dock.toml")
+}
+
+/// Monitor configs disabled by the dock profile, to re-enable on undock.
+/// On disk so an app restart while docked doesn't strand the built-in panel.
+pub(crate) fn disabled_by_dock_path() -> PathBuf {
+    config_dir().join("dock_disabled.toml")
+}
+
+fn load_disabled_by_dock() -> HashMap<String, MonitorConfig> {
+    fs::read_to_string(disabled_by_dock_path())
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_disabled_by_dock(configs: &HashMap<String, MonitorConfig>) -> Result<(), String> {
+    let path = disabled_by_dock_path();
+    if configs.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to clear disabled monitors: {}", e))?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let content = toml::to_string_pretty(configs)
+        .map_err(|e| format!("Failed to serialize disabled monitors: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write disabled monitors: {}", e))
+}
+
+pub fn get_dock_profiles() -> DockProfiles {
+    fs::read_to_string(dock_profiles_path())
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_dock_profiles(profiles: &DockProfiles) -> Result<(), String> {
+    let path = dock_profiles_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let content = toml::to_string_pretty(profiles)
+        .map_err(|e| format!("Failed to serialize dock profiles: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write dock profiles: {}", e))
+}
+
+fn is_external_monitor(name: &str) -> bool {
+    !INTERNAL_CONNECTORS.iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// Whether an external monitor is connected and active
+pub fn is_docked() -> Result<bool, String> {
+    Ok(screen::list_monitors()?
+        .iter()
+        .any(|m| m.active && is_external_monitor(&m.name)))
+}
+
+fn apply(profile: &DockProfile) -> Result<(), String> {
+    // Without a window class only the monitor switching applies
+    let settings = GameProfile {
+        screen: profile.screen.clone(),
+        ..Default::default()
+    };
+    if profile.screen.target_monitor.is_some() {
+        screen::apply_screen_settings(&settings)?;
+    }
+    if let Some(sink) = &profile.audio.target_sink {
+        audio::set_default_sink(sink)?;
+    }
+    Ok(())
+}
+
+/// Apply the docked profile; fails if no external monitor is connected
+pub fn apply_dock_profile() -> Result<(), String> {
+    if !is_docked()? {
+        return Err("No external monitor connected".to_string());
+    }
+    let profile = get_dock_profiles().docked;
+
+    // Remember monitors the profile is about to turn off
+    if let (Some(target), true) = (
+        &profile.screen.target_monitor,
+        profile.screen.disable_other_monitors,
+    ) {
+        let mut disabled = load_disabled_by_dock();
+        disabled.extend(
+            screen::get_monitor_configs()?
+                .into_iter()
+                .filter(|(name, _)| name != target),
+        );
+        save_disabled_by_dock(&disabled)?;
+    }
+
+    apply(&profile)
+}
+
+/// Re-enable monitors the dock profile turned off, then apply the undocked
+/// profile. Every monitor is attempted; built-in panels that fail stay on
+/// record for the next try and are reported in the error.
+pub fn apply_undocked_profile() -> Result<(), String> {
+    let mut failed = HashMap::new();
+    let mut errors = Vec::new();
+    for (name, config) in load_disabled_by_dock() {
+        match screen::enable_monitor(&name, &config) {
+            // The external monitor may already be unplugged
+            Err(_) if is_external_monitor(&name) => {}
+            Err(e) => {
+                errors.push(format!("{}: {}", name, e));
+                failed.insert(name, config);
+            }
+            Ok(()) => {}
+        }
+    }
+    save_disabled_by_dock(&failed)?;
+
+    if let Err(e) = apply(&get_dock_profiles().undocked) {
+        errors.push(e);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        errors.sort();
+        Err(errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_monitor_detection() {
+        assert!(!is_external_monitor("eDP-1"));
+        assert!(!is_external_monitor("DSI-1")); // Steam Deck panel
+        assert!(is_external_monitor("HDMI-A-1"));
+        assert!(is_external_monitor("DP-2"));
+    }
+
+    #[test]
+    fn test_disabled_monitors_round_trip() {
+        let configs = HashMap::from([(
+            "eDP-1".to_string(),
+            MonitorConfig {
+                width: 1280,
+                height: 800,
+                refresh_rate: 90.0,
+                x: 0,
+                y: 0,
+                scale: 1.25,
+            },
+        )]);
+        let content = toml::to_string_pretty(&configs).unwrap();
+        let parsed: HashMap<String, MonitorConfig> = toml::from_str(&content).unwrap();
+        assert_eq!(parsed, configs);
+    }
+}
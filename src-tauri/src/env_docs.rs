@@ -0,0 +1,158 @@
+//! Descriptions of environment variables used by Proton, Wine, DXVK and the
+//! NVIDIA driver, and which profile field (if any) already manages each one
+
+use crate::profiles::GameProfile;
+use serde::Serialize;
+
+pub struct EnvVarDoc {
+    pub name: &'static str,
+    pub field: Option<&'static str>, // Profile field that sets it, if first-class
+    pub description: &'static str,
+}
+
+const fn doc(name: &'static str, field: Option<&'static str>, description: &'static str) -> EnvVarDoc {
+    EnvVarDoc { name, field, description }
+}
+
+pub const ENV_VAR_DOCS: &[EnvVarDoc] = &[
+    // DLSS
+    doc("PROTON_DLSS_UPGRADE", Some("dlss.upgrade"), "Replace the game's DLSS DLL with the latest one"),
+    doc("PROTON_DLSS_INDICATOR", Some("dlss.indicator"), "Show the DLSS version overlay"),
+    doc("PROTON_ENABLE_NGX_UPDATER", Some("dlss.ngx_updater"), "Let the NGX updater fetch newer DLSS models"),
+    doc("DXVK_NVAPI_DRS_NGX_DLSS_SR_OVERRIDE", Some("dlss.sr_override"), "Override the DLSS Super Resolution preset"),
+    doc("DXVK_NVAPI_DRS_NGX_DLSS_RR_OVERRIDE", Some("dlss.rr_override"), "Override the DLSS Ray Reconstruction preset"),
+    doc("DXVK_NVAPI_DRS_NGX_DLSS_FG_OVERRIDE", Some("dlss.fg_override"), "Override DLSS Frame Generation"),
+    doc("DXVK_NVAPI_DRS_NGX_DLSS_SR_OVERRIDE_RENDER_PRESET_SELECTION", Some("dlss.sr_preset"), "DLSS Super Resolution render preset"),
+    doc("DXVK_NVAPI_DRS_NGX_DLSS_RR_OVERRIDE_RENDER_PRESET_SELECTION", Some("dlss.rr_preset"), "DLSS Ray Reconstruction render preset"),
+    doc("DXVK_NVAPI_DRS_NGX_DLSSG_MULTI_FRAME_COUNT", Some("dlss.fg_multi_frame"), "Generated frames per rendered frame"),
+    // DXVK / VKD3D
+    doc("DXVK_HUD", Some("dxvk.hud"), "DXVK overlay contents (fps, frametimes, ...)"),
+    doc("DXVK_ENABLE_NVAPI", Some("dxvk.nvapi"), "Expose NVAPI to the game through DXVK-NVAPI"),
+    doc("DXVK_ASYNC", Some("dxvk.async_compile"), "Compile shaders asynchronously (dxvk-async/GPL builds)"),
+    doc("DXVK_FRAME_RATE", Some("wrappers.frame_limiter.target_fps"), "DXVK built-in frame limiter"),
+    doc("VKD3D_CONFIG", Some("vkd3d"), "VKD3D-Proton feature flags (dxr, no_dxr, single_queue, ...)"),
+    doc("VKD3D_FRAME_RATE", Some("wrappers.frame_limiter.target_fps"), "VKD3D-Proton built-in frame limiter"),
+    doc("VKD3D_SWAPCHAIN_LATENCY_FRAMES", Some("wrappers.frame_limiter.swapchain_latency"), "Maximum frames queued in the D3D12 swapchain"),
+    doc("DXVK_CONFIG_FILE", None, "Path to a dxvk.conf with per-game DXVK options"),
+    doc("DXVK_STATE_CACHE_PATH", None, "Where DXVK keeps its pipeline state cache"),
+    doc("DXVK_LOG_LEVEL", None, "DXVK log verbosity (none, error, warn, info, debug)"),
+    doc("VKD3D_DEBUG", None, "VKD3D-Proton log verbosity"),
+    doc("VKD3D_SHADER_CACHE_PATH", None, "Where VKD3D-Proton keeps its shader cache"),
+    // NVIDIA
+    doc("__GL_SYNC_TO_VBLANK", Some("nvidia.vsync"), "Force OpenGL vsync on or off"),
+    doc("__NV_PRIME_RENDER_OFFLOAD", Some("nvidia.prime"), "Render on the NVIDIA GPU in a PRIME setup (0 keeps the game on the iGPU)"),
+    doc("__VK_LAYER_NV_optimus", Some("nvidia.prime"), "Expose only the NVIDIA GPU to Vulkan under PRIME (non_NVIDIA_only hides it)"),
+    doc("__GLX_VENDOR_LIBRARY_NAME", Some("nvidia.prime"), "Pick the GLX implementation: nvidia, or mesa for the iGPU"),
+    doc("DRI_PRIME", Some("nvidia.offload_target"), "Mesa GPU selection; 0 is the default (integrated) GPU"),
+    doc("NVPRESENT_ENABLE_SMOOTH_MOTION", Some("nvidia.smooth_motion"), "Driver-side frame generation (RTX 40/50)"),
+    doc("__GL_MaxFramesAllowed", Some("nvidia.max_frames_allowed"), "OpenGL frames queued ahead of the GPU"),
+    doc("__GL_SHADER_DISK_CACHE", Some("nvidia.shader_disk_cache"), "Enable the NVIDIA shader disk cache"),
+    doc("__GL_SHADER_DISK_CACHE_PATH", Some("nvidia.shader_disk_cache_path"), "Location of the NVIDIA shader disk cache"),
+    doc("__GL_GSYNC_ALLOWED", Some("nvidia.gsync_allowed"), "Allow G-SYNC/VRR for this game (driver-wide VRR must be allowed too)"),
+    doc("__GL_SHADER_DISK_CACHE_SKIP_CLEANUP", None, "Let the NVIDIA shader cache grow past its size limit"),
+    doc("__GL_THREADED_OPTIMIZATIONS", None, "Threaded OpenGL driver optimizations"),
+    // Proton / Wine
+    doc("PROTON_VERB", Some("proton.verb"), "Proton launch verb (waitforexitandrun, run, ...)"),
+    doc("PROTON_NO_ESYNC", Some("proton.sync_mode"), "Disable eventfd-based synchronization"),
+    doc("PROTON_NO_FSYNC", Some("proton.sync_mode"), "Disable futex-based synchronization"),
+    doc("WINEFSYNC_FUTEX2", Some("proton.sync_mode"), "Use futex2/ntsync synchronization"),
+    doc("PROTON_ENABLE_WAYLAND", Some("proton.enable_wayland"), "Use Wine's native Wayland driver"),
+    doc("SDL_VIDEODRIVER", Some("proton.force_display_server"), "Display backend SDL picks (x11 or wayland)"),
+    doc("PROTON_ENABLE_HDR", Some("proton.enable_hdr"), "Enable HDR output (Wayland/gamescope)"),
+    doc("WINE_FULLSCREEN_INTEGER_SCALING", Some("proton.integer_scaling"), "Integer scaling for fullscreen games"),
+    doc("LD_PRELOAD", Some("proton.ld_preload"), "Libraries loaded into the game first (mods, fps unlockers)"),
+    doc("PROTON_LOG", None, "Write a Proton log to ~/steam-<appid>.log"),
+    doc("PROTON_USE_WINED3D", None, "Use WineD3D (OpenGL) instead of DXVK"),
+    doc("PROTON_NO_D3D11", None, "Disable D3D11 so the game falls back to D3D9/10"),
+    doc("PROTON_NO_D3D12", None, "Disable D3D12"),
+    doc("PROTON_HIDE_NVIDIA_GPU", None, "Report the NVIDIA GPU as AMD to the game"),
+    doc("PROTON_ENABLE_NVAPI", None, "Enable NVAPI in Proton (DLSS, Reflex)"),
+    doc("PROTON_FORCE_LARGE_ADDRESS_AWARE", None, "Give 32-bit games the full 4 GB address space"),
+    doc("WINEDLLOVERRIDES", None, "Native/builtin load order for specific DLLs"),
+    doc("WINEPREFIX", None, "Wine prefix directory to run in"),
+    doc("WINEDEBUG", None, "Wine debug channels (-all to silence)"),
+    doc("STAGING_SHARED_MEMORY", None, "Wine-staging shared memory optimization"),
+    // Overlays and post-processing
+    doc("MANGOHUD_CONFIG", Some("wrappers.mangohud.fps_limit"), "MangoHud options (fps_limit, position, ...)"),
+    doc("MANGOHUD", None, "Load MangoHud as a Vulkan layer without the wrapper"),
+    doc("ENABLE_VKBASALT", Some("vkbasalt.enabled"), "Load the vkBasalt post-processing layer"),
+    doc("VKBASALT_CONFIG_FILE", Some("vkbasalt.enabled"), "vkBasalt config written from the profile"),
+];
+
+/// Look up the description entry for an environment variable
+pub fn find_env_doc(name: &str) -> Option<&'static EnvVarDoc> {
+    ENV_VAR_DOCS.iter().find(|d| d.name == name)
+}
+
+/// Classification of one custom_env entry
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomEnvNote {
+    pub key: String,
+    pub kind: String,                // "managed", "known" or "unknown"
+    pub field: Option<String>,       // First-class field to migrate to, for "managed"
+    pub description: Option<String>,
+}
+
+/// Classify each custom_env key so leftovers can be migrated or documented
+pub fn analyze_custom_env(profile: &GameProfile) -> Vec<CustomEnvNote> {
+    let mut notes: Vec<CustomEnvNote> = profile
+        .custom_env
+        .keys()
+        .map(|key| match find_env_doc(key) {
+            Some(doc) => CustomEnvNote {
+                key: key.clone(),
+                kind: if doc.field.is_some() { "managed" } else { "known" }.to_string(),
+                field: doc.field.map(String::from),
+                description: Some(doc.description.to_string()),
+            },
+            None => CustomEnvNote {
+                key: key.clone(),
+                kind: "unknown".to_string(),
+                field: None,
+                description: None,
+            },
+        })
+        .collect();
+
+    notes.sort_by(|a, b| a.key.cmp(&b.key));
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::ProfileManager;
+
+    #[test]
+    fn test_analyze_custom_env_kinds() {
+        let mut profile = GameProfile::default();
+        for key in ["DXVK_ASYNC", "PROTON_LOG", "MY_MOD_FLAG"] {
+            profile.custom_env.insert(key.to_string(), "1".to_string());
+        }
+
+        let notes = analyze_custom_env(&profile);
+        let kinds: Vec<(&str, &str)> = notes.iter().map(|n| (n.key.as_str(), n.kind.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![("DXVK_ASYNC", "managed"), ("MY_MOD_FLAG", "unknown"), ("PROTON_LOG", "known")]
+        );
+        assert_eq!(notes[0].field.as_deref(), Some("dxvk.async_compile"));
+    }
+
+    #[test]
+    fn test_managed_fields_match_env_builder() {
+        // Every var the builder sets for this profile must be documented with its field
+        let mut profile = GameProfile::default();
+        profile.dxvk.async_compile = true;
+        profile.dxvk.nvapi = true;
+        profile.dlss.upgrade = true;
+        profile.nvidia.prime = true;
+        profile.nvidia.max_frames_allowed = Some(2);
+
+        let manager = ProfileManager::with_profiles_dir(std::env::temp_dir().join("unvcpfl-test-env-docs"));
+        for (key, source) in manager.trace_env_sources(&profile) {
+            let doc = find_env_doc(&key).unwrap_or_else(|| panic!("{} is undocumented", key));
+            assert_eq!(doc.field, Some(source.as_str()), "{}", key);
+        }
+    }
+}
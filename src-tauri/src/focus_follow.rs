@@ -0,0 +1,200 @@
+//! Apply a game's LACT profile only while the game has focus (Hyprland)
+//!
+//! Listens on Hyprland's event socket for `activewindow` changes. When a
+//! profiled game gains focus its LACT profile (clocks, power limit) is set;
+//! alt-tabbing to anything else puts the desktop profile back.
+
+use crate::lact::{current_lact_profile, set_lact_profile};
+use crate::profiles::{GameProfile, ProfileManager};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Focus must settle this long before switching, so quick alt-tabs don't thrash clocks
+const FOCUS_DEBOUNCE: Duration = Duration::from_millis(750);
+
+struct FocusFollow {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+static FOCUS_FOLLOW: Mutex<Option<FocusFollow>> = Mutex::new(None);
+
+fn event_socket_path() -> Result<PathBuf, String> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+        .map_err(|_| "Focus follow needs Hyprland (HYPRLAND_INSTANCE_SIGNATURE not set)".to_string())?;
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
+
+    // Hyprland 0.40+ uses $XDG_RUNTIME_DIR/hypr, older versions /tmp/hypr
+    [PathBuf::from(runtime_dir), PathBuf::from("/tmp")]
+        .into_iter()
+        .map(|dir| dir.join("hypr").join(&signature).join(".socket2.sock"))
+        .find(|p| p.exists())
+        .ok_or_else(|| "Hyprland event socket not found".to_string())
+}
+
+/// Window class from an `activewindow>>class,title` event line
+fn parse_active_window(line: &str) -> Option<&str> {
+    line.strip_prefix("activewindow>>")
+        .and_then(|rest| rest.split(',').next())
+}
+
+/// The profile a focused window belongs to (Proton games are "steam_app_<appid>")
+fn profile_for_class<'a>(profiles: &'a [GameProfile], class: &str) -> Option<&'a GameProfile> {
+    let appid = class
+        .strip_prefix("steam_app_")
+        .and_then(|id| id.parse::<u32>().ok());
+    let class = class.to_lowercase();
+
+    profiles.iter().filter(|p| !p.is_template).find(|p| {
+        if appid.is_some() && p.steam_appid == appid {
+            return true;
+        }
+        p.executable_match.as_ref().is_some_and(|exe| {
+            let exe = exe.to_lowercase();
+            exe == class || exe.strip_suffix(".exe") == Some(class.as_str())
+        })
+    })
+}
+
+/// Tracks which LACT profile is applied and what to restore
+#[derive(Default)]
+struct FocusState {
+    desktop_profile: Option<String>, // Active before the first switch
+    applied: Option<String>,
+}
+
+impl FocusState {
+    fn focus(&mut self, wanted: Option<String>) {
+        if wanted == self.applied {
+            return;
+        }
+        match wanted {
+            Some(profile) => {
+                if self.applied.is_none() {
+                    self.desktop_profile = current_lact_profile();
+                }
+                set_lact_profile(&profile).ok();
+                self.applied = Some(profile);
+            }
+            None => self.restore(),
+        }
+    }
+
+    fn restore(&mut self) {
+        if self.applied.take().is_some() {
+            if let Some(desktop) = &self.desktop_profile {
+                set_lact_profile(desktop).ok();
+            }
+        }
+    }
+}
+
+async fn follow_focus(manager: Arc<ProfileManager>, stream: UnixStream, mut stop: oneshot::Receiver<()>) {
+    let mut lines = BufReader::new(stream).lines();
+    let mut state = FocusState::default();
+    let mut pending: Option<String> = None;
+
+    loop {
+        // Only wait out the debounce while a focus change is pending
+        let debounce = async {
+            match pending {
+                Some(_) => tokio::time::sleep(FOCUS_DEBOUNCE).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            _ = &mut stop => break,
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => {
+                    if let Some(class) = parse_active_window(&line) {
+                        pending = Some(class.to_string());
+                    }
+                }
+                // Socket closed (Hyprland exited) or errored
+                _ => break,
+            },
+            _ = debounce => {
+                if let Some(class) = pending.take() {
+                    // Profile reads and lact calls block, keep them off the async workers
+                    let manager = Arc::clone(&manager);
+                    let mut moved = std::mem::take(&mut state);
+                    state = tokio::task::spawn_blocking(move || {
+                        let profiles = manager.list_profiles();
+                        let wanted = profile_for_class(&profiles, &class)
+                            .and_then(|p| p.wrappers.lact_profile.clone());
+                        moved.focus(wanted);
+                        moved
+                    })
+                    .await
+                    .unwrap_or_default();
+                }
+            }
+        }
+    }
+
+    tokio::task::spawn_blocking(move || state.restore()).await.ok();
+}
+
+/// Start switching LACT profiles with window focus
+pub async fn start_focus_follow(manager: Arc<ProfileManager>) -> Result<(), String> {
+    if !crate::profiles::is_lact_available() {
+        return Err("Focus follow needs LACT to switch GPU profiles".to_string());
+    }
+    let stream = UnixStream::connect(event_socket_path()?)
+        .await
+        .map_err(|e| format!("Failed to connect to Hyprland events: {}", e))?;
+
+    let mut follow = FOCUS_FOLLOW.lock().map_err(|e| e.to_string())?;
+    // A task that ended on its own (Hyprland socket closed) is replaced
+    if follow.as_ref().is_some_and(|f| !f.task.is_finished()) {
+        return Err("Focus follow is already running".to_string());
+    }
+
+    let (stop, stop_rx) = oneshot::channel();
+    let task = tokio::spawn(follow_focus(manager, stream, stop_rx));
+    *follow = Some(FocusFollow { stop, task });
+    Ok(())
+}
+
+/// Stop following focus and put the desktop LACT profile back
+pub async fn stop_focus_follow() -> Result<(), String> {
+    let follow = FOCUS_FOLLOW.lock().map_err(|e| e.to_string())?.take();
+    if let Some(follow) = follow {
+        follow.stop.send(()).ok();
+        follow.task.await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focused_window_matches_profile() {
+        let profiles = vec![
+            GameProfile {
+                name: "Elden Ring".to_string(),
+                steam_appid: Some(1245620),
+                ..Default::default()
+            },
+            GameProfile {
+                name: "Osu".to_string(),
+                executable_match: Some("osu!.exe".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let class = parse_active_window("activewindow>>steam_app_1245620,ELDEN RING™").unwrap();
+        assert_eq!(profile_for_class(&profiles, class).map(|p| p.name.as_str()), Some("Elden Ring"));
+        assert_eq!(profile_for_class(&profiles, "osu!").map(|p| p.name.as_str()), Some("Osu"));
+        assert!(profile_for_class(&profiles, "firefox").is_none());
+        assert!(parse_active_window("workspace>>2").is_none());
+    }
+}
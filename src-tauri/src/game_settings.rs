@@ -3,8 +3,10 @@
 
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 use std::process::Command;
+use std::sync::Mutex;
 
 /// A resolved game path with existence status
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,7 +51,7 @@ struct WikiQueryResponse {
 
 #[derive(Debug, Deserialize)]
 struct WikiQuery {
-    pages: std::collections::HashMap<String, WikiPage>,
+    pages: HashMap<String, WikiPage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -63,32 +65,51 @@ struct WikiRevision {
     content: String,
 }
 
-/// Fetch game data paths from PCGamingWiki
-pub async fn fetch_pcgamingwiki_paths(steam_appid: u32) -> GameDataPaths {
+/// Wikitext already fetched this session, keyed by AppID: (page name, wikitext)
+static WIKITEXT_CACHE: Mutex<Option<HashMap<u32, (String, String)>>> = Mutex::new(None);
+
+/// Page name and wikitext for a Steam AppID, fetched once per session.
+/// On error, the page name is included when only the wikitext fetch failed.
+async fn fetch_wikitext(steam_appid: u32) -> Result<(String, String), (Option<String>, String)> {
+    if let Some(cached) = WIKITEXT_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.as_ref()?.get(&steam_appid).cloned())
+    {
+        return Ok(cached);
+    }
+
     let client = reqwest::Client::builder()
         .user_agent("Mozilla/5.0 (X11; Linux x86_64) uNVCPfL/1.0")
         .build()
         .unwrap_or_default();
 
     // Step 1: Get page name from Steam AppID
-    let page_name = match get_page_name(&client, steam_appid).await {
-        Ok(name) => name,
-        Err(e) => {
-            return GameDataPaths {
-                game_name: format!("AppID {}", steam_appid),
-                config_paths: vec![],
-                save_paths: vec![],
-                error: Some(e),
-            }
-        }
-    };
+    let page_name = get_page_name(&client, steam_appid)
+        .await
+        .map_err(|e| (None, e))?;
 
     // Step 2: Get wiki content
     let wikitext = match get_wikitext(&client, &page_name).await {
         Ok(text) => text,
-        Err(e) => {
+        Err(e) => return Err((Some(page_name), e)),
+    };
+
+    if let Ok(mut cache) = WIKITEXT_CACHE.lock() {
+        cache
+            .get_or_insert_with(HashMap::new)
+            .insert(steam_appid, (page_name.clone(), wikitext.clone()));
+    }
+    Ok((page_name, wikitext))
+}
+
+/// Fetch game data paths from PCGamingWiki
+pub async fn fetch_pcgamingwiki_paths(steam_appid: u32) -> GameDataPaths {
+    let (page_name, wikitext) = match fetch_wikitext(steam_appid).await {
+        Ok(fetched) => fetched,
+        Err((page_name, e)) => {
             return GameDataPaths {
-                game_name: page_name,
+                game_name: page_name.unwrap_or_else(|| format!("AppID {}", steam_appid)),
                 config_paths: vec![],
                 save_paths: vec![],
                 error: Some(e),
@@ -108,6 +129,231 @@ pub async fn fetch_pcgamingwiki_paths(steam_appid: u32) -> GameDataPaths {
     }
 }
 
+/// Graphics APIs listed in the game's PCGamingWiki API section,
+/// e.g. ["Direct3D 11", "Direct3D 12", "Vulkan"]; empty when unknown
+pub async fn detect_game_graphics_api(steam_appid: u32) -> Vec<String> {
+    fetch_wikitext(steam_appid)
+        .await
+        .map(|(_, wikitext)| parse_graphics_apis(&wikitext))
+        .unwrap_or_default()
+}
+
+/// Parse the `{{API ...}}` template fields into API names
+fn parse_graphics_apis(wikitext: &str) -> Vec<String> {
+    let Some(start) = wikitext.find("{{API\n").or_else(|| wikitext.find("{{API|")) else {
+        return vec![];
+    };
+    let mut apis = vec![];
+
+    for line in wikitext[start + 5..].lines() {
+        let line = line.trim();
+        if line.starts_with("}}") {
+            break;
+        }
+        let Some((key, value)) = line.trim_start_matches('|').split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty()
+            || value.eq_ignore_ascii_case("false")
+            || value.eq_ignore_ascii_case("unknown")
+        {
+            continue;
+        }
+        let supported = value.eq_ignore_ascii_case("true");
+
+        match key.trim() {
+            // Versions are listed as "9, 11" or "11 (default), 12"
+            "direct3d versions" => apis.extend(
+                value
+                    .split(',')
+                    .filter_map(|v| v.split_whitespace().next())
+                    .filter(|v| v.chars().next().is_some_and(|c| c.is_ascii_digit()))
+                    .map(|v| format!("Direct3D {}", v)),
+            ),
+            "directdraw versions" => apis.push("DirectDraw".to_string()),
+            "opengl versions" => apis.push("OpenGL".to_string()),
+            "glide versions" => apis.push("Glide".to_string()),
+            "vulkan versions" => apis.push("Vulkan".to_string()),
+            "mantle support" if supported => apis.push("Mantle".to_string()),
+            "software mode" if supported => apis.push("Software".to_string()),
+            _ => {}
+        }
+    }
+
+    apis
+}
+
+/// Upscalers from the game's PCGamingWiki Video section, e.g. ["DLSS", "FSR"].
+/// None when the wiki can't be reached or doesn't say; empty when it lists none.
+pub async fn detect_game_upscalers(steam_appid: u32) -> Option<Vec<String>> {
+    fetch_wikitext(steam_appid)
+        .await
+        .ok()
+        .and_then(|(_, wikitext)| parse_upscalers(&wikitext))
+}
+
+/// Parse `upscaling`, `upscaling tech` and `upscaling notes` from `{{Video ...}}`
+fn parse_upscalers(wikitext: &str) -> Option<Vec<String>> {
+    let start = wikitext.find("{{Video")?;
+    let section = &wikitext[start..];
+    let section = &section[..section.find("\n}}").unwrap_or(section.len())];
+    let name_re = Regex::new(r"\b(DLSS|FSR|XeSS|TSR|PSSR)\b").unwrap();
+
+    let mut flag = None;
+    let mut upscalers: Vec<String> = Vec::new();
+    for line in section.lines() {
+        let Some((key, value)) = line.trim().trim_start_matches('|').split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "upscaling" => match value.trim().to_lowercase().as_str() {
+                "true" | "limited" | "hackable" => flag = Some(true),
+                "false" | "n/a" => flag = Some(false),
+                _ => {}
+            },
+            "upscaling tech" | "upscaling notes" => {
+                upscalers.extend(name_re.find_iter(value).map(|m| m.as_str().to_string()))
+            }
+            _ => {}
+        }
+    }
+
+    upscalers.sort();
+    upscalers.dedup();
+    match flag {
+        Some(false) => Some(Vec::new()),
+        // "true" without naming the technique says nothing about DLSS
+        _ if upscalers.is_empty() => None,
+        _ => Some(upscalers),
+    }
+}
+
+/// Winetricks verbs a game's PCGamingWiki fixes or middleware call for
+pub async fn suggest_winetricks(steam_appid: u32) -> Vec<String> {
+    fetch_wikitext(steam_appid)
+        .await
+        .map(|(_, wikitext)| parse_winetricks_verbs(&wikitext))
+        .unwrap_or_default()
+}
+
+/// The parts of a page that tell the reader what to install: `{{Fixbox}}`
+/// templates and sections headed like "Issues fixed". API tables and
+/// availability notes mention runtimes too, but only as facts about the game.
+fn fix_sections(wikitext: &str) -> Vec<&str> {
+    let mut sections: Vec<&str> = wikitext
+        .match_indices("{{Fixbox")
+        .map(|(start, _)| {
+            let section = &wikitext[start..];
+            &section[..section.find("\n}}").unwrap_or(section.len())]
+        })
+        .collect();
+
+    // A fix section runs until the next heading of the same or a higher level
+    let mut fix_section: Option<(usize, usize)> = None; // (start offset, heading level)
+    let mut offset = 0;
+    for line in wikitext.split_inclusive('\n') {
+        let heading = line.trim();
+        if heading.len() > 2 && heading.starts_with('=') && heading.ends_with('=') {
+            let level = heading.chars().take_while(|&c| c == '=').count();
+            if let Some((start, fix_level)) = fix_section {
+                if level <= fix_level {
+                    sections.push(&wikitext[start..offset]);
+                    fix_section = None;
+                }
+            }
+            if fix_section.is_none() && heading.to_lowercase().contains("fix") {
+                fix_section = Some((offset + line.len(), level));
+            }
+        }
+        offset += line.len();
+    }
+    if let Some((start, _)) = fix_section {
+        sections.push(&wikitext[start..]);
+    }
+    sections
+}
+
+/// Verbs named in fix instructions (e.g. "protontricks 1234 vcrun2019"),
+/// plus runtimes implied by the Middleware template
+fn parse_winetricks_verbs(wikitext: &str) -> Vec<String> {
+    let verb_re = Regex::new(
+        r"(?i)\b(vcrun(?:20\d\d|6)|dotnet\d{2,3}|d3dx9(?:_\d+)?|d3dx1[01](?:_\d+)?|d3dcompiler_\d+|xact(?:_x64)?|physx|xna\d+|quartz|wmp\d+|mfc\d+|corefonts|openal)\b",
+    )
+    .unwrap();
+    let mut verbs: Vec<String> = fix_sections(wikitext)
+        .into_iter()
+        .flat_map(|section| verb_re.find_iter(section))
+        .map(|m| m.as_str().to_lowercase())
+        .collect();
+
+    // Middleware that needs a runtime the prefix doesn't ship
+    if let Some(start) = wikitext.find("{{Middleware") {
+        let section = &wikitext[start..];
+        let section = &section[..section.find("\n}}").unwrap_or(section.len())];
+        for line in section.lines() {
+            let Some((key, value)) = line.trim_start_matches('|').split_once('=') else {
+                continue;
+            };
+            let value = value.to_lowercase();
+            match key.trim() {
+                "physics" if value.contains("physx") => verbs.push("physx".to_string()),
+                "audio" if value.contains("xact") => verbs.push("xact".to_string()),
+                "audio" if value.contains("openal") => verbs.push("openal".to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    verbs.sort();
+    verbs.dedup();
+    verbs
+}
+
+/// Install winetricks verbs into the game's prefix, preferring protontricks
+pub async fn apply_winetricks(steam_appid: u32, verbs: &[String]) -> Result<String, String> {
+    if verbs.is_empty() {
+        return Err("No winetricks verbs given".to_string());
+    }
+    if let Some(bad) = verbs
+        .iter()
+        .find(|v| v.is_empty() || !v.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+    {
+        return Err(format!("Invalid winetricks verb '{}'", bad));
+    }
+
+    let mut cmd = if crate::system::is_tool_available("protontricks") {
+        let mut cmd = tokio::process::Command::new("protontricks");
+        cmd.arg(steam_appid.to_string());
+        cmd
+    } else if crate::system::is_tool_available("winetricks") {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/home/user".to_string());
+        let prefix = find_proton_prefix(steam_appid, &home);
+        if !Path::new(&prefix).exists() {
+            return Err(format!("No Proton prefix for AppID {} (run the game once)", steam_appid));
+        }
+        let mut cmd = tokio::process::Command::new("winetricks");
+        cmd.env("WINEPREFIX", prefix);
+        cmd
+    } else {
+        return Err("Neither protontricks nor winetricks is installed".to_string());
+    };
+
+    let output = cmd
+        .arg("-q")
+        .args(verbs)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run winetricks: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "winetricks failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
 /// Get page name from Steam AppID using cargo query
 async fn get_page_name(client: &reqwest::Client, steam_appid: u32) -> Result<String, String> {
     let url = format!(
@@ -277,7 +523,7 @@ fn find_proton_prefix(steam_appid: u32, home: &str) -> String {
 }
 
 /// Find game installation path
-fn find_game_install_path(steam_appid: u32, home: &str) -> String {
+pub(crate) fn find_game_install_path(steam_appid: u32, home: &str) -> String {
     let library_paths = [
         format!("{}/.steam/steam/steamapps", home),
         format!("{}/.local/share/Steam/steamapps", home),
@@ -315,6 +561,108 @@ pub fn check_path_exists(path: &str) -> bool {
     Path::new(path).exists()
 }
 
+/// Width/height key pairs used by common engines in ini-style configs
+const RESOLUTION_KEYS: &[(&str, &str)] = &[
+    ("resolutionsizex", "resolutionsizey"), // Unreal Engine GameUserSettings.ini
+    ("isize w", "isize h"),                 // Bethesda
+    ("screenwidth", "screenheight"),
+    ("resolutionwidth", "resolutionheight"),
+    ("resolution_width", "resolution_height"),
+    ("width", "height"),
+];
+
+/// Parse a resolution from ini-style `key=value` content
+fn parse_ini_resolution(content: &str) -> Option<(u32, u32)> {
+    let mut values = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with(';') || line.starts_with('#') || line.starts_with('[') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=').or_else(|| line.split_once(':')) {
+            let value = value.trim().trim_matches('"');
+            // First occurrence wins, later sections are usually defaults
+            values
+                .entry(key.trim().to_lowercase())
+                .or_insert_with(|| value.to_string());
+        }
+    }
+
+    // Single-key form, e.g. Resolution=1920x1080
+    if let Some(value) = values.get("resolution") {
+        if let Some((w, h)) = value.split_once(['x', 'X']) {
+            if let (Ok(w), Ok(h)) = (w.trim().parse(), h.trim().parse()) {
+                return Some((w, h));
+            }
+        }
+    }
+
+    RESOLUTION_KEYS.iter().find_map(|(w_key, h_key)| {
+        let w: u32 = values.get(*w_key)?.parse().ok()?;
+        let h: u32 = values.get(*h_key)?.parse().ok()?;
+        (w > 0 && h > 0).then_some((w, h))
+    })
+}
+
+/// Parse a Unity resolution from a Wine user.reg
+/// (`"Screenmanager Resolution Width_h182942802"=dword:00000780`)
+fn parse_registry_resolution(content: &str) -> Option<(u32, u32)> {
+    let dword = |prefix: &str| {
+        content.lines().find_map(|line| {
+            let rest = line.trim().strip_prefix(prefix)?;
+            let hex = rest.split_once("=dword:")?.1;
+            u32::from_str_radix(hex.trim(), 16).ok()
+        })
+    };
+    let w = dword("\"Screenmanager Resolution Width")?;
+    let h = dword("\"Screenmanager Resolution Height")?;
+    (w > 0 && h > 0).then_some((w, h))
+}
+
+/// Config files directly under a resolved config path (file or directory)
+fn config_files(path: &Path) -> Vec<std::path::PathBuf> {
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.is_file()
+                        && p.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+                            matches!(e.to_lowercase().as_str(), "ini" | "cfg" | "txt")
+                        })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Best-effort detection of the game's last-used resolution from its own
+/// config files (via PCGamingWiki paths) or the prefix registry
+pub async fn detect_game_resolution(steam_appid: u32) -> Option<(u32, u32)> {
+    let paths = fetch_pcgamingwiki_paths(steam_appid).await;
+    let from_config = paths
+        .config_paths
+        .iter()
+        .filter(|p| p.exists)
+        .flat_map(|p| config_files(Path::new(&p.resolved_path)))
+        .find_map(|file| {
+            let content = std::fs::read_to_string(file).ok()?;
+            parse_ini_resolution(&content)
+        });
+    if from_config.is_some() {
+        return from_config;
+    }
+
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/home/user".to_string());
+    let user_reg = format!("{}/user.reg", find_proton_prefix(steam_appid, &home));
+    let content = std::fs::read_to_string(user_reg).ok()?;
+    parse_registry_resolution(&content)
+}
+
 /// Open path in file manager (Dolphin, Nautilus, etc.)
 pub fn open_in_file_manager(path: &str) -> Result<(), String> {
     let path_to_open = if Path::new(path).is_file() {
@@ -403,4 +751,45 @@ mod tests {
         let resolved = resolve_wine_path(raw, 292030);
         assert!(resolved.contains("Documents/The Witcher 3"));
     }
+
+    #[test]
+    fn test_parse_config_resolution() {
+        let ue = "[/Script/Engine.GameUserSettings]\nResolutionSizeX=2560\nResolutionSizeY=1440\n";
+        assert_eq!(parse_ini_resolution(ue), Some((2560, 1440)));
+        assert_eq!(parse_ini_resolution("Resolution = 1920x1080"), Some((1920, 1080)));
+        assert_eq!(parse_ini_resolution("; Width=1\nfov=90"), None);
+
+        let reg = "\"Screenmanager Resolution Height_h2627697771\"=dword:00000438\n\"Screenmanager Resolution Width_h182942802\"=dword:00000780\n";
+        assert_eq!(parse_registry_resolution(reg), Some((1920, 1080)));
+    }
+
+    #[test]
+    fn test_parse_upscalers() {
+        let wikitext = "{{Video\n|upscaling              = true\n|upscaling tech         = DLSS 3.7, FSR 2.2, XeSS\n|upscaling notes        = \n}}";
+        assert_eq!(parse_upscalers(wikitext), Some(vec!["DLSS".into(), "FSR".into(), "XeSS".into()]));
+        assert_eq!(parse_upscalers("{{Video\n|upscaling = false\n}}"), Some(vec![]));
+        assert_eq!(parse_upscalers("{{Video\n|upscaling = true\n}}"), None);
+        assert_eq!(parse_upscalers("==Video=="), None);
+    }
+
+    #[test]
+    fn test_parse_graphics_apis() {
+        let wikitext = "==API==\n{{API\n|direct3d versions      = 11 (default), 12\n|direct3d notes         =\n|directdraw versions    =\n|opengl versions        = false\n|vulkan versions        = 1.2\n|mantle support         = false\n|software mode          = unknown\n}}\n{{Middleware\n|audio = Wwise\n}}";
+        assert_eq!(parse_graphics_apis(wikitext), vec!["Direct3D 11", "Direct3D 12", "Vulkan"]);
+        assert!(parse_graphics_apis("==Video==").is_empty());
+    }
+
+    #[test]
+    fn test_parse_winetricks_verbs() {
+        let wikitext = "===Game crashes on startup===\n{{Fixbox|description=Install runtimes|fix=\nRun <code>protontricks 12345 vcrun2019 d3dx9_43</code>.\n}}\n{{Middleware\n|physics = PhysX\n|audio   = FMOD\n}}";
+        assert_eq!(parse_winetricks_verbs(wikitext), vec!["d3dx9_43", "physx", "vcrun2019"]);
+        assert!(parse_winetricks_verbs("No known issues.").is_empty());
+
+        // Runtimes mentioned outside fixes and the Middleware template are facts, not advice
+        let wikitext = "==Availability==\nShips with corefonts and the quartz video player.\n\
+            ==API==\n{{API\n|direct3d versions = 9\n|physx notes = Uses PhysX and OpenAL\n}}\n\
+            ==Issues fixed==\n===Missing music===\nInstall <code>xact</code>.\n\
+            ==System requirements==\nOpenAL soft is bundled.\n";
+        assert_eq!(parse_winetricks_verbs(wikitext), vec!["xact"]);
+    }
 }
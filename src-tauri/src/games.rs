@@ -1,9 +1,10 @@
+use crate::{app_config, vdf};
 use regex::Regex;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -12,6 +13,7 @@ pub enum GameSource {
     Lutris,
     Heroic,
     Faugus,
+    Wine, // Bare prefix no launcher knows about
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +26,35 @@ pub struct Game {
     pub icon_url: Option<String>,
 }
 
+/// A Steam account with a userdata/<id> directory on this machine
+#[derive(Debug, Clone, Serialize)]
+pub struct SteamUser {
+    pub id: String,                   // SteamID3 account id, the userdata dir name
+    pub persona_name: Option<String>, // From localconfig.vdf
+}
+
+/// Installers, redistributables and crash reporters shipped next to game binaries
+const HELPER_EXECUTABLE_PREFIXES: &[&str] = &[
+    "unins",
+    "vcredist",
+    "vc_redist",
+    "dxsetup",
+    "dotnet",
+    "setup",
+    "crashreport",
+    "crashpad_handler",
+    "unitycrashhandler",
+    "ue4prereqsetup",
+    "ueprereqsetup",
+    "easyanticheat",
+];
+
+/// Steam app id of Proton Experimental
+const PROTON_EXPERIMENTAL_APPID: u32 = 1493710;
+
+/// Default Steam install on the Steam Deck's `deck` user
+const STEAM_DECK_LIBRARY: &str = "/home/deck/.local/share/Steam";
+
 pub struct GameDetector;
 
 impl GameDetector {
@@ -82,7 +113,7 @@ impl GameDetector {
         // Find Steam library folders
         let steam_paths = Self::get_steam_library_paths();
 
-        for library_path in steam_paths {
+        for library_path in &steam_paths {
             let steamapps = library_path.join("steamapps");
             if !steamapps.exists() {
                 continue;
@@ -92,7 +123,7 @@ impl GameDetector {
             for entry in fs::read_dir(&steamapps).into_iter().flatten().flatten() {
                 let path = entry.path();
                 if path.extension().map(|e| e == "acf").unwrap_or(false) {
-                    if let Some(game) = Self::parse_acf_file(&path, &steamapps) {
+                    if let Some(game) = Self::parse_acf_file(&path, &steamapps, &steam_paths) {
                         // Deduplicate by appid
                         if !seen_appids.contains(&game.id) {
                             seen_appids.insert(game.id.clone());
@@ -106,7 +137,201 @@ impl GameDetector {
         games
     }
 
-    fn get_steam_library_paths() -> Vec<PathBuf> {
+    /// Main Steam installation directory (holds config/ and the default library)
+    fn steam_root() -> Option<PathBuf> {
+        let home = dirs::home_dir()?;
+        [
+            home.join(".steam").join("steam"),
+            home.join(".local").join("share").join("Steam"),
+        ]
+        .into_iter()
+        .find(|p| p.exists())
+        .and_then(|p| fs::canonicalize(p).ok())
+    }
+
+    /// Compatibility tool Steam forces for a game ("Force the use of a specific
+    /// Steam Play compatibility tool"), read from config.vdf's CompatToolMapping.
+    /// Steam's mapping wins over anything a profile sets.
+    pub fn get_game_proton_override(steam_appid: u32) -> Option<String> {
+        let config = Self::steam_root()?.join("config").join("config.vdf");
+        let content = fs::read_to_string(config).ok()?;
+        let root = vdf::parse(&content);
+
+        root.get_path(&[
+            "InstallConfigStore",
+            "Software",
+            "Valve",
+            "Steam",
+            "CompatToolMapping",
+            &steam_appid.to_string(),
+            "name",
+        ])
+        .and_then(|v| v.as_str())
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+    }
+
+    /// Branch of Proton Experimental a game runs on ("default" or
+    /// "bleeding-edge"); None when the game isn't on Experimental or it can't
+    /// be told. The build's version file names bleeding-edge builds; Steam's
+    /// beta key for the tool covers builds that don't.
+    pub fn get_proton_experimental_branch(steam_appid: u32) -> Option<String> {
+        let prefix_version = Self::find_compatdata_path(steam_appid)
+            .and_then(|dir| fs::read_to_string(dir.join("version")).ok());
+        let tool = Self::get_game_proton_override(steam_appid);
+        if !uses_proton_experimental(tool.as_deref(), prefix_version.as_deref()) {
+            return None;
+        }
+
+        let install = Self::find_steam_install_path(PROTON_EXPERIMENTAL_APPID);
+        let build_version = install
+            .as_ref()
+            .and_then(|dir| fs::read_to_string(dir.join("version")).ok());
+        let beta_key = Self::get_steam_library_paths().iter().find_map(|lib| {
+            let manifest = lib
+                .join("steamapps")
+                .join(format!("appmanifest_{}.acf", PROTON_EXPERIMENTAL_APPID));
+            let content = fs::read_to_string(manifest).ok()?;
+            vdf::parse(&content)
+                .get_path(&["AppState", "UserConfig", "BetaKey"])
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        });
+
+        // The prefix's version is what the game last ran with, if Experimental isn't installed
+        let version = build_version.or(prefix_version.filter(|_| install.is_none()))?;
+        Some(experimental_branch(&version, beta_key.as_deref()))
+    }
+
+    /// userdata/<id> directories of real accounts ("0" is the anonymous user)
+    fn steam_user_dirs() -> Vec<(String, PathBuf)> {
+        let Some(userdata) = Self::steam_root().map(|r| r.join("userdata")) else {
+            return Vec::new();
+        };
+        let mut dirs: Vec<(String, PathBuf)> = fs::read_dir(userdata)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+            .filter(|(id, _)| id != "0" && id.chars().all(|c| c.is_ascii_digit()))
+            .collect();
+        dirs.sort();
+        dirs
+    }
+
+    /// Steam accounts that have logged in on this machine
+    pub fn list_steam_users() -> Vec<SteamUser> {
+        Self::steam_user_dirs()
+            .into_iter()
+            .map(|(id, dir)| SteamUser {
+                persona_name: fs::read_to_string(dir.join("config").join("localconfig.vdf"))
+                    .ok()
+                    .and_then(|content| persona_name_from_localconfig(&content)),
+                id,
+            })
+            .collect()
+    }
+
+    /// userdata/<id> of the account chosen in the app config, or else the one
+    /// with the most recently modified localconfig.vdf
+    pub fn active_steam_user_dir() -> Option<PathBuf> {
+        let users = Self::steam_user_dirs();
+        if let Some(chosen) = app_config::get_app_config().steam_user {
+            if let Some((_, dir)) = users.iter().find(|(id, _)| *id == chosen) {
+                return Some(dir.clone());
+            }
+        }
+
+        users
+            .into_iter()
+            .filter_map(|(_, dir)| {
+                let localconfig = dir.join("config").join("localconfig.vdf");
+                let modified = fs::metadata(localconfig).and_then(|m| m.modified()).ok()?;
+                Some((modified, dir))
+            })
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, dir)| dir)
+    }
+
+    /// Steam app IDs ordered by LastPlayed (most recent first), read from the
+    /// active user's localconfig.vdf
+    pub fn get_recent_appids(limit: u32) -> Vec<u32> {
+        Self::active_steam_user_dir()
+            .and_then(|dir| fs::read_to_string(dir.join("config").join("localconfig.vdf")).ok())
+            .map(|content| recent_appids_from_localconfig(&content, limit))
+            .unwrap_or_default()
+    }
+
+    /// Proton prefix directory (steamapps/compatdata/<appid>) for a Steam game
+    pub fn find_compatdata_path(steam_appid: u32) -> Option<PathBuf> {
+        Self::find_steamapps_subdir("compatdata", steam_appid)
+    }
+
+    /// Steam's per-game shader cache (steamapps/shadercache/<appid>)
+    pub fn find_shadercache_path(steam_appid: u32) -> Option<PathBuf> {
+        Self::find_steamapps_subdir("shadercache", steam_appid)
+    }
+
+    /// Install dir of a Steam game, from its appmanifest
+    pub fn find_steam_install_path(steam_appid: u32) -> Option<PathBuf> {
+        let libraries = Self::get_steam_library_paths();
+        libraries.iter().find_map(|lib| {
+            let steamapps = lib.join("steamapps");
+            let manifest = steamapps.join(format!("appmanifest_{}.acf", steam_appid));
+            Self::parse_acf_file(&manifest, &steamapps, &libraries)?.install_path
+        })
+    }
+
+    /// First file named `exe_name` (case-insensitive) under `install_dir`
+    pub fn find_executable(install_dir: &Path, exe_name: &str) -> Option<PathBuf> {
+        WalkDir::new(install_dir)
+            .max_depth(5)
+            .into_iter()
+            .flatten()
+            .find(|e| {
+                e.file_type().is_file()
+                    && e.file_name().to_string_lossy().eq_ignore_ascii_case(exe_name)
+            })
+            .map(|e| e.into_path())
+    }
+
+    /// Executable names that look like the game itself (Windows .exe files and
+    /// native ELF binaries), largest first since the game usually outweighs its
+    /// launcher. Helpers like uninstallers and crash reporters are left out.
+    pub fn find_game_executables(install_dir: &Path) -> Vec<String> {
+        let mut found: Vec<(u64, String)> = WalkDir::new(install_dir)
+            .max_depth(4)
+            .into_iter()
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                let is_exe = name.to_lowercase().ends_with(".exe");
+                if is_helper_executable(&name) || !(is_exe || is_native_executable(e.path())) {
+                    return None;
+                }
+                Some((e.metadata().ok()?.len(), name))
+            })
+            .collect();
+
+        found.sort_by(|(a_size, a), (b_size, b)| b_size.cmp(a_size).then_with(|| a.cmp(b)));
+        let mut seen = HashSet::new();
+        found
+            .into_iter()
+            .map(|(_, name)| name)
+            .filter(|name| seen.insert(name.to_lowercase()))
+            .collect()
+    }
+
+    fn find_steamapps_subdir(subdir: &str, steam_appid: u32) -> Option<PathBuf> {
+        Self::get_steam_library_paths()
+            .into_iter()
+            .map(|lib| lib.join("steamapps").join(subdir).join(steam_appid.to_string()))
+            .find(|p| p.exists())
+    }
+
+    pub fn get_steam_library_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
         let mut seen_canonicalized: HashSet<PathBuf> = HashSet::new();
 
@@ -158,10 +383,54 @@ impl GameDetector {
             }
         }
 
+        // Steam Deck: the default library when running as another user, and SD
+        // cards that libraryfolders.vdf doesn't list (not yet mounted when Steam wrote it)
+        if crate::system::is_steam_deck() {
+            let mut deck_paths = vec![PathBuf::from(STEAM_DECK_LIBRARY)];
+            deck_paths.extend(Self::find_media_libraries(Path::new("/run/media")));
+            for lib_path in deck_paths {
+                if let Ok(canonical) = fs::canonicalize(&lib_path) {
+                    if seen_canonicalized.insert(canonical.clone()) {
+                        paths.push(canonical);
+                    }
+                }
+            }
+        }
+
         paths
     }
 
-    fn parse_acf_file(path: &PathBuf, steamapps: &PathBuf) -> Option<Game> {
+    /// Libraries on removable media: `<root>/<label>` (older SteamOS) or
+    /// `<root>/<user>/<label>`, recognised by their steamapps folder
+    fn find_media_libraries(root: &Path) -> Vec<PathBuf> {
+        let subdirs = |dir: &Path| -> Vec<PathBuf> {
+            let mut dirs: Vec<PathBuf> = fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect();
+            dirs.sort();
+            dirs
+        };
+
+        let mut libraries = Vec::new();
+        for mount in subdirs(root) {
+            if mount.join("steamapps").is_dir() {
+                libraries.push(mount);
+                continue;
+            }
+            libraries.extend(
+                subdirs(&mount)
+                    .into_iter()
+                    .filter(|p| p.join("steamapps").is_dir()),
+            );
+        }
+        libraries
+    }
+
+    fn parse_acf_file(path: &PathBuf, steamapps: &PathBuf, libraries: &[PathBuf]) -> Option<Game> {
         let content = fs::read_to_string(path).ok()?;
 
         let appid_regex = Regex::new(r#""appid"\s+"(\d+)""#).ok()?;
@@ -172,14 +441,14 @@ impl GameDetector {
         let name = name_regex.captures(&content)?.get(1)?.as_str().to_string();
         let installdir = installdir_regex.captures(&content)?.get(1)?.as_str();
 
-        let install_path = steamapps.join("common").join(installdir);
+        let install_path = Self::resolve_install_path(steamapps, installdir, libraries);
 
         Some(Game {
             id: appid.clone(),
             name,
             executable: None,
             source: GameSource::Steam,
-            install_path: Some(install_path),
+            install_path,
             icon_url: Some(format!(
                 "https://steamcdn-a.akamaihd.net/steam/apps/{}/library_600x900.jpg",
                 appid
@@ -187,6 +456,24 @@ impl GameDetector {
         })
     }
 
+    /// Locate a game's install dir, checking the manifest's own library first.
+    /// Falls back to other libraries in case the game folder was moved by hand.
+    fn resolve_install_path(
+        steamapps: &Path,
+        installdir: &str,
+        libraries: &[PathBuf],
+    ) -> Option<PathBuf> {
+        let primary = steamapps.join("common").join(installdir);
+        if primary.exists() {
+            return Some(primary);
+        }
+
+        libraries
+            .iter()
+            .map(|lib| lib.join("steamapps").join("common").join(installdir))
+            .find(|p| p.exists())
+    }
+
     pub fn detect_lutris_games() -> Vec<Game> {
         let mut games = Vec::new();
 
@@ -321,6 +608,11 @@ impl GameDetector {
                             if let Some(name) = entry.get("title").and_then(|v| v.as_str()) {
                                 let prefix =
                                     entry.get("prefix").and_then(|v| v.as_str()).unwrap_or("");
+                                let executable = entry
+                                    .get("path")
+                                    .and_then(|v| v.as_str())
+                                    .filter(|p| !p.is_empty())
+                                    .map(PathBuf::from);
                                 let id =
                                     format!("faugus-{}", name.to_lowercase().replace(' ', "-"));
 
@@ -329,7 +621,7 @@ impl GameDetector {
                                     games.push(Game {
                                         id,
                                         name: name.to_string(),
-                                        executable: None,
+                                        executable,
                                         source: GameSource::Faugus,
                                         install_path: if prefix.is_empty() {
                                             None
@@ -401,13 +693,328 @@ impl GameDetector {
             .unwrap_or("unknown")
             .to_string();
 
+        let executable = Regex::new(r"(?m)^Exec=(.+)$")
+            .ok()?
+            .captures(content)
+            .and_then(|c| Self::parse_exec_executable(c.get(1)?.as_str()));
+
         Some(Game {
             id,
             name,
-            executable: None,
+            executable,
             source: GameSource::Faugus,
             install_path: None,
             icon_url: None,
         })
     }
+
+    /// Game executable from a desktop Exec line, e.g.
+    /// `env WINEPREFIX=/p umu-run "/games/My Game/game.exe" %u`.
+    /// Prefers a Windows executable; otherwise takes the first argument after
+    /// the umu-run/faugus launcher.
+    fn parse_exec_executable(exec: &str) -> Option<PathBuf> {
+        let args: Vec<String> = split_exec_line(exec)
+            .into_iter()
+            // Field codes (%f, %u, ...) and env prefixes aren't part of the command
+            .filter(|a| !(a.len() == 2 && a.starts_with('%')))
+            .filter(|a| a != "env" && !Self::is_env_assignment(a))
+            .collect();
+
+        let windows_exe = args.iter().find(|a| {
+            let lower = a.to_lowercase();
+            [".exe", ".bat", ".msi", ".lnk"]
+                .iter()
+                .any(|ext| lower.ends_with(ext))
+        });
+        if let Some(exe) = windows_exe {
+            return Some(PathBuf::from(exe));
+        }
+
+        let launcher = args.iter().position(|a| {
+            let cmd = a.rsplit('/').next().unwrap_or(a);
+            cmd == "umu-run" || cmd.starts_with("faugus-")
+        })?;
+        args[launcher + 1..]
+            .iter()
+            .find(|a| !a.starts_with('-'))
+            .map(PathBuf::from)
+    }
+
+    fn is_env_assignment(arg: &str) -> bool {
+        arg.split_once('=')
+            .map(|(key, _)| {
+                !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// IMAGE_FILE_MACHINE_* from the COFF header of a PE image
+fn pe_bitness(header: &[u8]) -> Option<&'static str> {
+    if !header.starts_with(b"MZ") {
+        return None;
+    }
+    let pe_offset = u32::from_le_bytes(header.get(0x3c..0x40)?.try_into().ok()?) as usize;
+    if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+        return None;
+    }
+    match u16::from_le_bytes(header.get(pe_offset + 4..pe_offset + 6)?.try_into().ok()?) {
+        0x014c => Some("32"),          // i386
+        0x8664 | 0xaa64 => Some("64"), // x86-64, ARM64
+        _ => None,
+    }
+}
+
+/// "32" or "64" for a Windows executable; None when it isn't a PE image
+pub fn detect_exe_bitness(path: &Path) -> Option<String> {
+    use std::io::Read;
+    let mut header = Vec::new();
+    fs::File::open(path)
+        .ok()?
+        .take(4096)
+        .read_to_end(&mut header)
+        .ok()?;
+    pe_bitness(&header).map(String::from)
+}
+
+/// Steam's mapping names the tool outright; without one, the prefix's version
+/// file (e.g. "1718111356 experimental-9.0-20240611") shows what it last ran
+fn uses_proton_experimental(tool: Option<&str>, prefix_version: Option<&str>) -> bool {
+    match tool {
+        Some(tool) => tool == "proton_experimental",
+        None => prefix_version.is_some_and(|v| v.contains("experimental")),
+    }
+}
+
+fn experimental_branch(version: &str, beta_key: Option<&str>) -> String {
+    if version.contains("bleeding-edge") || version.contains("bleeding_edge") {
+        return "bleeding-edge".to_string();
+    }
+    beta_key
+        .filter(|key| !key.is_empty() && *key != "public")
+        .unwrap_or("default")
+        .to_string()
+}
+
+fn is_helper_executable(name: &str) -> bool {
+    let name = name.to_lowercase();
+    HELPER_EXECUTABLE_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+/// An ELF binary with the execute bit set (shared libraries don't have it)
+fn is_native_executable(path: &Path) -> bool {
+    use std::io::Read;
+    use std::os::unix::fs::PermissionsExt;
+
+    let executable = fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o111 != 0);
+    let mut magic = [0u8; 4];
+    executable
+        && fs::File::open(path)
+            .and_then(|mut f| f.read_exact(&mut magic))
+            .is_ok()
+        && &magic == b"\x7fELF"
+}
+
+fn persona_name_from_localconfig(content: &str) -> Option<String> {
+    vdf::parse(content)
+        .get_path(&["UserLocalConfigStore", "friends", "PersonaName"])
+        .and_then(|v| v.as_str())
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+}
+
+/// App IDs with a LastPlayed timestamp, most recent first
+fn recent_appids_from_localconfig(content: &str, limit: u32) -> Vec<u32> {
+    let root = vdf::parse(content);
+    let Some(vdf::VdfValue::Section(apps)) = root.get_path(&[
+        "UserLocalConfigStore",
+        "Software",
+        "Valve",
+        "Steam",
+        "apps",
+    ]) else {
+        return Vec::new();
+    };
+
+    let mut played: Vec<(u64, u32)> = apps
+        .iter()
+        .filter_map(|(appid, app)| {
+            let last_played = app.get("LastPlayed")?.as_str()?.parse::<u64>().ok()?;
+            Some((last_played, appid.parse::<u32>().ok()?))
+        })
+        .filter(|(last_played, _)| *last_played > 0)
+        .collect();
+
+    played.sort_by_key(|(last_played, _)| std::cmp::Reverse(*last_played));
+    played
+        .into_iter()
+        .take(limit as usize)
+        .map(|(_, appid)| appid)
+        .collect()
+}
+
+/// Split a desktop entry Exec value into arguments, honoring double quotes
+/// and backslash escapes per the Desktop Entry spec
+fn split_exec_line(exec: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                    has_arg = true;
+                }
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(current);
+    }
+
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pe_bitness() {
+        let pe = |machine: u16| {
+            let mut header = vec![0u8; 0x86];
+            header[..2].copy_from_slice(b"MZ");
+            header[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+            header[0x80..0x84].copy_from_slice(b"PE\0\0");
+            header[0x84..0x86].copy_from_slice(&machine.to_le_bytes());
+            header
+        };
+        assert_eq!(pe_bitness(&pe(0x014c)), Some("32"));
+        assert_eq!(pe_bitness(&pe(0x8664)), Some("64"));
+        // Truncated header or an ELF binary
+        assert_eq!(pe_bitness(&pe(0x8664)[..0x82]), None);
+        assert_eq!(pe_bitness(b"\x7fELF"), None);
+    }
+
+    #[test]
+    fn test_proton_experimental_branch() {
+        let stable = "1718111356 proton-9.0-2";
+        let experimental = "1718111356 experimental-9.0-20240611";
+        assert!(uses_proton_experimental(Some("proton_experimental"), None));
+        assert!(!uses_proton_experimental(Some("GE-Proton9-7"), Some(experimental)));
+        assert!(uses_proton_experimental(None, Some(experimental)));
+        assert!(!uses_proton_experimental(None, Some(stable)));
+
+        let bleeding = "1718899999 experimental_bleeding-edge-9.0-12345-20240620";
+        assert_eq!(experimental_branch(bleeding, None), "bleeding-edge");
+        assert_eq!(experimental_branch(experimental, Some("bleeding-edge")), "bleeding-edge");
+        assert_eq!(experimental_branch(experimental, Some("")), "default");
+        assert_eq!(experimental_branch(experimental, None), "default");
+    }
+
+    #[test]
+    fn test_find_game_executables() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join("unvcpfl-test-executables");
+        fs::remove_dir_all(&root).ok();
+        fs::create_dir_all(root.join("bin/x64")).unwrap();
+        fs::write(root.join("bin/x64/witcher3.exe"), vec![0u8; 64]).unwrap();
+        fs::write(root.join("Launcher.exe"), vec![0u8; 16]).unwrap();
+        fs::write(root.join("unins000.exe"), vec![0u8; 128]).unwrap();
+        fs::write(root.join("UnityCrashHandler64.exe"), vec![0u8; 128]).unwrap();
+        fs::write(root.join("game.x86_64"), b"\x7fELF native").unwrap();
+        fs::set_permissions(root.join("game.x86_64"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(root.join("start.sh"), b"#!/bin/sh").unwrap();
+        fs::set_permissions(root.join("start.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+
+        let executables = GameDetector::find_game_executables(&root);
+        fs::remove_dir_all(&root).ok();
+        assert_eq!(executables, vec!["witcher3.exe", "Launcher.exe", "game.x86_64"]);
+    }
+
+    #[test]
+    fn test_find_media_libraries() {
+        let root = std::env::temp_dir().join("unvcpfl-test-media");
+        fs::remove_dir_all(&root).ok();
+        for dir in ["mmcblk0p1/steamapps", "deck/SDCARD/steamapps", "deck/USB"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+
+        let libraries = GameDetector::find_media_libraries(&root);
+        fs::remove_dir_all(&root).ok();
+        assert_eq!(
+            libraries,
+            vec![root.join("deck").join("SDCARD"), root.join("mmcblk0p1")]
+        );
+    }
+
+    #[test]
+    fn test_recent_appids_ordered_by_last_played() {
+        let content = r#"
+"UserLocalConfigStore"
+{
+	"friends"
+	{
+		"PersonaName"		"Gordon"
+	}
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"apps"
+				{
+					"620" { "LastPlayed" "1700000000" }
+					"1091500" { "LastPlayed" "1710000000" "Playtime" "42" }
+					"730" { "Playtime" "5" }
+					"570" { "LastPlayed" "1690000000" }
+				}
+			}
+		}
+	}
+}
+"#;
+        assert_eq!(recent_appids_from_localconfig(content, 2), vec![1091500, 620]);
+        assert_eq!(persona_name_from_localconfig(content).as_deref(), Some("Gordon"));
+    }
+
+    #[test]
+    fn test_exec_line_quoted_exe_with_env_prefix() {
+        let exec = r#"env WINEPREFIX="/home/u/Faugus/my game" GAMEID=0 umu-run "/home/u/Games/My Game/Game.exe" %U"#;
+        assert_eq!(
+            GameDetector::parse_exec_executable(exec),
+            Some(PathBuf::from("/home/u/Games/My Game/Game.exe"))
+        );
+    }
+
+    #[test]
+    fn test_exec_line_argument_after_launcher() {
+        let exec = r"/usr/bin/faugus-run --fullscreen /home/u/Games/launcher\ script.sh";
+        assert_eq!(
+            GameDetector::parse_exec_executable(exec),
+            Some(PathBuf::from("/home/u/Games/launcher script.sh"))
+        );
+    }
 }
@@ -0,0 +1,281 @@
+//! Time-of-day GPU settings, e.g. a quieter power limit and fan curve at night
+//!
+//! Independent of game launches: while enabled, a background task applies the
+//! entry whose time range contains the current local time and restores the
+//! default (stock power limit, desktop LACT profile) outside all ranges.
+
+use crate::lact::{current_lact_profile, set_lact_profile};
+use crate::nvidia::SharedGpuState;
+use chrono::{Local, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// Re-check at least this often, so suspend or clock changes are picked up
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// GPU settings for a local time range; `end` before `start` wraps past midnight
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct GpuScheduleEntry {
+    pub start: String, // "HH:MM"
+    pub end: String,   // "HH:MM", exclusive
+    #[serde(default)]
+    pub power_limit_watts: Option<u32>,
+    #[serde(default)]
+    pub lact_profile: Option<String>, // LACT profiles carry the fan curve
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GpuSchedule {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub entries: Vec<GpuScheduleEntry>,
+    /// LACT profile outside all ranges (default: the one active when the schedule started)
+    #[serde(default)]
+    pub default_lact_profile: Option<String>,
+}
+
+struct ScheduleTask {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+static GPU_SCHEDULE: Mutex<Option<ScheduleTask>> = Mutex::new(None);
+
+/// Why the schedule last failed to start or apply; cleared by a clean apply
+static LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+fn set_last_error(error: Option<String>) {
+    if let Ok(mut last) = LAST_ERROR.lock() {
+        *last = error;
+    }
+}
+
+/// The background task has no caller to return errors to, so they are kept here
+pub fn get_gpu_schedule_error() -> Option<String> {
+    LAST_ERROR.lock().ok().and_then(|e| e.clone())
+}
+
+fn schedule_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("unvcpfl")
+        .join("gpu_schedule.toml")
+}
+
+pub fn get_gpu_schedule() -> GpuSchedule {
+    fs::read_to_string(schedule_path())
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_gpu_schedule(schedule: &GpuSchedule) -> Result<(), String> {
+    let path = schedule_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let content = toml::to_string_pretty(schedule)
+        .map_err(|e| format!("Failed to serialize GPU schedule: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write GPU schedule: {}", e))
+}
+
+/// "HH:MM" to seconds since midnight
+fn parse_time(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60).then_some((hours * 60 + minutes) * 60)
+}
+
+fn validate(entries: &[GpuScheduleEntry]) -> Result<(), String> {
+    for entry in entries {
+        let start = parse_time(&entry.start)
+            .ok_or_else(|| format!("Invalid start time '{}' (expected HH:MM)", entry.start))?;
+        let end = parse_time(&entry.end)
+            .ok_or_else(|| format!("Invalid end time '{}' (expected HH:MM)", entry.end))?;
+        if start == end {
+            return Err(format!("Time range {}-{} is empty", entry.start, entry.end));
+        }
+        if entry.power_limit_watts.is_none() && entry.lact_profile.is_none() {
+            return Err(format!(
+                "Time range {}-{} changes nothing",
+                entry.start, entry.end
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Index of the first entry whose range contains `now` (seconds since midnight)
+fn active_entry(entries: &[GpuScheduleEntry], now: u32) -> Option<usize> {
+    entries.iter().position(|entry| {
+        match (parse_time(&entry.start), parse_time(&entry.end)) {
+            (Some(start), Some(end)) if start < end => (start..end).contains(&now),
+            (Some(start), Some(end)) => now >= start || now < end,
+            _ => false,
+        }
+    })
+}
+
+/// Seconds from `now` until the next range start or end
+fn seconds_until_boundary(entries: &[GpuScheduleEntry], now: u32) -> u32 {
+    entries
+        .iter()
+        .flat_map(|e| [parse_time(&e.start), parse_time(&e.end)])
+        .flatten()
+        .map(|boundary| match (boundary + SECONDS_PER_DAY - now) % SECONDS_PER_DAY {
+            0 => SECONDS_PER_DAY,
+            wait => wait,
+        })
+        .min()
+        .unwrap_or(SECONDS_PER_DAY)
+}
+
+/// Local time as seconds since midnight
+fn local_seconds() -> u32 {
+    Local::now().num_seconds_from_midnight()
+}
+
+/// Switch from the `previous` entry to `entry` (None = outside all ranges). The
+/// power limit is only reset when leaving an entry that set one, so LACT-only
+/// schedules never need root and a limit set by hand is left alone.
+async fn apply(
+    gpu_state: &SharedGpuState,
+    previous: Option<&GpuScheduleEntry>,
+    entry: Option<&GpuScheduleEntry>,
+    default_lact: Option<&str>,
+) {
+    let watts = entry.and_then(|e| e.power_limit_watts);
+    let had_limit = previous.is_some_and(|e| e.power_limit_watts.is_some());
+    let mut error = gpu_state.read().await.monitor.as_ref().and_then(|monitor| {
+        let result = match watts {
+            Some(watts) => monitor.set_power_limit(watts),
+            None if had_limit => monitor.reset_power_limit(),
+            None => Ok(()),
+        };
+        result.err()
+    });
+    if let Some(profile) = entry.and_then(|e| e.lact_profile.as_deref()).or(default_lact) {
+        // lact is a subprocess, don't block an async worker on it
+        let profile = profile.to_string();
+        let result = tokio::task::spawn_blocking(move || set_lact_profile(&profile))
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+        if let Err(e) = result {
+            error.get_or_insert(e);
+        }
+    }
+    set_last_error(error);
+}
+
+async fn run_schedule(gpu_state: SharedGpuState, schedule: GpuSchedule, mut stop: oneshot::Receiver<()>) {
+    let default_lact = match schedule.default_lact_profile.clone() {
+        Some(profile) => Some(profile),
+        None => tokio::task::spawn_blocking(current_lact_profile).await.ok().flatten(),
+    };
+    // None until the first check, so startup always applies
+    let mut applied: Option<Option<usize>> = None;
+
+    loop {
+        let now = local_seconds();
+        let wanted = active_entry(&schedule.entries, now);
+        if applied != Some(wanted) {
+            let previous = applied.flatten().map(|i| &schedule.entries[i]);
+            let entry = wanted.map(|i| &schedule.entries[i]);
+            apply(&gpu_state, previous, entry, default_lact.as_deref()).await;
+            applied = Some(wanted);
+        }
+
+        let wait = Duration::from_secs(seconds_until_boundary(&schedule.entries, now).into())
+            .min(MAX_CHECK_INTERVAL);
+        tokio::select! {
+            _ = &mut stop => break,
+            _ = tokio::time::sleep(wait) => {}
+        }
+    }
+
+    if let Some(previous) = applied.flatten() {
+        apply(&gpu_state, Some(&schedule.entries[previous]), None, default_lact.as_deref()).await;
+    }
+}
+
+/// Start applying the saved schedule; does nothing when it is disabled
+pub fn start_gpu_schedule(gpu_state: SharedGpuState) -> Result<(), String> {
+    let schedule = get_gpu_schedule();
+    if !schedule.enabled || schedule.entries.is_empty() {
+        return Ok(());
+    }
+    if let Err(e) = validate(&schedule.entries) {
+        set_last_error(Some(e.clone()));
+        return Err(e);
+    }
+
+    let mut running = GPU_SCHEDULE.lock().map_err(|e| e.to_string())?;
+    if running.is_some() {
+        return Err("GPU schedule is already running".to_string());
+    }
+    let (stop, stop_rx) = oneshot::channel();
+    let task = tokio::spawn(run_schedule(gpu_state, schedule, stop_rx));
+    *running = Some(ScheduleTask { stop, task });
+    Ok(())
+}
+
+/// Stop the schedule and restore the default settings
+pub async fn stop_gpu_schedule() -> Result<(), String> {
+    let running = GPU_SCHEDULE.lock().map_err(|e| e.to_string())?.take();
+    if let Some(running) = running {
+        running.stop.send(()).ok();
+        running.task.await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Save the schedule and restart the background task with it
+pub async fn set_gpu_schedule(gpu_state: SharedGpuState, schedule: GpuSchedule) -> Result<(), String> {
+    validate(&schedule.entries)?;
+    save_gpu_schedule(&schedule)?;
+    stop_gpu_schedule().await?;
+    set_last_error(None);
+    start_gpu_schedule(gpu_state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(start: &str, end: &str) -> GpuScheduleEntry {
+        GpuScheduleEntry {
+            start: start.to_string(),
+            end: end.to_string(),
+            power_limit_watts: Some(200),
+            lact_profile: None,
+        }
+    }
+
+    #[test]
+    fn test_schedule_ranges() {
+        let entries = vec![entry("22:30", "07:00"), entry("12:00", "13:00")];
+        let at = |time: &str| parse_time(time).unwrap();
+
+        // Night range wraps past midnight
+        assert_eq!(active_entry(&entries, at("23:00")), Some(0));
+        assert_eq!(active_entry(&entries, at("03:15")), Some(0));
+        assert_eq!(active_entry(&entries, at("07:00")), None);
+        assert_eq!(active_entry(&entries, at("12:30")), Some(1));
+
+        assert_eq!(seconds_until_boundary(&entries, at("21:30")), 3600);
+        assert_eq!(seconds_until_boundary(&entries, at("06:00")), 3600);
+        assert_eq!(seconds_until_boundary(&[], 0), SECONDS_PER_DAY);
+
+        assert!(parse_time("24:00").is_none());
+        assert!(validate(&[entry("08:00", "08:00")]).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+//! The DXVK / VKD3D environment a game really gets under Proton
+//!
+//! Proton's launch script sets some DXVK and VKD3D variables itself unless
+//! they are already in the environment. Layering the profile over those
+//! defaults shows which profile values change anything and which are redundant.
+
+use crate::games::GameDetector;
+use crate::profiles::{GameProfile, ProfileManager};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Used when the Proton build can't be found or its script can't be read
+const FALLBACK_PROTON_DEFAULTS: &[(&str, &str)] = &[
+    ("DXVK_LOG_LEVEL", "none"),
+    ("VKD3D_DEBUG", "none"),
+    ("VKD3D_SHADER_DEBUG", "none"),
+];
+
+/// Where an effective value comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum EnvOrigin {
+    Proton,          // Proton default, the profile doesn't set it
+    Profile,         // Only the profile sets it
+    OverridesProton, // The profile replaces a different Proton default
+    SameAsProton,    // The profile sets what Proton would set anyway
+    ForcedByProton,  // Proton assigns it unconditionally, the profile value is ignored
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveEnvVar {
+    pub value: String,
+    pub proton_default: Option<String>,
+    pub origin: EnvOrigin,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveGraphicsEnv {
+    pub proton: Option<String>, // Build the defaults were read from; None = built-in list
+    pub vars: HashMap<String, EffectiveEnvVar>,
+}
+
+fn is_graphics_var(key: &str) -> bool {
+    key.starts_with("DXVK_") || key.starts_with("VKD3D_")
+}
+
+/// A DXVK/VKD3D value Proton's launch script sets
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProtonDefault {
+    value: String,
+    forced: bool, // `self.env["K"] = "V"`: replaces whatever the environment had
+}
+
+/// DXVK/VKD3D values from Proton's launch script. `self.env.setdefault("K", "V")`
+/// only fills in a variable the environment lacks, so the first one wins;
+/// `self.env["K"] = "V"` always replaces it. Assignments inside the
+/// `if "PROTON_LOG" ...` block are skipped: they only apply with logging on,
+/// and come before the regular defaults in Proton's script.
+fn parse_proton_defaults(script: &str) -> HashMap<String, ProtonDefault> {
+    let Ok(setdefault) = Regex::new(r#"env\.setdefault\(\s*"(\w+)"\s*,\s*"([^"]*)""#) else {
+        return HashMap::new();
+    };
+    let Ok(assign) = Regex::new(r#"env\[\s*"(\w+)"\s*\]\s*=\s*"([^"]*)""#) else {
+        return HashMap::new();
+    };
+
+    let mut defaults: HashMap<String, ProtonDefault> = HashMap::new();
+    let mut log_block_indent: Option<usize> = None;
+    for line in script.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        match log_block_indent {
+            Some(block) if indent > block => continue,
+            Some(_) => log_block_indent = None,
+            None => {}
+        }
+        if trimmed.starts_with("if ") && trimmed.contains("\"PROTON_LOG\"") {
+            log_block_indent = Some(indent);
+            continue;
+        }
+
+        if let Some(cap) = assign.captures(trimmed) {
+            if is_graphics_var(&cap[1]) {
+                let value = ProtonDefault {
+                    value: cap[2].to_string(),
+                    forced: true,
+                };
+                defaults.insert(cap[1].to_string(), value);
+            }
+        } else if let Some(cap) = setdefault.captures(trimmed) {
+            if is_graphics_var(&cap[1]) {
+                defaults
+                    .entry(cap[1].to_string())
+                    .or_insert_with(|| ProtonDefault {
+                        value: cap[2].to_string(),
+                        forced: false,
+                    });
+            }
+        }
+    }
+    defaults
+}
+
+/// "Proton 9.0" and "proton_9", "Proton - Experimental" and "proton_experimental"
+fn normalize_tool_name(name: &str) -> String {
+    name.replace(".0", "")
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Install directory of a compatibility tool: compatibilitytools.d/<name> for
+/// custom builds, steamapps/common/Proton* for Valve's
+fn find_proton_install(tool_name: &str) -> Option<PathBuf> {
+    let libraries = GameDetector::get_steam_library_paths();
+    let custom = libraries
+        .iter()
+        .map(|lib| lib.join("compatibilitytools.d").join(tool_name))
+        .find(|p| p.join("proton").exists());
+    if custom.is_some() {
+        return custom;
+    }
+
+    let wanted = normalize_tool_name(tool_name);
+    libraries
+        .iter()
+        .flat_map(|lib| {
+            fs::read_dir(lib.join("steamapps").join("common"))
+                .into_iter()
+                .flatten()
+        })
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| {
+            p.join("proton").exists()
+                && p.file_name()
+                    .map(|n| normalize_tool_name(&n.to_string_lossy()) == wanted)
+                    .unwrap_or(false)
+        })
+}
+
+/// Profile vars layered over the Proton defaults
+fn layer_env(
+    proton: &HashMap<String, ProtonDefault>,
+    profile: &HashMap<String, String>,
+) -> HashMap<String, EffectiveEnvVar> {
+    let mut vars: HashMap<String, EffectiveEnvVar> = proton
+        .iter()
+        .map(|(key, default)| {
+            let var = EffectiveEnvVar {
+                value: default.value.clone(),
+                proton_default: Some(default.value.clone()),
+                origin: EnvOrigin::Proton,
+            };
+            (key.clone(), var)
+        })
+        .collect();
+
+    for (key, value) in profile.iter().filter(|(key, _)| is_graphics_var(key)) {
+        let default = proton.get(key);
+        let (value, origin) = match default {
+            None => (value.clone(), EnvOrigin::Profile),
+            Some(default) if default.value == *value => (value.clone(), EnvOrigin::SameAsProton),
+            Some(default) if default.forced => (default.value.clone(), EnvOrigin::ForcedByProton),
+            Some(_) => (value.clone(), EnvOrigin::OverridesProton),
+        };
+        let var = EffectiveEnvVar {
+            value,
+            proton_default: default.map(|d| d.value.clone()),
+            origin,
+        };
+        vars.insert(key.clone(), var);
+    }
+    vars
+}
+
+/// Effective DXVK/VKD3D environment for a profile. The Proton build is the
+/// one Steam forces for `steam_appid`, else the profile's Proton version.
+pub fn compute_effective_graphics_env(
+    manager: &ProfileManager,
+    profile: &GameProfile,
+    steam_appid: Option<u32>,
+) -> EffectiveGraphicsEnv {
+    let tool = steam_appid
+        .or(profile.steam_appid)
+        .and_then(GameDetector::get_game_proton_override)
+        .or_else(|| profile.proton.version.clone());
+    let install = tool.as_deref().and_then(find_proton_install);
+    let script_defaults = install
+        .as_ref()
+        .and_then(|dir| fs::read_to_string(dir.join("proton")).ok())
+        .map(|script| parse_proton_defaults(&script));
+
+    let (proton, defaults) = match script_defaults {
+        Some(defaults) => (
+            install.and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string())),
+            defaults,
+        ),
+        None => (
+            None,
+            FALLBACK_PROTON_DEFAULTS
+                .iter()
+                .map(|(k, v)| {
+                    let default = ProtonDefault {
+                        value: v.to_string(),
+                        forced: false,
+                    };
+                    (k.to_string(), default)
+                })
+                .collect(),
+        ),
+    };
+
+    EffectiveGraphicsEnv {
+        proton,
+        vars: layer_env(&defaults, &manager.build_env_vars(profile)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer_over_proton_defaults() {
+        let script = r#"
+        if "PROTON_LOG" in self.env and nonzero(self.env["PROTON_LOG"]):
+            self.env.setdefault("WINEDEBUG", "+timestamp,+pid")
+            self.env.setdefault("DXVK_LOG_LEVEL", "info")
+            self.env.setdefault("VKD3D_DEBUG", "warn")
+
+        #for performance, logging is disabled by default
+        self.env.setdefault("WINEDEBUG", "-all")
+        self.env.setdefault("DXVK_LOG_LEVEL", "none")
+        self.env.setdefault("VKD3D_DEBUG", "none")
+        self.env["VKD3D_SHADER_DEBUG"] = "none"
+        "#;
+        let proton = parse_proton_defaults(script);
+        assert_eq!(proton.len(), 3);
+        assert_eq!(proton["DXVK_LOG_LEVEL"].value, "none");
+        assert!(!proton["VKD3D_DEBUG"].forced);
+        assert!(proton["VKD3D_SHADER_DEBUG"].forced);
+
+        let profile: HashMap<String, String> = [
+            ("DXVK_LOG_LEVEL", "none"),
+            ("VKD3D_DEBUG", "warn"),
+            ("VKD3D_SHADER_DEBUG", "fixme"),
+            ("DXVK_HUD", "fps"),
+            ("PROTON_ENABLE_NVAPI", "1"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let vars = layer_env(&proton, &profile);
+
+        assert_eq!(vars.len(), 4);
+        assert_eq!(vars["DXVK_LOG_LEVEL"].origin, EnvOrigin::SameAsProton);
+        assert_eq!(vars["VKD3D_DEBUG"].origin, EnvOrigin::OverridesProton);
+        assert_eq!(vars["VKD3D_DEBUG"].value, "warn");
+        assert_eq!(vars["VKD3D_SHADER_DEBUG"].origin, EnvOrigin::ForcedByProton);
+        assert_eq!(vars["VKD3D_SHADER_DEBUG"].value, "none");
+        assert_eq!(vars["DXVK_HUD"].origin, EnvOrigin::Profile);
+
+        assert_eq!(
+            normalize_tool_name("Proton 9.0"),
+            normalize_tool_name("proton_9")
+        );
+        assert_eq!(
+            normalize_tool_name("Proton - Experimental"),
+            normalize_tool_name("proton_experimental")
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Idle inhibitor wrappers, shared by desktop game mode, the launch wrappers
+//! the app builds and the CLI helper
+
+use serde_json::Value;
+use std::process::{Command, Stdio};
+
+const INHIBIT_WHO: &str = "unvcpfl";
+// No spaces: launch commands are joined and re-split on whitespace
+const INHIBIT_WHY: &str = "game";
+
+/// How idle gets inhibited on the running desktop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InhibitMethod {
+    /// swayidle ignores logind; Sway's own per-view inhibit_idle instead
+    Sway,
+    /// GNOME ignores logind idle inhibitors too
+    Gnome,
+    /// systemd-inhibit, honored by hypridle, KDE PowerDevil and most others
+    Logind,
+}
+
+impl InhibitMethod {
+    /// From the session's environment, with the same precedence as
+    /// screen::detect_compositor for the desktops that matter here
+    #[allow(dead_code)] // the app maps its detected Compositor instead
+    pub fn detect() -> Self {
+        let desktop = std::env::var("XDG_CURRENT_DESKTOP")
+            .unwrap_or_default()
+            .to_lowercase();
+        if desktop == "gamescope" || std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok() {
+            InhibitMethod::Logind
+        } else if std::env::var("SWAYSOCK").is_ok() {
+            InhibitMethod::Sway
+        } else if std::env::var("GNOME_DESKTOP_SESSION_ID").is_ok() || desktop.contains("gnome") {
+            InhibitMethod::Gnome
+        } else {
+            InhibitMethod::Logind
+        }
+    }
+
+    /// Wrapper that holds an idle inhibitor for the lifetime of the wrapped
+    /// command. Sway goes through the CLI's swaymsg-based wrapper.
+    pub fn wrapper(self) -> Vec<String> {
+        match self {
+            InhibitMethod::Sway => vec!["unvcpfl-cli".to_string(), "sway-inhibit-idle".to_string()],
+            InhibitMethod::Gnome => vec![
+                "gnome-session-inhibit".to_string(),
+                "--inhibit".to_string(),
+                "idle".to_string(),
+                "--reason".to_string(),
+                INHIBIT_WHY.to_string(),
+            ],
+            InhibitMethod::Logind => vec![
+                "systemd-inhibit".to_string(),
+                "--what=idle".to_string(),
+                format!("--who={}", INHIBIT_WHO),
+                format!("--why={}", INHIBIT_WHY),
+                "--mode=block".to_string(),
+            ],
+        }
+    }
+}
+
+/// Con ids of the views in a `swaymsg -t get_tree` reply that carry no user
+/// idle inhibitor, so one set by hand or another tool is left alone
+fn uninhibited_views(node: &Value, ids: &mut Vec<i64>) {
+    let user_inhibitor = node
+        .get("idle_inhibitors")
+        .and_then(|i| i.get("user"))
+        .and_then(Value::as_str);
+    if let (Some("none"), Some(id)) = (user_inhibitor, node.get("id").and_then(Value::as_i64)) {
+        ids.push(id);
+    }
+
+    for key in ["nodes", "floating_nodes"] {
+        for child in node.get(key).and_then(Value::as_array).into_iter().flatten() {
+            uninhibited_views(child, ids);
+        }
+    }
+}
+
+fn set_sway_inhibit(ids: &[i64], mode: &str) -> Result<(), String> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let cmd = ids
+        .iter()
+        .map(|id| format!("[con_id={}] inhibit_idle {}", id, mode))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let status = Command::new("swaymsg")
+        .arg(cmd)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to run swaymsg: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("swaymsg failed to change idle inhibition".to_string())
+    }
+}
+
+/// Inhibit idle on every Sway view that has no user inhibitor yet; returns
+/// the views it inhibited, for sway_release_views
+pub fn sway_inhibit_views() -> Result<Vec<i64>, String> {
+    let output = Command::new("swaymsg")
+        .args(["-t", "get_tree", "-r"])
+        .output()
+        .map_err(|e| format!("Failed to run swaymsg: {}", e))?;
+    let tree: Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse the Sway tree: {}", e))?;
+
+    let mut ids = Vec::new();
+    uninhibited_views(&tree, &mut ids);
+    set_sway_inhibit(&ids, "open")?;
+    Ok(ids)
+}
+
+/// Lift the inhibitors sway_inhibit_views set; closed views are simply gone
+pub fn sway_release_views(ids: &[i64]) -> Result<(), String> {
+    set_sway_inhibit(ids, "none")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uninhibited_views_skip_user_inhibitors() {
+        let tree: Value = serde_json::from_str(
+            r#"{"id": 1, "nodes": [{"id": 4, "nodes": [
+                {"id": 10, "idle_inhibitors": {"user": "none", "application": "none"}, "nodes": []},
+                {"id": 11, "idle_inhibitors": {"user": "fullscreen", "application": "none"}, "nodes": []}
+            ], "floating_nodes": [
+                {"id": 12, "idle_inhibitors": {"user": "none", "application": "enabled"}, "nodes": []}
+            ]}]}"#,
+        )
+        .unwrap();
+
+        let mut ids = Vec::new();
+        uninhibited_views(&tree, &mut ids);
+        assert_eq!(ids, vec![10, 12]);
+    }
+}
@@ -0,0 +1,32 @@
+//! LACT profile switching through `lact cli`, shared by focus follow, the
+//! GPU schedule and the panic restore
+
+use std::process::Command;
+
+/// Name of the active LACT profile
+pub(crate) fn current_lact_profile() -> Option<String> {
+    Command::new("lact")
+        .args(["cli", "profile", "current"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|p| !p.is_empty())
+}
+
+/// Switch LACT to profile `name`
+pub(crate) fn set_lact_profile(name: &str) -> Result<(), String> {
+    let output = Command::new("lact")
+        .args(["cli", "profile", "set", name])
+        .output()
+        .map_err(|e| format!("Failed to run lact: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "LACT could not switch to profile '{}': {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
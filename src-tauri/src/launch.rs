@@ -0,0 +1,426 @@
+//! Launch command building and test-launch support
+//!
+//! Test launches spawn the game with the profile's env and wrappers, check it
+//! stays alive for a short time, then shut it down again.
+
+use crate::games::{Game, GameDetector, GameSource};
+use crate::profiles::{GameProfile, ProfileManager};
+use crate::system;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+/// Default time the game must survive to count as a successful launch
+pub const DEFAULT_TEST_TIMEOUT_SECS: u64 = 10;
+
+/// Keep at most this much stderr in the result
+const MAX_STDERR_BYTES: usize = 16 * 1024;
+
+/// How long to keep collecting stderr after the game is gone; wineserver and
+/// other leftover children can hold the pipe open indefinitely
+const STDERR_GRACE: Duration = Duration::from_secs(2);
+
+/// Proton used by umu-run when the profile doesn't pick one (latest GE-Proton)
+const DEFAULT_UMU_PROTON: &str = "GE-Proton";
+
+/// Build the full shell launch command for a game with a profile applied
+pub fn build_launch_command(manager: &ProfileManager, game: &Game, profile: &GameProfile) -> String {
+    let mut env_vars: Vec<(String, String)> = manager.build_env_vars(profile).into_iter().collect();
+
+    // umu-run needs the game's exe; without one fall back to the launcher URI
+    let umu_exe = match (&game.source, &game.executable) {
+        (GameSource::Faugus, Some(exe)) if profile.proton.use_umu => Some(exe),
+        _ => None,
+    };
+    if umu_exe.is_some() {
+        // Faugus reports the Wine prefix as the install path
+        env_vars.extend(umu_env_vars(profile, game.install_path.as_deref()));
+    }
+    env_vars.sort();
+
+    let env_string = env_vars
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let wrapper_string = manager.build_wrapper_cmd(profile).join(" ");
+
+    // Build launch command based on game source
+    let exec = match game.source {
+        GameSource::Steam => format!("env {} {} steam steam://rungameid/{}", env_string, wrapper_string, game.id),
+        GameSource::Lutris => format!("env {} {} lutris lutris:rungameid/{}", env_string, wrapper_string, game.id),
+        GameSource::Heroic => format!("env {} {} heroic heroic://launch/{}", env_string, wrapper_string, game.id),
+        GameSource::Faugus => match umu_exe {
+            Some(exe) => format!("env {} {} umu-run \"{}\"", env_string, wrapper_string, exe.display()),
+            None => format!("env {} {} xdg-open faugus://{}", env_string, wrapper_string, game.id),
+        },
+        // Without a known exe, open Wine's explorer in the prefix to start it by hand
+        GameSource::Wine => {
+            let prefix = game
+                .install_path
+                .as_ref()
+                .map(|p| format!("WINEPREFIX=\"{}\"", p.display()))
+                .unwrap_or_default();
+            match &game.executable {
+                Some(exe) => format!("env {} {} {} wine \"{}\"", env_string, prefix, wrapper_string, exe.display()),
+                None => format!("env {} {} {} wine explorer", env_string, prefix, wrapper_string),
+            }
+        }
+    };
+
+    exec.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether the Steam client is up; steam:// launches cold-start it otherwise
+pub fn is_steam_running() -> bool {
+    system::list_processes().iter().any(|(_, comm)| comm == "steam")
+}
+
+/// Launch command plus what to expect when running it
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchPreview {
+    pub command: String,
+    pub steam_cold_start: bool, // Steam game but the client isn't running yet
+}
+
+pub fn preview_launch(manager: &ProfileManager, game: &Game, profile: &GameProfile) -> LaunchPreview {
+    LaunchPreview {
+        command: build_launch_command(manager, game, profile),
+        steam_cold_start: game.source == GameSource::Steam && !is_steam_running(),
+    }
+}
+
+/// Launch argv for spawning without a shell
+#[derive(Debug, Clone, Serialize)]
+pub struct GenericLaunchCommand {
+    pub argv: Vec<String>,
+    pub pre_launch: Vec<String>, // Shell commands to run first, e.g. the LACT profile switch
+}
+
+/// Wrapper argv, plus the shell steps that have to run before it: the LACT
+/// switch is a `cmd &&` chain and means nothing as argv tokens
+fn split_wrappers(wrappers: Vec<Vec<String>>) -> (Vec<String>, Vec<String>) {
+    let mut argv = Vec::new();
+    let mut pre_launch = Vec::new();
+    for wrapper in wrappers {
+        match wrapper.first().and_then(|cmd| cmd.strip_suffix("&&")) {
+            Some(step) => pre_launch.push(step.trim().to_string()),
+            None => argv.extend(wrapper),
+        }
+    }
+    (argv, pre_launch)
+}
+
+/// Launch command for an executable no launcher knows about, as argv: env,
+/// wrappers, then `umu-run` (profile.proton.use_umu) or `wine` with the exe.
+/// WINEPREFIX comes from the profile's custom env, else the runner's default.
+pub fn build_generic_launch_command(
+    manager: &ProfileManager,
+    exe_path: &Path,
+    profile: &GameProfile,
+) -> Result<GenericLaunchCommand, String> {
+    if !exe_path.is_file() {
+        return Err(format!("Executable not found: {}", exe_path.display()));
+    }
+
+    let mut env_vars: Vec<(String, String)> = manager.build_env_vars(profile).into_iter().collect();
+    let runner = if profile.proton.use_umu {
+        // A WINEPREFIX in custom_env is already part of env_vars
+        env_vars.extend(umu_env_vars(profile, None));
+        "umu-run"
+    } else {
+        "wine"
+    };
+    env_vars.sort();
+
+    let (wrappers, pre_launch) = split_wrappers(manager.build_wrappers(profile));
+    let mut argv = vec!["env".to_string()];
+    argv.extend(env_vars.iter().map(|(k, v)| format!("{}={}", k, v)));
+    argv.extend(wrappers);
+    argv.push(runner.to_string());
+    argv.push(exe_path.to_string_lossy().to_string());
+    Ok(GenericLaunchCommand { argv, pre_launch })
+}
+
+/// GAMEID / PROTONPATH / WINEPREFIX for umu-run
+fn umu_env_vars(profile: &GameProfile, prefix: Option<&Path>) -> Vec<(String, String)> {
+    // umu applies protonfixes by GAMEID; "0" means no game-specific fixes
+    let game_id = profile
+        .steam_appid
+        .map(|id| format!("umu-{}", id))
+        .unwrap_or_else(|| "0".to_string());
+
+    // Prefer an installed compatibility tool; otherwise let umu resolve the name
+    let version = profile
+        .proton
+        .version
+        .clone()
+        .unwrap_or_else(|| DEFAULT_UMU_PROTON.to_string());
+    let proton_path = dirs::home_dir()
+        .map(|home| home.join(".local/share/Steam/compatibilitytools.d").join(&version))
+        .filter(|p| p.exists())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or(version);
+
+    let mut env = vec![
+        ("GAMEID".to_string(), game_id),
+        ("PROTONPATH".to_string(), proton_path),
+    ];
+    if let Some(prefix) = prefix {
+        env.push(("WINEPREFIX".to_string(), prefix.to_string_lossy().to_string()));
+    }
+    env
+}
+
+fn is_windows_exe(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| ext == "exe" || ext == "bat")
+}
+
+/// Steam doesn't report executables; take the likeliest one in the install dir
+fn steam_executable(game: &Game) -> Option<PathBuf> {
+    let install_dir = game.install_path.as_deref()?;
+    let name = GameDetector::find_game_executables(install_dir).into_iter().next()?;
+    GameDetector::find_executable(install_dir, &name)
+}
+
+/// Runner and its env for an executable, matching the real launch: Steam
+/// games run through Proton (umu-run in the game's compatdata prefix), games
+/// with proton.use_umu through umu-run, other Windows executables through
+/// wine, and native ones directly
+fn runner_for(
+    game: &Game,
+    profile: &GameProfile,
+    exe: &Path,
+) -> (Option<&'static str>, Vec<(String, String)>) {
+    if !is_windows_exe(exe) {
+        return (None, Vec::new());
+    }
+
+    let prefix = match game.source {
+        GameSource::Steam => game
+            .id
+            .parse()
+            .ok()
+            .and_then(GameDetector::find_compatdata_path)
+            .map(|dir| dir.join("pfx")),
+        // Faugus and bare prefixes report the Wine prefix as the install path
+        GameSource::Faugus | GameSource::Wine => game.install_path.clone(),
+        _ => None,
+    };
+
+    if game.source == GameSource::Steam || profile.proton.use_umu {
+        (Some("umu-run"), umu_env_vars(profile, prefix.as_deref()))
+    } else {
+        let env = prefix
+            .map(|p| ("WINEPREFIX".to_string(), p.to_string_lossy().to_string()))
+            .into_iter()
+            .collect();
+        (Some("wine"), env)
+    }
+}
+
+/// Outcome of a test launch
+#[derive(Debug, Clone, Serialize)]
+pub struct TestLaunchResult {
+    pub success: bool,
+    pub exit_code: Option<i32>,   // Set when the game exited before the timeout
+    pub stderr: String,
+    pub message: String,
+}
+
+/// Launch a game with a profile and report whether it survived `timeout_secs`
+pub async fn test_launch(
+    manager: &ProfileManager,
+    game: &Game,
+    profile: &GameProfile,
+    timeout_secs: u64,
+) -> Result<TestLaunchResult, String> {
+    // Launcher URIs (steam://, lutris:) hand off and exit, so there's nothing to watch
+    let executable = game
+        .executable
+        .clone()
+        .or_else(|| match game.source {
+            GameSource::Steam => steam_executable(game),
+            _ => None,
+        })
+        .ok_or_else(|| format!("No executable known for '{}'; cannot test-launch", game.name))?;
+
+    // Skip pre-launch steps: the LACT switch shouldn't change GPU state for a test
+    let (mut argv, _) = split_wrappers(manager.build_wrappers(profile));
+    let (runner, runner_env) = runner_for(game, profile, &executable);
+    argv.extend(runner.map(String::from));
+    argv.push(executable.to_string_lossy().to_string());
+    if let Some(args) = &profile.custom_args {
+        argv.extend(args.split_whitespace().map(String::from));
+    }
+
+    let mut cmd = Command::new(&argv[0]);
+    cmd.args(&argv[1..])
+        .envs(manager.build_env_vars(profile))
+        .envs(runner_env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        // Own process group so wrappers and their children are killed together
+        .process_group(0);
+    if let Some(dir) = &game.install_path {
+        cmd.current_dir(dir);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", argv[0], e))?;
+
+    // Drain stderr concurrently so a chatty game can't block on a full pipe,
+    // keeping only the tail
+    let stderr = Arc::new(Mutex::new(Vec::new()));
+    let mut stderr_pipe = child.stderr.take();
+    let mut stderr_task = tokio::spawn({
+        let stderr = Arc::clone(&stderr);
+        async move {
+            let Some(pipe) = stderr_pipe.as_mut() else {
+                return;
+            };
+            let mut chunk = [0u8; 4096];
+            while let Ok(n) = pipe.read(&mut chunk).await {
+                if n == 0 {
+                    break;
+                }
+                let mut buf = stderr.lock().unwrap_or_else(|e| e.into_inner());
+                buf.extend_from_slice(&chunk[..n]);
+                if buf.len() > MAX_STDERR_BYTES {
+                    let excess = buf.len() - MAX_STDERR_BYTES;
+                    buf.drain(..excess);
+                }
+            }
+        }
+    });
+
+    let waited = tokio::time::timeout(Duration::from_secs(timeout_secs), child.wait()).await;
+
+    let (success, exit_code, message) = match waited {
+        Ok(Ok(status)) => (
+            false,
+            status.code(),
+            format!("Game exited after less than {} seconds ({})", timeout_secs, status),
+        ),
+        Ok(Err(e)) => (false, None, format!("Failed to wait for game: {}", e)),
+        Err(_) => {
+            if let Some(pid) = child.id() {
+                std::process::Command::new("kill")
+                    .args(["-TERM", &format!("-{}", pid)])
+                    .status()
+                    .ok();
+            }
+            child.kill().await.ok();
+            (
+                true,
+                None,
+                format!("Game was still running after {} seconds", timeout_secs),
+            )
+        }
+    };
+
+    if tokio::time::timeout(STDERR_GRACE, &mut stderr_task).await.is_err() {
+        stderr_task.abort();
+    }
+    let stderr = stderr.lock().unwrap_or_else(|e| e.into_inner()).clone();
+
+    Ok(TestLaunchResult {
+        success,
+        exit_code,
+        stderr: String::from_utf8_lossy(&stderr).to_string(),
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_umu_launch_command() {
+        let manager =
+            ProfileManager::with_profiles_dir(std::env::temp_dir().join("unvcpfl-test-profiles"));
+        let game = Game {
+            id: "faugus-some-game".to_string(),
+            name: "Some Game".to_string(),
+            executable: Some(PathBuf::from("/games/Some Game/game.exe")),
+            source: GameSource::Faugus,
+            install_path: Some(PathBuf::from("/prefixes/some-game")),
+            icon_url: None,
+        };
+        let mut profile = GameProfile::default();
+        profile.proton.use_umu = true;
+        profile.proton.version = Some("UMU-Test-Proton".to_string());
+
+        assert_eq!(
+            build_launch_command(&manager, &game, &profile),
+            "env GAMEID=0 PROTONPATH=UMU-Test-Proton WINEPREFIX=/prefixes/some-game umu-run \"/games/Some Game/game.exe\""
+        );
+    }
+
+    #[test]
+    fn test_runner_matches_real_launch() {
+        let mut game = Game {
+            id: "wine-some-game".to_string(),
+            name: "Some Game".to_string(),
+            executable: None,
+            source: GameSource::Wine,
+            install_path: Some(PathBuf::from("/prefixes/some-game")),
+            icon_url: None,
+        };
+        let mut profile = GameProfile::default();
+        let exe = Path::new("/games/Some Game/Game.EXE");
+
+        let (runner, env) = runner_for(&game, &profile, exe);
+        assert_eq!(runner, Some("wine"));
+        assert_eq!(env, vec![("WINEPREFIX".to_string(), "/prefixes/some-game".to_string())]);
+        assert_eq!(runner_for(&game, &profile, Path::new("/games/native/game.x86_64")).0, None);
+
+        game.source = GameSource::Steam;
+        game.id = "1091500".to_string();
+        assert_eq!(runner_for(&game, &profile, exe).0, Some("umu-run"));
+        game.source = GameSource::Faugus;
+        profile.proton.use_umu = true;
+        assert_eq!(runner_for(&game, &profile, exe).0, Some("umu-run"));
+    }
+
+    #[test]
+    fn test_generic_launch_command() {
+        let manager =
+            ProfileManager::with_profiles_dir(std::env::temp_dir().join("unvcpfl-test-profiles"));
+        let mut profile = GameProfile::default();
+        profile.proton.use_umu = true;
+        profile.wrappers.gamemode = true;
+        profile.wrappers.lact_profile = Some("Gaming Max".to_string());
+        profile
+            .custom_env
+            .insert("WINEPREFIX".to_string(), "/prefixes/generic".to_string());
+
+        let exe = std::env::temp_dir().join("unvcpfl-generic-test.exe");
+        std::fs::write(&exe, b"MZ").unwrap();
+        let cmd = build_generic_launch_command(&manager, &exe, &profile).unwrap();
+        std::fs::remove_file(&exe).ok();
+
+        assert_eq!(cmd.pre_launch, vec!["lact cli profile set \"Gaming Max\""]);
+        assert!(!cmd.argv.iter().any(|a| a.contains("lact") || a == "&&"));
+        assert_eq!(cmd.argv.iter().filter(|a| a.starts_with("WINEPREFIX=")).count(), 1);
+        let tail = &cmd.argv[cmd.argv.len() - 3..];
+        assert_eq!(tail[..2], ["gamemoderun", "umu-run"]);
+        assert_eq!(tail[2], exe.to_string_lossy());
+
+        assert!(
+            build_generic_launch_command(&manager, Path::new("/nonexistent/game.exe"), &profile)
+                .is_err()
+        );
+    }
+}
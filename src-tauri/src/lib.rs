@@ -1,13 +1,47 @@
+mod app_config;
+mod audio;
+mod backup;
+mod debug_report;
+mod desktop;
+mod dock;
+mod env_docs;
+mod focus_follow;
 mod games;
 mod game_settings;
+mod gpu_schedule;
+mod graphics_env;
+mod idle_inhibit;
+mod lact;
+mod launch;
+mod lockfile;
+mod lutris;
+mod mangohud;
+mod metrics_server;
 mod nvidia;
+mod nvidia_settings;
+mod panic_restore;
+mod precheck;
+mod prefixes;
 mod profiles;
+mod protondb;
+mod running_game;
 mod screen;
+mod screenshot;
+mod session_capture;
+mod shader_cache;
+mod steam_import;
+mod system;
+mod vdf;
 
 use games::{Game, GameDetector};
-use nvidia::{create_gpu_state, GpuInfo, SharedGpuState};
-use profiles::{GameProfile, ProfileManager};
-use screen::{Compositor, Monitor};
+use nvidia::{
+    create_gpu_state, GpuAdvancedInfo, GpuInfo, NvmlLatency, ResizableBarStatus, SessionEnergy,
+    SharedGpuState,
+};
+use profiles::{FieldDiff, GameProfile, ProfileManager, ReconcileReport};
+use screen::{ColorInfo, Compositor, HdrSupport, Monitor, MonitorLayout};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::State;
 
@@ -16,12 +50,132 @@ use tauri::State;
 async fn get_gpu_info(state: State<'_, SharedGpuState>) -> Result<Option<GpuInfo>, String> {
     let state = state.read().await;
     if let Some(monitor) = &state.monitor {
-        Ok(Some(monitor.get_info().map_err(|e| e.to_string())?))
+        Ok(Some(monitor.get_info(0)?))
     } else {
         Ok(None)
     }
 }
 
+#[tauri::command]
+async fn get_gpu_info_by_index(
+    state: State<'_, SharedGpuState>,
+    index: u32,
+) -> Result<Option<GpuInfo>, String> {
+    let state = state.read().await;
+    match &state.monitor {
+        Some(monitor) => Ok(Some(monitor.get_info(index)?)),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+async fn get_all_gpu_info(state: State<'_, SharedGpuState>) -> Result<Vec<GpuInfo>, String> {
+    let state = state.read().await;
+    Ok(state
+        .monitor
+        .as_ref()
+        .map(|m| m.get_all_gpu_info())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+async fn get_gpu_device_count(state: State<'_, SharedGpuState>) -> Result<u32, String> {
+    let state = state.read().await;
+    Ok(state.monitor.as_ref().map(|m| m.device_count()).unwrap_or(0))
+}
+
+#[tauri::command]
+async fn get_resizable_bar_status(
+    state: State<'_, SharedGpuState>,
+) -> Result<Option<ResizableBarStatus>, String> {
+    let state = state.read().await;
+    match &state.monitor {
+        Some(monitor) => Ok(Some(monitor.get_resizable_bar_status()?)),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+async fn get_gpu_advanced_info(
+    state: State<'_, SharedGpuState>,
+) -> Result<Option<GpuAdvancedInfo>, String> {
+    let state = state.read().await;
+    if let Some(monitor) = &state.monitor {
+        Ok(Some(monitor.get_advanced_info()?))
+    } else {
+        Ok(None)
+    }
+}
+
+#[tauri::command]
+async fn measure_nvml_latency(
+    state: State<'_, SharedGpuState>,
+) -> Result<Option<NvmlLatency>, String> {
+    let state = state.read().await;
+    if let Some(monitor) = &state.monitor {
+        Ok(Some(monitor.measure_latency()?))
+    } else {
+        Ok(None)
+    }
+}
+
+#[tauri::command]
+async fn get_last_session_energy(
+    state: State<'_, SharedGpuState>,
+) -> Result<Option<SessionEnergy>, String> {
+    Ok(state.read().await.last_session_energy.clone())
+}
+
+#[tauri::command]
+async fn get_session_energy_history(
+    state: State<'_, SharedGpuState>,
+) -> Result<Vec<SessionEnergy>, String> {
+    Ok(state.read().await.energy_history.iter().cloned().collect())
+}
+
+#[tauri::command]
+async fn lock_gpu_clocks(
+    state: State<'_, SharedGpuState>,
+    min_mhz: u32,
+    max_mhz: u32,
+) -> Result<(), String> {
+    let state = state.read().await;
+    match &state.monitor {
+        Some(monitor) => monitor.lock_gpu_clocks(min_mhz, max_mhz),
+        None => Err("No NVIDIA GPU detected".to_string()),
+    }
+}
+
+/// Put monitors, GPU and LACT back to their state at startup; meant for a global shortcut
+#[tauri::command]
+async fn panic_restore(
+    state: State<'_, SharedGpuState>,
+) -> Result<panic_restore::PanicRestoreReport, String> {
+    Ok(panic_restore::panic_restore(Arc::clone(&state)).await)
+}
+
+#[tauri::command]
+async fn unlock_gpu_clocks(state: State<'_, SharedGpuState>) -> Result<(), String> {
+    let state = state.read().await;
+    match &state.monitor {
+        Some(monitor) => monitor.unlock_gpu_clocks(),
+        None => Err("No NVIDIA GPU detected".to_string()),
+    }
+}
+
+#[tauri::command]
+async fn generate_debug_report(
+    gpu_state: State<'_, SharedGpuState>,
+    profile_state: State<'_, Arc<ProfileManager>>,
+) -> Result<String, String> {
+    let gpu = gpu_state.read().await.monitor.as_ref().map(|m| debug_report::GpuReport {
+        name: m.get_gpu_name(),
+        nvml_version: m.get_nvml_version(),
+    });
+    let profiles = profile_state.list_profiles();
+    Ok(debug_report::generate_debug_report(gpu.as_ref(), &profiles))
+}
+
 #[tauri::command]
 async fn get_gpu_name(state: State<'_, SharedGpuState>) -> Result<String, String> {
     let state = state.read().await;
@@ -53,6 +207,64 @@ fn detect_heroic_games() -> Vec<Game> {
     GameDetector::detect_heroic_games()
 }
 
+#[tauri::command]
+fn get_game_proton_override(steam_appid: u32) -> Option<String> {
+    GameDetector::get_game_proton_override(steam_appid)
+}
+
+#[tauri::command]
+fn get_proton_experimental_branch(steam_appid: u32) -> Option<String> {
+    GameDetector::get_proton_experimental_branch(steam_appid)
+}
+
+#[tauri::command]
+fn list_wine_prefixes() -> Vec<prefixes::PrefixInfo> {
+    prefixes::list_wine_prefixes()
+}
+
+#[tauri::command]
+fn detect_orphan_prefixes() -> Vec<Game> {
+    prefixes::detect_orphan_prefixes()
+}
+
+#[tauri::command]
+fn list_orphaned_compatdata() -> Vec<prefixes::OrphanPrefix> {
+    prefixes::list_orphaned_compatdata()
+}
+
+#[tauri::command]
+fn delete_compatdata(steam_appid: u32, path: PathBuf, confirm_size_bytes: u64) -> Result<u64, String> {
+    prefixes::delete_compatdata(steam_appid, &path, confirm_size_bytes)
+}
+
+#[tauri::command]
+fn list_steam_users() -> Vec<games::SteamUser> {
+    GameDetector::list_steam_users()
+}
+
+#[tauri::command]
+fn get_app_config() -> app_config::AppConfig {
+    app_config::get_app_config()
+}
+
+/// Pick the Steam account to read userdata from; None goes back to the most recently used
+#[tauri::command]
+fn set_steam_user(id: Option<String>) -> Result<(), String> {
+    if let Some(id) = &id {
+        if !GameDetector::list_steam_users().iter().any(|u| &u.id == id) {
+            return Err(format!("Steam user '{}' not found", id));
+        }
+    }
+    let mut config = app_config::get_app_config();
+    config.steam_user = id;
+    app_config::save_app_config(&config)
+}
+
+#[tauri::command]
+fn get_recent_appids(limit: u32) -> Vec<u32> {
+    GameDetector::get_recent_appids(limit)
+}
+
 // Profile management commands
 #[tauri::command]
 fn list_profiles(state: State<'_, Arc<ProfileManager>>) -> Vec<GameProfile> {
@@ -82,6 +294,20 @@ fn delete_profile(state: State<'_, Arc<ProfileManager>>, name: String) -> Result
     state.delete_profile(&name)
 }
 
+#[tauri::command]
+fn list_profile_versions(state: State<'_, Arc<ProfileManager>>, name: String) -> Vec<u64> {
+    state.list_profile_versions(&name)
+}
+
+#[tauri::command]
+fn restore_profile_version(
+    state: State<'_, Arc<ProfileManager>>,
+    name: String,
+    timestamp: u64,
+) -> Result<GameProfile, String> {
+    state.restore_profile_version(&name, timestamp)
+}
+
 #[tauri::command]
 fn duplicate_profile(
     state: State<'_, Arc<ProfileManager>>,
@@ -91,11 +317,66 @@ fn duplicate_profile(
     state.duplicate_profile(&source_name, &new_name)
 }
 
+#[tauri::command]
+fn export_profile_lockfile(
+    state: State<'_, Arc<ProfileManager>>,
+    name: String,
+) -> Result<lockfile::ProfileLockfile, String> {
+    lockfile::export_profile_lockfile(&state, &name)
+}
+
+#[tauri::command]
+fn reconcile_profiles(state: State<'_, Arc<ProfileManager>>) -> Result<ReconcileReport, String> {
+    state.reconcile_profiles()
+}
+
+#[tauri::command]
+fn list_orphaned_profiles(state: State<'_, Arc<ProfileManager>>) -> Vec<GameProfile> {
+    state.list_orphaned_profiles(&GameDetector::detect_all_games())
+}
+
+#[tauri::command]
+fn check_profiles_bound_status(state: State<'_, Arc<ProfileManager>>) -> HashMap<String, bool> {
+    state.check_profiles_bound_status(&GameDetector::detect_all_games())
+}
+
+#[tauri::command]
+fn find_duplicate_bindings(state: State<'_, Arc<ProfileManager>>) -> Vec<profiles::DuplicateBinding> {
+    state.find_duplicate_bindings()
+}
+
+#[tauri::command]
+fn prune_orphaned_profiles(
+    state: State<'_, Arc<ProfileManager>>,
+    names: Vec<String>,
+) -> Result<Vec<String>, String> {
+    state.prune_orphaned_profiles(&names, &GameDetector::detect_all_games())
+}
+
+#[tauri::command]
+fn diff_from_default(
+    state: State<'_, Arc<ProfileManager>>,
+    name: String,
+    baseline: Option<String>,
+) -> Result<Vec<FieldDiff>, String> {
+    state.diff_from_default(&name, baseline.as_deref())
+}
+
 #[tauri::command]
 fn list_template_profiles(state: State<'_, Arc<ProfileManager>>) -> Vec<GameProfile> {
     state.list_template_profiles()
 }
 
+#[tauri::command]
+async fn list_applicable_templates(
+    state: State<'_, Arc<ProfileManager>>,
+    gpu_state: State<'_, SharedGpuState>,
+) -> Result<Vec<GameProfile>, String> {
+    let gpu = gpu_state.read().await;
+    let gpu_name = gpu.monitor.as_ref().map(|m| m.get_gpu_name());
+    Ok(state.list_applicable_templates(gpu_name.as_deref()))
+}
+
 #[tauri::command]
 fn apply_template(
     state: State<'_, Arc<ProfileManager>>,
@@ -105,6 +386,21 @@ fn apply_template(
     state.apply_template(&template_name, &game_name)
 }
 
+#[tauri::command]
+fn export_profile_to_lutris(state: State<'_, Arc<ProfileManager>>, name: String) -> Result<String, String> {
+    lutris::export_profile_to_lutris(&state, &name)
+}
+
+#[tauri::command]
+fn import_steam_launch_options(line: String) -> GameProfile {
+    steam_import::import_steam_launch_options(&line)
+}
+
+#[tauri::command]
+fn make_safe(state: State<'_, Arc<ProfileManager>>, name: String) -> Result<GameProfile, String> {
+    state.make_safe(&name)
+}
+
 #[tauri::command]
 fn build_env_vars(
     state: State<'_, Arc<ProfileManager>>,
@@ -118,6 +414,117 @@ fn build_wrapper_cmd(state: State<'_, Arc<ProfileManager>>, profile: GameProfile
     state.build_wrapper_cmd(&profile)
 }
 
+#[tauri::command]
+fn set_profile_flag(
+    state: State<'_, Arc<ProfileManager>>,
+    name: String,
+    field_path: String,
+    value: bool,
+) -> Result<GameProfile, String> {
+    state.set_profile_flag(&name, &field_path, value)
+}
+
+#[tauri::command]
+fn verify_profile_roundtrip(
+    state: State<'_, Arc<ProfileManager>>,
+    name: String,
+) -> Result<Vec<String>, String> {
+    state.verify_profile_roundtrip(&name)
+}
+
+#[tauri::command]
+fn build_env_export_block(state: State<'_, Arc<ProfileManager>>, profile: GameProfile) -> String {
+    state.build_env_export_block(&profile)
+}
+
+#[tauri::command]
+fn trace_env_sources(
+    state: State<'_, Arc<ProfileManager>>,
+    profile: GameProfile,
+) -> HashMap<String, String> {
+    state.trace_env_sources(&profile)
+}
+
+#[tauri::command]
+fn compute_effective_graphics_env(
+    state: State<'_, Arc<ProfileManager>>,
+    profile: GameProfile,
+    steam_appid: Option<u32>,
+) -> graphics_env::EffectiveGraphicsEnv {
+    graphics_env::compute_effective_graphics_env(&state, &profile, steam_appid)
+}
+
+#[tauri::command]
+fn analyze_custom_env(profile: GameProfile) -> Vec<env_docs::CustomEnvNote> {
+    env_docs::analyze_custom_env(&profile)
+}
+
+#[tauri::command]
+async fn check_profile_warnings(
+    state: State<'_, Arc<ProfileManager>>,
+    gpu_state: State<'_, SharedGpuState>,
+    profile: GameProfile,
+) -> Result<Vec<String>, String> {
+    let gpu = gpu_state.read().await;
+    let rebar = gpu
+        .monitor
+        .as_ref()
+        .and_then(|m| m.get_resizable_bar_status().ok());
+    Ok(state.check_profile_warnings(&profile, rebar.as_ref()))
+}
+
+#[tauri::command]
+async fn precheck_launch(
+    state: State<'_, Arc<ProfileManager>>,
+    profile: GameProfile,
+    steam_appid: Option<u32>,
+) -> Result<Vec<String>, String> {
+    Ok(precheck::precheck_launch(&state, &profile, steam_appid).await)
+}
+
+#[tauri::command]
+fn detect_framecap_conflicts(profile: GameProfile) -> Vec<String> {
+    profiles::detect_framecap_conflicts(&profile)
+}
+
+#[tauri::command]
+fn detect_exe_bitness(path: PathBuf) -> Option<String> {
+    games::detect_exe_bitness(&path)
+}
+
+#[tauri::command]
+fn validate_gamescope_resolution(
+    profile: GameProfile,
+    monitor_name: String,
+) -> Result<Vec<String>, String> {
+    let monitor = screen::list_monitors()?
+        .into_iter()
+        .find(|m| m.name == monitor_name)
+        .ok_or_else(|| format!("Monitor '{}' not found", monitor_name))?;
+    Ok(profiles::validate_gamescope_resolution(&profile, &monitor))
+}
+
+#[tauri::command]
+fn get_profile_summary(
+    state: State<'_, Arc<ProfileManager>>,
+    name: String,
+) -> Result<profiles::ProfileSummary, String> {
+    state.get_profile_summary(&name, &GameDetector::detect_all_games())
+}
+
+#[tauri::command]
+fn suggest_executable_matches(
+    state: State<'_, Arc<ProfileManager>>,
+    profile_name: String,
+) -> Result<Vec<String>, String> {
+    state.suggest_executable_matches(&profile_name, &GameDetector::detect_all_games())
+}
+
+#[tauri::command]
+fn list_profile_summaries(state: State<'_, Arc<ProfileManager>>) -> Vec<profiles::ProfileSummary> {
+    state.list_profile_summaries(&GameDetector::detect_all_games())
+}
+
 #[tauri::command]
 fn is_lact_available() -> bool {
     profiles::is_lact_available()
@@ -128,6 +535,12 @@ fn get_lact_profiles() -> Vec<String> {
     profiles::get_lact_profiles()
 }
 
+// Audio commands
+#[tauri::command]
+fn list_audio_sinks() -> Vec<String> {
+    audio::list_audio_sinks()
+}
+
 // Screen configuration commands
 #[tauri::command]
 fn detect_compositor() -> Compositor {
@@ -145,6 +558,11 @@ fn list_monitors() -> Result<Vec<Monitor>, String> {
     screen::list_monitors()
 }
 
+#[tauri::command]
+fn get_focused_monitor() -> Option<Monitor> {
+    screen::get_focused_monitor()
+}
+
 #[tauri::command]
 fn is_screen_config_supported() -> bool {
     screen::is_screen_config_supported()
@@ -157,7 +575,17 @@ fn disable_monitor(name: String) -> Result<(), String> {
 
 #[tauri::command]
 fn enable_monitor(name: String, config: String) -> Result<(), String> {
-    screen::enable_monitor(&name, &config)
+    screen::enable_monitor_config_string(&name, &config)
+}
+
+#[tauri::command]
+fn get_self_window_class() -> Result<String, String> {
+    screen::self_window_class()
+}
+
+#[tauri::command]
+fn set_self_window_rule(rule: String) -> Result<(), String> {
+    screen::set_self_window_rule(&rule)
 }
 
 #[tauri::command]
@@ -165,9 +593,219 @@ fn set_game_monitor_rule(window_class: String, monitor_name: String) -> Result<(
     screen::set_game_monitor_rule(&window_class, &monitor_name)
 }
 
+#[tauri::command]
+fn set_game_workspace_rule(
+    window_class: String,
+    workspace: String,
+    persist: bool,
+) -> Result<(), String> {
+    screen::set_game_workspace_rule(&window_class, &workspace, persist)
+}
+
+#[tauri::command]
+fn remove_game_workspace_rule(window_class: String) -> Result<(), String> {
+    screen::remove_game_workspace_rule(&window_class)
+}
+
+#[tauri::command]
+fn set_game_tearing_rule(window_class: String, enabled: bool) -> Result<(), String> {
+    screen::set_game_tearing_rule(&window_class, enabled)
+}
+
+#[tauri::command]
+fn apply_screen_settings(profile: GameProfile) -> Result<(), String> {
+    screen::apply_screen_settings(&profile)
+}
+
+#[tauri::command]
+fn preview_screen_commands(profile: GameProfile) -> Vec<String> {
+    screen::preview_screen_commands(&profile)
+}
+
+#[tauri::command]
+fn suggest_frame_cap(monitor_name: String, margin: Option<u32>) -> Result<u32, String> {
+    screen::suggest_frame_cap(&monitor_name, margin)
+}
+
+#[tauri::command]
+fn set_monitor_gamma(name: String, gamma: f32) -> Result<(), String> {
+    screen::set_monitor_gamma(&name, gamma)
+}
+
+#[tauri::command]
+fn set_monitor_temperature(name: String, kelvin: u32) -> Result<(), String> {
+    screen::set_monitor_temperature(&name, kelvin)
+}
+
+#[tauri::command]
+fn reset_monitor_color(name: String) -> Result<(), String> {
+    screen::reset_monitor_color(&name)
+}
+
 #[tauri::command]
 fn get_monitor_configs() -> Result<std::collections::HashMap<String, String>, String> {
-    screen::get_monitor_configs()
+    screen::get_monitor_config_strings()
+}
+
+#[tauri::command]
+fn get_dock_profiles() -> dock::DockProfiles {
+    dock::get_dock_profiles()
+}
+
+#[tauri::command]
+fn save_dock_profiles(profiles: dock::DockProfiles) -> Result<(), String> {
+    dock::save_dock_profiles(&profiles)
+}
+
+#[tauri::command]
+fn is_docked() -> Result<bool, String> {
+    dock::is_docked()
+}
+
+#[tauri::command]
+fn apply_dock_profile() -> Result<(), String> {
+    dock::apply_dock_profile()
+}
+
+#[tauri::command]
+fn apply_undocked_profile() -> Result<(), String> {
+    dock::apply_undocked_profile()
+}
+
+#[tauri::command]
+fn get_compositor_display_state() -> screen::CompositorDisplayState {
+    screen::get_compositor_display_state()
+}
+
+#[tauri::command]
+fn get_hdr_support() -> HdrSupport {
+    screen::get_hdr_support()
+}
+
+#[tauri::command]
+fn capture_screenshot(output_path: String) -> Result<(), String> {
+    screenshot::capture_screenshot(&PathBuf::from(output_path))
+}
+
+#[tauri::command]
+fn capture_profile_screenshot(profile_name: String) -> Result<String, String> {
+    screenshot::capture_profile_screenshot(&profile_name)
+}
+
+#[tauri::command]
+fn suggest_integer_scale_resolutions(monitor: String) -> Result<Vec<String>, String> {
+    screen::suggest_integer_scale_resolutions(&monitor)
+}
+
+#[tauri::command]
+fn get_monitor_color_info(name: String) -> Result<ColorInfo, String> {
+    screen::get_monitor_color_info(&name)
+}
+
+#[tauri::command]
+fn save_monitor_layout(name: String) -> Result<(), String> {
+    screen::save_monitor_layout(&name)
+}
+
+#[tauri::command]
+fn list_monitor_layouts() -> Vec<MonitorLayout> {
+    screen::list_monitor_layouts()
+}
+
+#[tauri::command]
+fn apply_monitor_layout(name: String) -> Result<(), String> {
+    screen::apply_monitor_layout(&name)
+}
+
+#[tauri::command]
+fn set_desktop_gamemode(enabled: bool) -> Result<(), String> {
+    desktop::set_desktop_gamemode(enabled)
+}
+
+#[tauri::command]
+fn create_full_backup(dest: PathBuf) -> Result<(), String> {
+    backup::create_full_backup(&dest)
+}
+
+#[tauri::command]
+async fn estimate_save_backup_size(steam_appid: u32) -> u64 {
+    backup::estimate_save_backup_size(steam_appid).await
+}
+
+#[tauri::command]
+async fn backup_game_saves(steam_appid: u32, dest: PathBuf) -> Result<(), String> {
+    backup::backup_game_saves(steam_appid, &dest).await
+}
+
+#[tauri::command]
+fn restore_full_backup(
+    src: PathBuf,
+    skip_machine_specific: bool,
+) -> Result<backup::RestoreReport, String> {
+    backup::restore_full_backup(&src, skip_machine_specific)
+}
+
+#[tauri::command]
+fn get_powermizer_mode() -> Result<String, String> {
+    nvidia_settings::get_powermizer_mode()
+}
+
+#[tauri::command]
+fn set_powermizer_mode(mode: String) -> Result<(), String> {
+    nvidia_settings::set_powermizer_mode(&mode)
+}
+
+#[tauri::command]
+fn set_gsync_allowed(enabled: bool) -> Result<(), String> {
+    nvidia_settings::set_gsync_allowed(enabled)
+}
+
+#[tauri::command]
+fn scan_shell_env_conflicts() -> Vec<String> {
+    system::scan_shell_env_conflicts()
+}
+
+#[tauri::command]
+fn get_kernel_module_type() -> Option<String> {
+    system::get_kernel_module_type()
+}
+
+#[tauri::command]
+fn get_system_info() -> system::SystemInfo {
+    system::get_system_info()
+}
+
+#[tauri::command]
+fn get_game_mangohud_override(exe_name: String) -> Option<profiles::MangoHudSettings> {
+    mangohud::get_game_mangohud_override(&exe_name)
+}
+
+#[tauri::command]
+fn check_mangohud_layer() -> bool {
+    system::check_mangohud_layer()
+}
+
+#[tauri::command]
+fn check_gamescope_hdr_support() -> bool {
+    system::check_gamescope_hdr_support()
+}
+
+#[tauri::command]
+fn get_active_vulkan_icd() -> Vec<String> {
+    system::get_active_vulkan_icd()
+}
+
+#[tauri::command]
+fn get_shader_cache_info(
+    state: State<'_, Arc<ProfileManager>>,
+    steam_appid: u32,
+) -> shader_cache::ShaderCacheInfo {
+    shader_cache::get_shader_cache_info(&state, steam_appid)
+}
+
+#[tauri::command]
+fn clear_shader_cache(steam_appid: u32, confirm_size_bytes: u64) -> Result<u64, String> {
+    shader_cache::clear_shader_cache(steam_appid, confirm_size_bytes)
 }
 
 #[tauri::command]
@@ -182,6 +820,31 @@ async fn get_game_data_paths(steam_appid: u32) -> game_settings::GameDataPaths {
     game_settings::fetch_pcgamingwiki_paths(steam_appid).await
 }
 
+#[tauri::command]
+async fn detect_game_resolution(steam_appid: u32) -> Option<(u32, u32)> {
+    game_settings::detect_game_resolution(steam_appid).await
+}
+
+#[tauri::command]
+async fn detect_game_graphics_api(steam_appid: u32) -> Vec<String> {
+    game_settings::detect_game_graphics_api(steam_appid).await
+}
+
+#[tauri::command]
+async fn suggest_winetricks(steam_appid: u32) -> Vec<String> {
+    game_settings::suggest_winetricks(steam_appid).await
+}
+
+#[tauri::command]
+async fn apply_winetricks(steam_appid: u32, verbs: Vec<String>) -> Result<String, String> {
+    game_settings::apply_winetricks(steam_appid, &verbs).await
+}
+
+#[tauri::command]
+async fn fetch_protondb_tips(steam_appid: u32) -> protondb::ProtonTips {
+    protondb::fetch_protondb_tips(steam_appid).await
+}
+
 #[tauri::command]
 fn open_game_path(path: String, in_editor: bool) -> Result<(), String> {
     if in_editor {
@@ -191,25 +854,97 @@ fn open_game_path(path: String, in_editor: bool) -> Result<(), String> {
     }
 }
 
+#[tauri::command]
+async fn start_focus_follow(state: State<'_, Arc<ProfileManager>>) -> Result<(), String> {
+    focus_follow::start_focus_follow(Arc::clone(&state)).await
+}
+
+#[tauri::command]
+async fn stop_focus_follow() -> Result<(), String> {
+    focus_follow::stop_focus_follow().await
+}
+
+#[tauri::command]
+async fn start_metrics_server(state: State<'_, SharedGpuState>, port: u16) -> Result<(), String> {
+    metrics_server::start_metrics_server(Arc::clone(&state), port).await
+}
+
+#[tauri::command]
+async fn stop_metrics_server() -> Result<(), String> {
+    metrics_server::stop_metrics_server().await
+}
+
+#[tauri::command]
+async fn start_session_capture(
+    state: State<'_, SharedGpuState>,
+    game: Option<String>,
+) -> Result<(), String> {
+    session_capture::start_session_capture(Arc::clone(&state), game).await
+}
+
+#[tauri::command]
+async fn stop_session_capture() -> Result<usize, String> {
+    session_capture::stop_session_capture().await
+}
+
+#[tauri::command]
+fn export_gpu_session_csv(dest: String) -> Result<(), String> {
+    session_capture::export_gpu_session_csv(std::path::Path::new(&dest))
+}
+
+#[tauri::command]
+fn get_gpu_schedule() -> gpu_schedule::GpuSchedule {
+    gpu_schedule::get_gpu_schedule()
+}
+
+#[tauri::command]
+fn get_gpu_schedule_error() -> Option<String> {
+    gpu_schedule::get_gpu_schedule_error()
+}
+
+#[tauri::command]
+async fn set_gpu_schedule(
+    state: State<'_, SharedGpuState>,
+    schedule: gpu_schedule::GpuSchedule,
+) -> Result<(), String> {
+    gpu_schedule::set_gpu_schedule(Arc::clone(&state), schedule).await
+}
+
+#[tauri::command]
+fn apply_to_running_game(
+    state: State<'_, Arc<ProfileManager>>,
+    window_class: String,
+    profile_name: String,
+) -> Result<running_game::RunningGameResult, String> {
+    running_game::apply_to_running_game(&state, &window_class, &profile_name)
+}
+
+#[tauri::command]
+fn is_steam_running() -> bool {
+    launch::is_steam_running()
+}
+
+#[tauri::command]
+fn preview_launch(
+    state: State<'_, Arc<ProfileManager>>,
+    game: Game,
+    profile: GameProfile,
+) -> launch::LaunchPreview {
+    launch::preview_launch(&state, &game, &profile)
+}
+
+#[tauri::command]
+fn build_generic_launch_command(
+    state: State<'_, Arc<ProfileManager>>,
+    exe_path: PathBuf,
+    profile: GameProfile,
+) -> Result<launch::GenericLaunchCommand, String> {
+    launch::build_generic_launch_command(&state, &exe_path, &profile)
+}
+
 #[tauri::command]
 fn create_desktop_entry(game: Game, profile: GameProfile, state: State<'_, Arc<ProfileManager>>) -> Result<String, String> {
-    let env_vars = state.build_env_vars(&profile);
-    let wrappers = state.build_wrapper_cmd(&profile);
-    
-    let env_string = env_vars.iter()
-        .map(|(k, v)| format!("{}={}", k, v))
-        .collect::<Vec<_>>()
-        .join(" ");
-    
-    let wrapper_string = wrappers.join(" ");
-    
-    // Build launch command based on game source
-    let exec = match game.source {
-        games::GameSource::Steam => format!("env {} {} steam steam://rungameid/{}", env_string, wrapper_string, game.id),
-        games::GameSource::Lutris => format!("env {} {} lutris lutris:rungameid/{}", env_string, wrapper_string, game.id),
-        games::GameSource::Heroic => format!("env {} {} heroic heroic://launch/{}", env_string, wrapper_string, game.id),
-        games::GameSource::Faugus => format!("env {} {} xdg-open faugus://{}", env_string, wrapper_string, game.id),
-    };
+    let exec = launch::build_launch_command(&state, &game, &profile);
     
     let desktop_entry = format!(
 r#"[Desktop Entry]
@@ -225,7 +960,7 @@ Categories=Game;
     
     // Write to ~/.local/share/applications/
     let apps_dir = dirs::data_local_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("~/.local/share"))
+        .unwrap_or_else(|| PathBuf::from("~/.local/share"))
         .join("applications");
     
     std::fs::create_dir_all(&apps_dir).ok();
@@ -239,53 +974,246 @@ Categories=Game;
     Ok(path.to_string_lossy().to_string())
 }
 
+#[tauri::command]
+async fn test_launch(
+    state: State<'_, Arc<ProfileManager>>,
+    gpu_state: State<'_, SharedGpuState>,
+    game: Game,
+    profile: GameProfile,
+    timeout_secs: Option<u64>,
+    lock_clocks_mhz: Option<(u32, u32)>,
+) -> Result<launch::TestLaunchResult, String> {
+    let timeout = timeout_secs.unwrap_or(launch::DEFAULT_TEST_TIMEOUT_SECS);
+
+    // Apply the profile's PowerMizer mode for the run, remembering the old one
+    let previous_powermizer = match &profile.nvidia.powermizer_mode {
+        Some(mode) => {
+            let previous = nvidia_settings::get_powermizer_mode().ok();
+            nvidia_settings::set_powermizer_mode(mode)?;
+            previous
+        }
+        None => None,
+    };
+    let restore_powermizer = || {
+        if let Some(previous) = &previous_powermizer {
+            nvidia_settings::set_powermizer_mode(previous).ok();
+        }
+    };
+
+    // Optionally pin clocks for repeatable runs, always releasing them afterwards
+    if let Some((min_mhz, max_mhz)) = lock_clocks_mhz {
+        let gpu = gpu_state.read().await;
+        let locked = match gpu.monitor.as_ref() {
+            Some(monitor) => monitor.lock_gpu_clocks(min_mhz, max_mhz),
+            None => Err("No NVIDIA GPU detected".to_string()),
+        };
+        if let Err(e) = locked {
+            restore_powermizer();
+            return Err(e);
+        }
+    }
+
+    let energy_start = gpu_state
+        .read()
+        .await
+        .monitor
+        .as_ref()
+        .and_then(|m| m.total_energy_mj());
+    let started = std::time::Instant::now();
+
+    let result = launch::test_launch(&state, &game, &profile, timeout).await;
+
+    let mut gpu = gpu_state.write().await;
+    let mut energy = None;
+    if let Some(monitor) = &gpu.monitor {
+        if lock_clocks_mhz.is_some() {
+            monitor.unlock_gpu_clocks().ok();
+        }
+        if let (Some(start), Some(end)) = (energy_start, monitor.total_energy_mj()) {
+            energy = Some(SessionEnergy::from_samples(&game.name, start, end, started));
+        }
+    }
+    if let Some(energy) = energy {
+        gpu.record_session_energy(energy);
+    }
+    restore_powermizer();
+
+    result
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let gpu_state = create_gpu_state();
     let profile_manager = Arc::new(ProfileManager::new());
 
+    let schedule_gpu_state = Arc::clone(&gpu_state);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .setup(move |_app| {
+            // Before anything reads layouts or the dock's disabled monitors
+            app_config::run_migrations().ok();
+            std::thread::spawn(panic_restore::take_snapshot);
+            // A failure here is kept for get_gpu_schedule_error
+            tauri::async_runtime::spawn(async move {
+                gpu_schedule::start_gpu_schedule(schedule_gpu_state).ok();
+            });
+            Ok(())
+        })
         .manage(gpu_state)
         .manage(profile_manager)
         .invoke_handler(tauri::generate_handler![
             // GPU commands
             get_gpu_info,
+            get_gpu_info_by_index,
+            get_all_gpu_info,
+            get_gpu_device_count,
+            get_gpu_advanced_info,
+            get_resizable_bar_status,
+            measure_nvml_latency,
+            get_last_session_energy,
+            get_session_energy_history,
             get_gpu_name,
+            generate_debug_report,
+            lock_gpu_clocks,
+            unlock_gpu_clocks,
+            panic_restore,
             // Game detection
             detect_games,
             detect_steam_games,
             detect_lutris_games,
             detect_heroic_games,
+            get_game_proton_override,
+            get_proton_experimental_branch,
+            list_steam_users,
+            get_app_config,
+            set_steam_user,
+            get_recent_appids,
+            list_wine_prefixes,
+            detect_orphan_prefixes,
+            list_orphaned_compatdata,
+            delete_compatdata,
             // Profile management
             list_profiles,
             get_profile,
             get_profile_by_executable,
             save_profile,
             delete_profile,
+            list_profile_versions,
+            restore_profile_version,
             duplicate_profile,
+            reconcile_profiles,
+            export_profile_lockfile,
+            list_orphaned_profiles,
+            check_profiles_bound_status,
+            find_duplicate_bindings,
+            prune_orphaned_profiles,
+            diff_from_default,
             list_template_profiles,
+            list_applicable_templates,
             apply_template,
+            make_safe,
+            import_steam_launch_options,
+            export_profile_to_lutris,
             build_env_vars,
             build_wrapper_cmd,
+            check_profile_warnings,
+            validate_gamescope_resolution,
+            detect_exe_bitness,
+            precheck_launch,
+            detect_framecap_conflicts,
+            get_profile_summary,
+            list_profile_summaries,
+            suggest_executable_matches,
+            trace_env_sources,
+            compute_effective_graphics_env,
+            analyze_custom_env,
+            build_env_export_block,
+            set_profile_flag,
+            verify_profile_roundtrip,
             // LACT integration
             is_lact_available,
             get_lact_profiles,
+            // Audio
+            list_audio_sinks,
             // Screen configuration
             detect_compositor,
             get_compositor_name,
             list_monitors,
+            get_focused_monitor,
             is_screen_config_supported,
             disable_monitor,
             enable_monitor,
             set_game_monitor_rule,
+            get_self_window_class,
+            set_self_window_rule,
+            set_game_workspace_rule,
+            remove_game_workspace_rule,
+            set_game_tearing_rule,
+            apply_screen_settings,
+            preview_screen_commands,
             get_monitor_configs,
+            suggest_frame_cap,
+            set_monitor_gamma,
+            set_monitor_temperature,
+            reset_monitor_color,
+            get_hdr_support,
+            get_compositor_display_state,
+            get_monitor_color_info,
+            suggest_integer_scale_resolutions,
+            capture_screenshot,
+            capture_profile_screenshot,
+            get_dock_profiles,
+            save_dock_profiles,
+            is_docked,
+            apply_dock_profile,
+            apply_undocked_profile,
+            save_monitor_layout,
+            list_monitor_layouts,
+            apply_monitor_layout,
+            set_desktop_gamemode,
+            create_full_backup,
+            restore_full_backup,
+            estimate_save_backup_size,
+            backup_game_saves,
+            get_powermizer_mode,
+            set_powermizer_mode,
+            set_gsync_allowed,
             // Game data paths (PCGamingWiki)
             get_game_data_paths,
+            detect_game_resolution,
+            detect_game_graphics_api,
+            suggest_winetricks,
+            apply_winetricks,
+            fetch_protondb_tips,
+            get_shader_cache_info,
+            clear_shader_cache,
             open_game_path,
             // System info
             get_hostname,
+            get_system_info,
+            check_mangohud_layer,
+            check_gamescope_hdr_support,
+            get_game_mangohud_override,
+            get_active_vulkan_icd,
+            get_kernel_module_type,
+            scan_shell_env_conflicts,
             create_desktop_entry,
+            is_steam_running,
+            start_focus_follow,
+            stop_focus_follow,
+            start_metrics_server,
+            stop_metrics_server,
+            start_session_capture,
+            stop_session_capture,
+            export_gpu_session_csv,
+            get_gpu_schedule,
+            get_gpu_schedule_error,
+            set_gpu_schedule,
+            preview_launch,
+            build_generic_launch_command,
+            test_launch,
+            apply_to_running_game,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
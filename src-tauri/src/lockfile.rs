@@ -0,0 +1,107 @@
+//! Profile lockfiles
+//!
+//! A lockfile pairs a profile with the versions it was actually run with
+//! (Proton and its Experimental branch, DXVK, VKD3D-Proton, driver, gamescope),
+//! so a shared profile says what it was tested against. Versions are
+//! best-effort and not part of the portable profile itself.
+
+use crate::games::GameDetector;
+use crate::profiles::{GameProfile, ProfileManager};
+use crate::system;
+use regex::bytes::Regex as BytesRegex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileLockfile {
+    pub profile: GameProfile,
+    pub generated_at: u64, // Unix seconds
+    pub proton_version: Option<String>,
+    #[serde(default)]
+    pub proton_experimental_branch: Option<String>, // "default" or "bleeding-edge"
+    pub dxvk_version: Option<String>,
+    pub vkd3d_version: Option<String>,
+    pub nvidia_driver_version: Option<String>,
+    pub gamescope_version: Option<String>,
+}
+
+/// Resolve the profile's runtime context and write it next to the profile
+pub fn export_profile_lockfile(
+    manager: &ProfileManager,
+    name: &str,
+) -> Result<ProfileLockfile, String> {
+    let profile = manager
+        .get_profile(name)
+        .ok_or_else(|| format!("Profile '{}' not found", name))?;
+
+    let compatdata = profile
+        .steam_appid
+        .and_then(GameDetector::find_compatdata_path);
+    let system32 = compatdata
+        .as_ref()
+        .map(|c| c.join("pfx/drive_c/windows/system32"));
+
+    // What the prefix was last run with beats what Steam or the profile asks for
+    let proton_version = compatdata
+        .as_deref()
+        .and_then(prefix_proton_version)
+        .or_else(|| {
+            profile
+                .steam_appid
+                .and_then(GameDetector::get_game_proton_override)
+        })
+        .or_else(|| profile.proton.version.clone());
+
+    let lockfile = ProfileLockfile {
+        generated_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        proton_version,
+        proton_experimental_branch: profile
+            .steam_appid
+            .and_then(GameDetector::get_proton_experimental_branch),
+        dxvk_version: system32
+            .as_ref()
+            .and_then(|dir| dll_version(&dir.join("d3d11.dll"))),
+        vkd3d_version: system32
+            .as_ref()
+            .and_then(|dir| dll_version(&dir.join("d3d12core.dll")))
+            .or_else(|| {
+                system32
+                    .as_ref()
+                    .and_then(|dir| dll_version(&dir.join("d3d12.dll")))
+            }),
+        nvidia_driver_version: system::nvidia_driver_version(),
+        gamescope_version: system::gamescope_version(),
+        profile,
+    };
+
+    let json = serde_json::to_string_pretty(&lockfile)
+        .map_err(|e| format!("Failed to serialize lockfile: {}", e))?;
+    fs::write(manager.lockfile_path(name), json)
+        .map_err(|e| format!("Failed to write lockfile: {}", e))?;
+
+    Ok(lockfile)
+}
+
+/// Proton records the version that last updated a prefix in compatdata/<appid>/version
+fn prefix_proton_version(compatdata: &Path) -> Option<String> {
+    fs::read_to_string(compatdata.join("version"))
+        .ok()
+        .or_else(|| fs::read_to_string(compatdata.join("config_info")).ok())
+        .and_then(|content| content.lines().next().map(|l| l.trim().to_string()))
+        .filter(|v| !v.is_empty())
+}
+
+/// DXVK and VKD3D-Proton embed their version (e.g. "v2.4" or "v2.13-12-gabcdef")
+/// as a standalone string in the DLL; Wine's builtin DLLs don't, so None means
+/// the prefix isn't using them.
+fn dll_version(path: &Path) -> Option<String> {
+    let data = fs::read(path).ok()?;
+    let re = BytesRegex::new(r"(?-u)\x00(v\d+\.\d+(?:\.\d+)?(?:-\d+-g[0-9a-f]+)?)\x00").ok()?;
+    re.captures(&data)
+        .and_then(|c| String::from_utf8(c[1].to_vec()).ok())
+}
+
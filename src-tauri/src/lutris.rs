@@ -0,0 +1,172 @@
+//! Export profiles as Lutris game configs
+//!
+//! Lutris keeps per-game settings in `~/.config/lutris/games/<slug>.yml`. The
+//! profile's environment goes into `system.env`, MangoHud and GameMode map to
+//! Lutris' own toggles, and every other wrapper becomes `system.prefix_command`.
+//! Shell steps run before the game (the LACT profile switch) go into
+//! `system.prelaunch_command`, which Lutris waits for before starting the game.
+
+use crate::profiles::{GameProfile, ProfileManager};
+use crate::screen::shell_quote;
+use std::collections::HashMap;
+
+/// YAML double-quoted scalar
+fn yaml_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn lutris_yaml(
+    profile: &GameProfile,
+    env: &HashMap<String, String>,
+    wrappers: Vec<Vec<String>>,
+) -> String {
+    let mut yaml = format!("# Exported from uNVCPfL profile \"{}\"\n", profile.name);
+
+    let mut prefix = Vec::new();
+    let mut prelaunch = Vec::new();
+    let (mut mangohud, mut gamemode) = (false, false);
+    for wrapper in wrappers {
+        match wrapper.as_slice() {
+            // The toggles take no arguments; with any, keep the wrapper as is
+            [cmd] if cmd == "mangohud" => mangohud = true,
+            [cmd] if cmd == "gamemoderun" => gamemode = true,
+            // A shell step before the game; prefix_command can't chain commands
+            [cmd] if cmd.ends_with("&&") => {
+                prelaunch.push(cmd.trim_end_matches("&&").trim().to_string());
+            }
+            // Lutris splits prefix_command like a shell
+            _ => prefix.extend(wrapper.iter().map(|arg| shell_quote(arg))),
+        }
+    }
+
+    yaml.push_str("system:\n");
+    if !env.is_empty() {
+        let mut vars: Vec<_> = env.iter().collect();
+        vars.sort();
+        yaml.push_str("  env:\n");
+        for (key, value) in vars {
+            yaml.push_str(&format!("    {}: {}\n", key, yaml_quote(value)));
+        }
+    }
+    if gamemode {
+        yaml.push_str("  gamemode: true\n");
+    }
+    if mangohud {
+        yaml.push_str("  mangohud: true\n");
+    }
+    if !prefix.is_empty() {
+        yaml.push_str(&format!(
+            "  prefix_command: {}\n",
+            yaml_quote(&prefix.join(" "))
+        ));
+    }
+    if !prelaunch.is_empty() {
+        // Lutris wants an existing executable first, so run the steps through sh
+        let script = shell_quote(&prelaunch.join(" && "));
+        yaml.push_str(&format!(
+            "  prelaunch_command: {}\n  prelaunch_wait: true\n",
+            yaml_quote(&format!("/bin/sh -c {}", script))
+        ));
+    }
+
+    if let Some(args) = profile
+        .custom_args
+        .as_deref()
+        .filter(|a| !a.trim().is_empty())
+    {
+        yaml.push_str(&format!("game:\n  args: {}\n", yaml_quote(args)));
+    }
+    yaml
+}
+
+/// Lutris game YAML for a saved profile
+pub fn export_profile_to_lutris(manager: &ProfileManager, name: &str) -> Result<String, String> {
+    let profile = manager
+        .get_profile(name)
+        .ok_or_else(|| format!("Profile '{}' not found", name))?;
+    Ok(lutris_yaml(
+        &profile,
+        &manager.build_env_vars(&profile),
+        manager.build_wrappers(&profile),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lutris_yaml_maps_wrappers() {
+        let profile = GameProfile {
+            name: "Witcher 3".to_string(),
+            custom_args: Some("-dx12".to_string()),
+            ..Default::default()
+        };
+        let env: HashMap<String, String> = [("PROTON_ENABLE_NVAPI", "1"), ("DXVK_HUD", "fps")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let wrappers = vec![
+            vec!["lact cli profile set \"Quiet\" &&".to_string()],
+            vec![
+                "gamescope".to_string(),
+                "-W".to_string(),
+                "2560".to_string(),
+                "--".to_string(),
+            ],
+            vec!["mangohud".to_string()],
+            vec!["gamemoderun".to_string()],
+            vec!["nice".to_string(), "-n".to_string(), "5".to_string()],
+        ];
+
+        let yaml = lutris_yaml(&profile, &env, wrappers);
+        assert!(yaml.contains("  env:\n    DXVK_HUD: \"fps\"\n    PROTON_ENABLE_NVAPI: \"1\"\n"));
+        assert!(yaml.contains("  gamemode: true\n  mangohud: true\n"));
+        assert!(yaml.contains("  prefix_command: \"gamescope -W 2560 -- nice -n 5\"\n"));
+        assert!(yaml.ends_with("game:\n  args: \"-dx12\"\n"));
+        assert_eq!(yaml_quote(r#"C:\a "b""#), r#""C:\\a \"b\"""#);
+    }
+
+    #[test]
+    fn test_lutris_yaml_round_trips() {
+        let profile = GameProfile {
+            name: "Quoted \"Game\"".to_string(),
+            ..Default::default()
+        };
+        let env: HashMap<String, String> = [
+            ("DXVK_CONFIG", r#"dxgi.customVendorId = "10de"; d3d11.maxFeatureLevel = 12_1"#),
+            ("WINEDLLOVERRIDES", "dxgi=n,b"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let wrappers = vec![
+            vec!["lact cli profile set \"Max Clocks\" &&".to_string()],
+            vec!["mangohud".to_string(), "--dlsym".to_string()],
+            vec!["taskset".to_string(), "-c".to_string(), "0-7".to_string()],
+            vec!["env".to_string(), "NAME=it's here".to_string()],
+        ];
+
+        let yaml = lutris_yaml(&profile, &env, wrappers);
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        let system = &parsed["system"];
+
+        let parsed_env: HashMap<String, String> =
+            serde_yaml::from_value(system["env"].clone()).unwrap();
+        assert_eq!(parsed_env, env);
+        assert!(system.get("mangohud").is_none());
+
+        // Lutris splits both commands with shlex
+        let prefix = shlex::split(system["prefix_command"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            prefix,
+            ["mangohud", "--dlsym", "taskset", "-c", "0-7", "env", "NAME=it's here"]
+        );
+        let prelaunch = shlex::split(system["prelaunch_command"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            prelaunch,
+            ["/bin/sh", "-c", "lact cli profile set \"Max Clocks\""]
+        );
+        assert_eq!(system["prelaunch_wait"], serde_yaml::Value::Bool(true));
+    }
+}
@@ -0,0 +1,100 @@
+//! Read existing per-game MangoHud configs for import into a profile
+//!
+//! MangoHud loads `~/.config/MangoHud/<app>.conf` for native games and
+//! `wine-<app>.conf` for Wine/Proton ones, where `<app>` is the executable
+//! name without its extension. Only the frame limit settings map onto profile
+//! fields; the rest of the file stays MangoHud's business.
+
+use crate::profiles::MangoHudSettings;
+use std::fs;
+use std::path::PathBuf;
+
+fn mangohud_config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("MangoHud")
+}
+
+/// Candidate config file names for an executable, the Wine name first
+fn config_file_names(exe_name: &str) -> Vec<String> {
+    let stem = exe_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(exe_name);
+    let mut names = vec![format!("wine-{}.conf", stem), format!("{}.conf", stem)];
+    if stem != exe_name {
+        names.push(format!("{}.conf", exe_name));
+    }
+    names
+}
+
+/// Frame limit settings from a MangoHud config. `fps_limit` may list several
+/// limits to cycle through; the first is the one active at startup.
+fn parse_mangohud_config(content: &str) -> MangoHudSettings {
+    // A per-game config only exists for games run under MangoHud
+    let mut settings = MangoHudSettings {
+        enabled: true,
+        ..Default::default()
+    };
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "fps_limit" => {
+                let limit = value
+                    .split(',')
+                    .next()
+                    .and_then(|v| v.trim().parse::<u32>().ok());
+                // 0 means unlimited
+                settings.fps_limit = limit.filter(|&l| l > 0);
+                settings.fps_limit_enabled = settings.fps_limit.is_some();
+            }
+            "fps_limit_method" => {
+                let method = value.trim();
+                if method == "early" || method == "late" {
+                    settings.fps_limiter_mode = Some(method.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    settings
+}
+
+/// MangoHud settings from the game's own MangoHud config, if it has one
+pub fn get_game_mangohud_override(exe_name: &str) -> Option<MangoHudSettings> {
+    let dir = mangohud_config_dir();
+    config_file_names(exe_name)
+        .iter()
+        .find_map(|name| fs::read_to_string(dir.join(name)).ok())
+        .map(|content| parse_mangohud_config(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mangohud_config() {
+        let content = "# per-game\nfps_limit = 117,60,0\nfps_limit_method=early\nno_display\n";
+        let settings = parse_mangohud_config(content);
+        assert!(settings.enabled);
+        assert!(settings.fps_limit_enabled);
+        assert_eq!(settings.fps_limit, Some(117));
+        assert_eq!(settings.fps_limiter_mode.as_deref(), Some("early"));
+
+        let unlimited = parse_mangohud_config("fps_limit=0\n");
+        assert!(!unlimited.fps_limit_enabled);
+        assert_eq!(unlimited.fps_limit, None);
+
+        assert_eq!(
+            config_file_names("witcher3.exe"),
+            vec!["wine-witcher3.conf", "witcher3.conf", "witcher3.exe.conf"]
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Opt-in local HTTP endpoint serving the latest GPU sample as JSON
+//!
+//! Lets external overlays and scripts read uNVCPfL's GPU data without the
+//! Tauri frontend: `curl http://127.0.0.1:<port>/` returns the current GpuInfo.
+//! Each request reads the shared GpuMonitor, the same way get_gpu_info does,
+//! so the server adds no polling of its own while nobody is asking.
+
+use crate::nvidia::{GpuInfo, SharedGpuState};
+use std::sync::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+struct MetricsServer {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+static METRICS_SERVER: Mutex<Option<MetricsServer>> = Mutex::new(None);
+
+// No CORS header: web pages in a browser have no business reading this
+fn http_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Response for a GPU reading; None when no NVIDIA GPU was detected
+fn metrics_response(sample: Option<Result<GpuInfo, String>>) -> String {
+    match sample {
+        Some(Ok(info)) => match serde_json::to_string(&info) {
+            Ok(json) => http_response("200 OK", &json),
+            Err(e) => http_response("500 Internal Server Error", &error_body(&e.to_string())),
+        },
+        Some(Err(e)) => http_response("503 Service Unavailable", &error_body(&e)),
+        None => http_response(
+            "503 Service Unavailable",
+            &error_body("No NVIDIA GPU detected"),
+        ),
+    }
+}
+
+async fn serve_client(mut stream: TcpStream, gpu_state: SharedGpuState) {
+    // Every path gets the same answer; just drain the request head
+    let mut buf = [0u8; 1024];
+    if stream.read(&mut buf).await.is_err() {
+        return;
+    }
+
+    let sample = gpu_state
+        .read()
+        .await
+        .monitor
+        .as_ref()
+        .map(|m| m.get_info(0));
+    let response = metrics_response(sample);
+    stream.write_all(response.as_bytes()).await.ok();
+    stream.shutdown().await.ok();
+}
+
+async fn run(listener: TcpListener, gpu_state: SharedGpuState, mut stop: oneshot::Receiver<()>) {
+    loop {
+        tokio::select! {
+            _ = &mut stop => break,
+            accepted = listener.accept() => {
+                if let Ok((stream, _)) = accepted {
+                    tokio::spawn(serve_client(stream, gpu_state.clone()));
+                }
+            }
+        }
+    }
+}
+
+/// Serve the latest GpuInfo on 127.0.0.1:`port` until stopped
+pub async fn start_metrics_server(gpu_state: SharedGpuState, port: u16) -> Result<(), String> {
+    if METRICS_SERVER.lock().map_err(|e| e.to_string())?.is_some() {
+        return Err("Metrics server is already running".to_string());
+    }
+
+    // Localhost only: GPU stats shouldn't be visible on the network
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to bind 127.0.0.1:{}: {}", port, e))?;
+
+    let (stop, stop_rx) = oneshot::channel();
+    let task = tokio::spawn(run(listener, gpu_state, stop_rx));
+    *METRICS_SERVER.lock().map_err(|e| e.to_string())? = Some(MetricsServer { stop, task });
+    Ok(())
+}
+
+/// Stop the metrics server, if running
+pub async fn stop_metrics_server() -> Result<(), String> {
+    let server = METRICS_SERVER.lock().map_err(|e| e.to_string())?.take();
+    if let Some(server) = server {
+        server.stop.send(()).ok();
+        server.task.await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> GpuInfo {
+        GpuInfo {
+            index: 0,
+            name: "NVIDIA GeForce RTX 4080".to_string(),
+            temperature: 54,
+            power_draw: 87.5,
+            power_limit: 320.0,
+            utilization: 12,
+            encoder_utilization: None,
+            decoder_utilization: None,
+            memory_used: 0,
+            memory_total: 0,
+            clock_graphics: 2610,
+            clock_memory: 11201,
+            fan_speed: Some(35),
+        }
+    }
+
+    #[test]
+    fn test_metrics_response() {
+        let ok = metrics_response(Some(Ok(sample())));
+        let (head, body) = ok.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK"));
+        assert!(!head.contains("Access-Control-Allow-Origin"));
+        assert!(head.contains(&format!("Content-Length: {}", body.len())));
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["temperature"], 54);
+
+        let lost = metrics_response(Some(Err("GPU \"0\" was removed".to_string())));
+        assert!(lost.starts_with("HTTP/1.1 503"));
+        let body = lost.split_once("\r\n\r\n").unwrap().1;
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["error"], "GPU \"0\" was removed");
+
+        assert!(metrics_response(None).contains("No NVIDIA GPU detected"));
+    }
+}
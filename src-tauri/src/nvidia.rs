@@ -1,15 +1,25 @@
-use nvml_wrapper::{enum_wrappers::device::Clock, enum_wrappers::device::TemperatureSensor, Nvml};
+use nvml_wrapper::{
+    enum_wrappers::device::Clock, enum_wrappers::device::ComputeMode,
+    enum_wrappers::device::TemperatureSensor, enums::device::GpuLockedClocksSetting,
+    error::NvmlError, Device, Nvml,
+};
 use serde::Serialize;
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::sync::RwLock;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct GpuInfo {
+    pub index: u32, // NVML device index
     pub name: String,
     pub temperature: u32,
     pub power_draw: f32,
     pub power_limit: f32,
     pub utilization: u32,
+    pub encoder_utilization: Option<u32>, // NVENC, percent
+    pub decoder_utilization: Option<u32>, // NVDEC, percent
     pub memory_used: u64,
     pub memory_total: u64,
     pub clock_graphics: u32,
@@ -17,64 +27,534 @@ pub struct GpuInfo {
     pub fan_speed: Option<u32>,
 }
 
+/// Diagnostic GPU details that don't change often (polled on demand, not per tick)
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuAdvancedInfo {
+    pub ecc_enabled: Option<bool>,    // None on consumer cards without ECC
+    pub ecc_pending: Option<bool>,    // ECC state after next reboot
+    pub compute_mode: Option<String>, // "Default", "Exclusive Process", "Prohibited"
+}
+
+/// Resizable BAR state from the size of the CPU-visible VRAM window (BAR1)
+#[derive(Debug, Clone, Serialize)]
+pub struct ResizableBarStatus {
+    pub bar1_total: u64,  // Bytes
+    pub vram_total: u64,  // Bytes
+    pub enabled: bool,
+}
+
+/// Without ReBAR the BAR1 window is 256 MiB
+const SMALL_BAR_BYTES: u64 = 256 * 1024 * 1024;
+
+impl ResizableBarStatus {
+    pub fn from_sizes(bar1_total: u64, vram_total: u64) -> Self {
+        Self {
+            bar1_total,
+            vram_total,
+            enabled: bar1_total > SMALL_BAR_BYTES,
+        }
+    }
+}
+
+/// Above this a full get_info call is considered slow enough to hurt the poll loop
+pub const SLOW_NVML_THRESHOLD_MS: f64 = 20.0;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NvmlQueryTiming {
+    pub query: String,
+    pub ms: f64,
+}
+
+/// Timings of the NVML queries used by get_info, for driver diagnostics
+#[derive(Debug, Clone, Serialize)]
+pub struct NvmlLatency {
+    pub queries: Vec<NvmlQueryTiming>,
+    pub get_info_ms: f64,
+    pub slow: bool,
+}
+
+/// GPU energy used over one game session (test launch or session capture)
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionEnergy {
+    pub game: String,
+    pub energy_mj: u64,
+    pub kwh: f64,
+    pub duration_secs: u64,
+}
+
+impl SessionEnergy {
+    /// Build from two total_energy_consumption samples (mJ since driver load)
+    pub fn from_samples(game: &str, start_mj: u64, end_mj: u64, started: Instant) -> Self {
+        let energy_mj = end_mj.saturating_sub(start_mj);
+        Self {
+            game: game.to_string(),
+            energy_mj,
+            kwh: energy_mj as f64 / 3.6e9,
+            duration_secs: started.elapsed().as_secs(),
+        }
+    }
+}
+
+/// Where GPU readings come from
+enum Backend {
+    Nvml {
+        // Leaked so the cached Devices can borrow it for 'static; a GpuMonitor
+        // is built once per process, so this is a one-off, not a per-poll leak
+        nvml: &'static Nvml,
+        devices: Mutex<HashMap<u32, Device<'static>>>, // By NVML index
+    },
+    /// libnvidia-ml failed to load (non-standard path, some driver versions)
+    /// but nvidia-smi works: monitoring only, no clock or power control
+    Smi,
+}
+
 pub struct GpuMonitor {
-    nvml: Arc<Nvml>,
+    backend: Backend,
 }
 
+/// nvidia-smi fields in GpuInfo order, after the index
+const SMI_QUERY: &str = "index,name,temperature.gpu,power.draw,power.limit,utilization.gpu,\
+utilization.encoder,utilization.decoder,memory.used,memory.total,clocks.gr,clocks.mem,fan.speed";
+
 impl GpuMonitor {
-    pub fn new() -> Result<Self, nvml_wrapper::error::NvmlError> {
-        let nvml = Nvml::init()?;
-        Ok(Self {
-            nvml: Arc::new(nvml),
+    pub fn new() -> Result<Self, NvmlError> {
+        let backend = match Nvml::init() {
+            Ok(nvml) => Backend::Nvml {
+                nvml: Box::leak(Box::new(nvml)),
+                devices: Mutex::new(HashMap::new()),
+            },
+            Err(e) => {
+                if smi_query(None).map_or(true, |gpus| gpus.is_empty()) {
+                    return Err(e);
+                }
+                Backend::Smi
+            }
+        };
+        Ok(Self { backend })
+    }
+
+    /// True when readings come from nvidia-smi instead of NVML
+    pub fn is_smi_fallback(&self) -> bool {
+        matches!(self.backend, Backend::Smi)
+    }
+
+    /// Run `f` against the cached handle of GPU `index`, looking it up on first use.
+    /// A lost device (eGPU unplug) clears the cache so the next call re-detects it.
+    fn with_device_at<T>(
+        &self,
+        index: u32,
+        f: impl FnOnce(&mut Device<'static>) -> Result<T, NvmlError>,
+    ) -> Result<T, NvmlError> {
+        let Backend::Nvml { nvml, devices } = &self.backend else {
+            return Err(NvmlError::Uninitialized);
+        };
+        let mut cached = devices.lock().unwrap_or_else(|e| e.into_inner());
+        let device = match cached.entry(index) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(nvml.device_by_index(index)?)
+            }
+        };
+
+        let result = f(device);
+        if matches!(result, Err(NvmlError::GpuLost)) {
+            cached.remove(&index);
+        }
+        result
+    }
+
+    /// Controls (clocks, power limit) and diagnostics act on GPU 0
+    fn with_device<T>(
+        &self,
+        f: impl FnOnce(&mut Device<'static>) -> Result<T, NvmlError>,
+    ) -> Result<T, NvmlError> {
+        self.with_device_at(0, f)
+    }
+
+    /// Number of NVIDIA GPUs NVML (or nvidia-smi) sees
+    pub fn device_count(&self) -> u32 {
+        match &self.backend {
+            Backend::Nvml { nvml, .. } => nvml.device_count().unwrap_or(0),
+            Backend::Smi => smi_query(None).map_or(0, |gpus| gpus.len() as u32),
+        }
+    }
+
+    pub fn get_info(&self, index: u32) -> Result<GpuInfo, String> {
+        if self.is_smi_fallback() {
+            return smi_query(Some(index))?
+                .pop()
+                .ok_or_else(|| "No NVIDIA GPU found".to_string());
+        }
+
+        self.with_device_at(index, |device| {
+            // Every field below falls back to a default, so probe for a lost
+            // device up front rather than reporting a GPU full of zeros
+            let temperature = match device.temperature(TemperatureSensor::Gpu) {
+                Err(NvmlError::GpuLost) => return Err(NvmlError::GpuLost),
+                t => t.unwrap_or(0),
+            };
+
+            let name = device.name().unwrap_or_else(|_| "Unknown GPU".to_string());
+            let power_draw = device.power_usage().unwrap_or(0) as f32 / 1000.0; // mW to W
+            let power_limit = device.power_management_limit().unwrap_or(0) as f32 / 1000.0;
+            let utilization = device.utilization_rates().map(|u| u.gpu).unwrap_or(0);
+            let encoder_utilization = device.encoder_utilization().ok().map(|u| u.utilization);
+            let decoder_utilization = device.decoder_utilization().ok().map(|u| u.utilization);
+            let memory_info = device.memory_info().ok();
+            let memory_used = memory_info.as_ref().map(|m| m.used).unwrap_or(0);
+            let memory_total = memory_info.as_ref().map(|m| m.total).unwrap_or(0);
+            let clock_graphics = device.clock_info(Clock::Graphics).unwrap_or(0);
+            let clock_memory = device.clock_info(Clock::Memory).unwrap_or(0);
+            let fan_speed = device.fan_speed(0).ok();
+
+            Ok(GpuInfo {
+                index,
+                name,
+                temperature,
+                power_draw,
+                power_limit,
+                utilization,
+                encoder_utilization,
+                decoder_utilization,
+                memory_used,
+                memory_total,
+                clock_graphics,
+                clock_memory,
+                fan_speed,
+            })
         })
+        .map_err(nvml_error)
     }
 
-    pub fn get_info(&self) -> Result<GpuInfo, nvml_wrapper::error::NvmlError> {
-        let device = self.nvml.device_by_index(0)?;
-
-        let name = device.name().unwrap_or_else(|_| "Unknown GPU".to_string());
-        let temperature = device.temperature(TemperatureSensor::Gpu).unwrap_or(0);
-        let power_draw = device.power_usage().unwrap_or(0) as f32 / 1000.0; // mW to W
-        let power_limit = device.power_management_limit().unwrap_or(0) as f32 / 1000.0;
-        let utilization = device.utilization_rates().map(|u| u.gpu).unwrap_or(0);
-        let memory_info = device.memory_info().ok();
-        let memory_used = memory_info.as_ref().map(|m| m.used).unwrap_or(0);
-        let memory_total = memory_info.as_ref().map(|m| m.total).unwrap_or(0);
-        let clock_graphics = device.clock_info(Clock::Graphics).unwrap_or(0);
-        let clock_memory = device.clock_info(Clock::Memory).unwrap_or(0);
-        let fan_speed = device.fan_speed(0).ok();
-
-        Ok(GpuInfo {
-            name,
-            temperature,
-            power_draw,
-            power_limit,
-            utilization,
-            memory_used,
-            memory_total,
-            clock_graphics,
-            clock_memory,
-            fan_speed,
+    /// Info for every GPU; one that errors (e.g. fell off the bus) is left out
+    pub fn get_all_gpu_info(&self) -> Vec<GpuInfo> {
+        if self.is_smi_fallback() {
+            return smi_query(None).unwrap_or_default();
+        }
+        (0..self.device_count())
+            .filter_map(|index| self.get_info(index).ok())
+            .collect()
+    }
+
+    /// Time each query get_info performs, then a full get_info call
+    pub fn measure_latency(&self) -> Result<NvmlLatency, String> {
+        fn timed<T>(queries: &mut Vec<NvmlQueryTiming>, query: &str, f: impl FnOnce() -> T) {
+            let start = Instant::now();
+            let _ = f();
+            queries.push(NvmlQueryTiming {
+                query: query.to_string(),
+                ms: start.elapsed().as_secs_f64() * 1000.0,
+            });
+        }
+
+        let queries = self
+            .with_device(|device| {
+                let mut queries = Vec::new();
+
+                timed(&mut queries, "name", || device.name());
+                timed(&mut queries, "temperature", || {
+                    device.temperature(TemperatureSensor::Gpu)
+                });
+                timed(&mut queries, "power_usage", || device.power_usage());
+                timed(&mut queries, "power_limit", || {
+                    device.power_management_limit()
+                });
+                timed(&mut queries, "utilization", || device.utilization_rates());
+                timed(&mut queries, "memory_info", || device.memory_info());
+                timed(&mut queries, "clock_graphics", || {
+                    device.clock_info(Clock::Graphics)
+                });
+                timed(&mut queries, "clock_memory", || {
+                    device.clock_info(Clock::Memory)
+                });
+                timed(&mut queries, "fan_speed", || device.fan_speed(0));
+
+                Ok(queries)
+            })
+            .map_err(nvml_error)?;
+
+        let start = Instant::now();
+        self.get_info(0)?;
+        let get_info_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        Ok(NvmlLatency {
+            queries,
+            get_info_ms,
+            slow: get_info_ms > SLOW_NVML_THRESHOLD_MS,
         })
     }
 
+    /// Energy used since the driver was loaded, in millijoules (Volta and newer)
+    pub fn total_energy_mj(&self) -> Option<u64> {
+        self.with_device(|d| d.total_energy_consumption()).ok()
+    }
+
+    pub fn get_advanced_info(&self) -> Result<GpuAdvancedInfo, String> {
+        self.with_device(|device| {
+            // Consumer GeForce cards report NotSupported for ECC queries
+            let ecc = device.is_ecc_enabled().ok();
+            let compute_mode = device.compute_mode().ok().map(|mode| {
+                match mode {
+                    ComputeMode::Default => "Default",
+                    ComputeMode::ExclusiveThread => "Exclusive Thread",
+                    ComputeMode::Prohibited => "Prohibited",
+                    ComputeMode::ExclusiveProcess => "Exclusive Process",
+                }
+                .to_string()
+            });
+
+            Ok(GpuAdvancedInfo {
+                ecc_enabled: ecc.as_ref().map(|e| e.currently_enabled),
+                ecc_pending: ecc.as_ref().map(|e| e.pending_enabled),
+                compute_mode,
+            })
+        })
+        .map_err(nvml_error)
+    }
+
+    /// Whether resizable BAR is active, from the BAR1 size
+    pub fn get_resizable_bar_status(&self) -> Result<ResizableBarStatus, String> {
+        self.with_device(|device| {
+            let bar1 = device.bar1_memory_info()?;
+            let vram = device.memory_info()?;
+            Ok(ResizableBarStatus::from_sizes(bar1.total, vram.total))
+        })
+        .map_err(nvml_error)
+    }
+
+    /// Lock GPU core clocks to a fixed range (requires root, Volta or newer)
+    pub fn lock_gpu_clocks(&self, min_mhz: u32, max_mhz: u32) -> Result<(), String> {
+        self.with_device(|device| {
+            device.set_gpu_locked_clocks(GpuLockedClocksSetting::Numeric {
+                min_clock_mhz: min_mhz,
+                max_clock_mhz: max_mhz,
+            })
+        })
+        .map_err(clock_error)
+    }
+
+    /// Release a previous clock lock
+    pub fn unlock_gpu_clocks(&self) -> Result<(), String> {
+        self.with_device(|device| device.reset_gpu_locked_clocks())
+            .map_err(clock_error)
+    }
+
+    /// Set the board power limit in watts (requires root)
+    pub fn set_power_limit(&self, watts: u32) -> Result<(), String> {
+        self.with_device(|device| device.set_power_management_limit(watts * 1000))
+            .map_err(power_limit_error)
+    }
+
+    /// Restore the stock board power limit
+    pub fn reset_power_limit(&self) -> Result<(), String> {
+        self.with_device(|device| {
+            let default = device.power_management_limit_default()?;
+            device.set_power_management_limit(default)
+        })
+        .map_err(power_limit_error)
+    }
+
     pub fn get_gpu_name(&self) -> String {
-        self.nvml
-            .device_by_index(0)
-            .and_then(|d| d.name())
-            .unwrap_or_else(|_| "Unknown GPU".to_string())
+        let name = match self.backend {
+            Backend::Nvml { .. } => self.with_device(|d| d.name()).ok(),
+            Backend::Smi => self.get_info(0).ok().map(|info| info.name),
+        };
+        name.unwrap_or_else(|| "Unknown GPU".to_string())
+    }
+
+    /// NVML library version, e.g. "12.570.86.10"
+    pub fn get_nvml_version(&self) -> Option<String> {
+        match &self.backend {
+            Backend::Nvml { nvml, .. } => nvml.sys_nvml_version().ok(),
+            Backend::Smi => None,
+        }
     }
 }
 
+/// Run nvidia-smi for one GPU, or all of them with `index` None
+fn smi_query(index: Option<u32>) -> Result<Vec<GpuInfo>, String> {
+    let mut cmd = Command::new("nvidia-smi");
+    cmd.arg(format!("--query-gpu={}", SMI_QUERY))
+        .arg("--format=csv,noheader,nounits");
+    if let Some(index) = index {
+        cmd.arg("-i").arg(index.to_string());
+    }
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run nvidia-smi: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "nvidia-smi failed: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        ));
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_smi_line)
+        .collect()
+}
+
+/// One `--format=csv,noheader,nounits` line of SMI_QUERY. Unsupported fields
+/// read "[N/A]" or "[Not Supported]" and fall back like the NVML path does.
+fn parse_smi_line(line: &str) -> Result<GpuInfo, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() != 13 {
+        return Err(format!("Unexpected nvidia-smi output: {}", line));
+    }
+    let float = |i: usize| fields[i].parse::<f32>().ok();
+    let int = |i: usize| float(i).map(|v| v.round() as u32);
+    // Memory is reported in MiB
+    let mib = |i: usize| int(i).map_or(0, |v| v as u64 * 1024 * 1024);
+
+    Ok(GpuInfo {
+        index: int(0).ok_or_else(|| format!("Unexpected nvidia-smi output: {}", line))?,
+        name: fields[1].to_string(),
+        temperature: int(2).unwrap_or(0),
+        power_draw: float(3).unwrap_or(0.0),
+        power_limit: float(4).unwrap_or(0.0),
+        utilization: int(5).unwrap_or(0),
+        encoder_utilization: int(6),
+        decoder_utilization: int(7),
+        memory_used: mib(8),
+        memory_total: mib(9),
+        clock_graphics: int(10).unwrap_or(0),
+        clock_memory: int(11).unwrap_or(0),
+        fan_speed: int(12),
+    })
+}
+
+/// NVIDIA GPU generations, oldest first
+pub const GPU_GENERATIONS: &[&str] = &["pascal", "turing", "ampere", "ada", "blackwell"];
+
+/// Generations before Pascal. They are recognized so an old card isn't taken
+/// for one newer than the table, but no template can target them.
+const PRE_PASCAL_GENERATIONS: &[&str] = &["kepler", "maxwell"];
+
+/// Name fragments to generation. Quadro RTX comes before "RTX 40" so the
+/// Turing-based Quadro RTX 4000 doesn't pass for Ada. Only the Pascal Titans
+/// are listed as Pascal; "GTX TITAN" is a Kepler Titan or the Maxwell GTX TITAN X.
+const GPU_SERIES: &[(&str, &str)] = &[
+    ("QUADRO RTX", "turing"),
+    ("RTX 50", "blackwell"),
+    ("RTX 40", "ada"),
+    ("RTX 30", "ampere"),
+    ("RTX A", "ampere"),
+    ("RTX 20", "turing"),
+    ("GTX 16", "turing"),
+    ("TITAN RTX", "turing"),
+    ("GTX 10", "pascal"),
+    ("TITAN XP", "pascal"),
+    ("TITAN X (PASCAL)", "pascal"),
+    ("MX150", "pascal"),
+    ("GTX TITAN X", "maxwell"),
+    ("GTX TITAN", "kepler"),
+    ("GTX 9", "maxwell"),
+    ("GTX 750", "maxwell"),
+    ("GTX 7", "kepler"),
+    ("GTX 6", "kepler"),
+    ("MX1", "maxwell"),
+    ("QUADRO M", "maxwell"),
+    ("TESLA M", "maxwell"),
+    ("QUADRO K", "kepler"),
+    ("TESLA K", "kepler"),
+];
+
+/// Generation of an NVML device name, e.g. "ada" for "NVIDIA GeForce RTX 4080".
+/// Workstation cards like "RTX 6000 Ada Generation" name it outright.
+pub fn gpu_generation(name: &str) -> Option<&'static str> {
+    let name = name.to_uppercase();
+    GPU_GENERATIONS
+        .iter()
+        .find(|generation| name.contains(&generation.to_uppercase()))
+        .copied()
+        .or_else(|| {
+            GPU_SERIES
+                .iter()
+                .find(|(series, _)| name.contains(series))
+                .map(|(_, generation)| *generation)
+        })
+}
+
+/// Whether a generation from gpu_generation predates every template target
+pub fn is_pre_pascal(generation: &str) -> bool {
+    PRE_PASCAL_GENERATIONS.contains(&generation)
+}
+
+/// Position in GPU_GENERATIONS, for "this generation or newer" checks
+pub fn generation_rank(generation: &str) -> Option<usize> {
+    GPU_GENERATIONS.iter().position(|g| *g == generation)
+}
+
+fn nvml_error(e: NvmlError) -> String {
+    match e {
+        NvmlError::GpuLost => {
+            "GPU removed: it fell off the bus or was unplugged, and will be re-detected when it's back"
+                .to_string()
+        }
+        // device_by_index(0) with no GPUs present
+        NvmlError::InvalidArg | NvmlError::NotFound => "No NVIDIA GPU found".to_string(),
+        // Backend::Smi
+        NvmlError::Uninitialized => {
+            "NVML failed to load; only monitoring through nvidia-smi is available".to_string()
+        }
+        e => e.to_string(),
+    }
+}
+
+fn clock_error(e: NvmlError) -> String {
+    match e {
+        NvmlError::NoPermission => {
+            "Permission denied: changing GPU clocks requires root privileges".to_string()
+        }
+        NvmlError::NotSupported => "Clock locking is not supported on this GPU".to_string(),
+        NvmlError::GpuLost
+        | NvmlError::InvalidArg
+        | NvmlError::NotFound
+        | NvmlError::Uninitialized => nvml_error(e),
+        e => format!("Failed to change GPU clocks: {}", e),
+    }
+}
+
+fn power_limit_error(e: NvmlError) -> String {
+    match e {
+        NvmlError::NoPermission => {
+            "Permission denied: changing the power limit requires root privileges".to_string()
+        }
+        NvmlError::NotSupported => "Power limits are not supported on this GPU".to_string(),
+        NvmlError::InvalidArg => "Power limit is outside the range this GPU allows".to_string(),
+        NvmlError::GpuLost | NvmlError::NotFound | NvmlError::Uninitialized => nvml_error(e),
+        e => format!("Failed to change power limit: {}", e),
+    }
+}
+
+/// Finished sessions kept in the energy history
+const MAX_ENERGY_HISTORY: usize = 100;
+
 // Global GPU monitor state
 pub struct GpuMonitorState {
     pub monitor: Option<GpuMonitor>,
+    pub last_session_energy: Option<SessionEnergy>,
+    pub energy_history: VecDeque<SessionEnergy>, // Oldest first
 }
 
 impl GpuMonitorState {
     pub fn new() -> Self {
         let monitor = GpuMonitor::new().ok();
-        Self { monitor }
+        Self {
+            monitor,
+            last_session_energy: None,
+            energy_history: VecDeque::new(),
+        }
+    }
+
+    /// Keep a finished session as the last one and append it to the history
+    pub fn record_session_energy(&mut self, session: SessionEnergy) {
+        if self.energy_history.len() >= MAX_ENERGY_HISTORY {
+            self.energy_history.pop_front();
+        }
+        self.energy_history.push_back(session.clone());
+        self.last_session_energy = Some(session);
     }
 }
 
@@ -83,3 +563,52 @@ pub type SharedGpuState = Arc<RwLock<GpuMonitorState>>;
 pub fn create_gpu_state() -> SharedGpuState {
     Arc::new(RwLock::new(GpuMonitorState::new()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_smi_line() {
+        let info = parse_smi_line(
+            "0, NVIDIA GeForce RTX 4080, 54, 87.32, 320.00, 12, 7, 0, 2431, 16376, 2610, 11201, 35",
+        )
+        .unwrap();
+        assert_eq!(info.index, 0);
+        assert_eq!(info.name, "NVIDIA GeForce RTX 4080");
+        assert_eq!(info.temperature, 54);
+        assert!((info.power_draw - 87.32).abs() < 0.01);
+        assert_eq!(info.encoder_utilization, Some(7));
+        assert_eq!(info.memory_total, 16376 * 1024 * 1024);
+        assert_eq!(info.clock_memory, 11201);
+        assert_eq!(info.fan_speed, Some(35));
+
+        // Laptops report no fan and sometimes no power readings
+        let laptop = parse_smi_line(
+            "1, NVIDIA GeForce RTX 3060 Laptop GPU, 48, [N/A], [N/A], 0, 0, 0, 5, 6144, 210, 405, [Not Supported]",
+        )
+        .unwrap();
+        assert_eq!(laptop.index, 1);
+        assert_eq!(laptop.power_draw, 0.0);
+        assert_eq!(laptop.fan_speed, None);
+
+        assert!(parse_smi_line("No devices were found").is_err());
+    }
+
+    #[test]
+    fn test_session_energy_history_is_bounded() {
+        let mut state = GpuMonitorState {
+            monitor: None,
+            last_session_energy: None,
+            energy_history: VecDeque::new(),
+        };
+        for i in 0..MAX_ENERGY_HISTORY as u64 + 5 {
+            let session = SessionEnergy::from_samples(&format!("game{}", i), 0, i, Instant::now());
+            state.record_session_energy(session);
+        }
+
+        assert_eq!(state.energy_history.len(), MAX_ENERGY_HISTORY);
+        assert_eq!(state.energy_history.front().unwrap().game, "game5");
+        assert_eq!(state.last_session_energy.unwrap().energy_mj, MAX_ENERGY_HISTORY as u64 + 4);
+    }
+}
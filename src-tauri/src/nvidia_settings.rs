@@ -0,0 +1,105 @@
+//! Driver settings changed through nvidia-settings (NV-CONTROL)
+//!
+//! PowerMizer: laptops in particular drop clocks aggressively under
+//! "Adaptive"; forcing "Prefer Maximum Performance" for a game session avoids that.
+//!
+//! G-SYNC Compatible: `AllowVRR` here is driver-wide and lasts for the X
+//! session, while the profile's `nvidia.gsync_allowed` only sets
+//! `__GL_GSYNC_ALLOWED` for the one game. Monitors not on NVIDIA's validated
+//! list still need VRR allowed at the driver level before either does anything.
+
+use std::io::ErrorKind;
+use std::process::Command;
+
+const POWERMIZER_ATTRIBUTE: &str = "[gpu:0]/GPUPowerMizerMode";
+
+/// Mode names accepted in profiles, with their GPUPowerMizerMode values
+pub const POWERMIZER_MODES: &[(&str, u8)] = &[("adaptive", 0), ("max", 1), ("auto", 2)];
+
+fn mode_value(mode: &str) -> Option<u8> {
+    POWERMIZER_MODES
+        .iter()
+        .find(|(name, _)| *name == mode)
+        .map(|(_, value)| *value)
+}
+
+/// Run nvidia-settings for `feature` ("PowerMizer", "VRR", ...), which names
+/// it in errors
+fn run_nvidia_settings(feature: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("nvidia-settings")
+        .args(args)
+        .output()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => "nvidia-settings is not installed".to_string(),
+            _ => format!("Failed to run nvidia-settings: {}", e),
+        })?;
+
+    // nvidia-settings exits 0 on many failures; errors only show up in the output
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let combined = format!("{}{}", stdout, stderr);
+    if !output.status.success() || combined.contains("ERROR") {
+        return Err(settings_error(feature, &combined));
+    }
+    Ok(stdout)
+}
+
+fn settings_error(feature: &str, output: &str) -> String {
+    if output.contains("NV-CONTROL") || output.contains("Unable to find display") {
+        format!(
+            "nvidia-settings can't reach the NVIDIA X driver; {} needs an X11 or XWayland session",
+            feature
+        )
+    } else if output.contains("read-only") || output.contains("not available") {
+        format!("{} can't be changed on this GPU or driver", feature)
+    } else {
+        format!("nvidia-settings failed to change {}: {}", feature, output.trim())
+    }
+}
+
+/// Current PowerMizer mode name ("adaptive", "max", "auto")
+pub fn get_powermizer_mode() -> Result<String, String> {
+    let output = run_nvidia_settings("PowerMizer", &["-t", "-q", POWERMIZER_ATTRIBUTE])?;
+    let value: u8 = output
+        .trim()
+        .parse()
+        .map_err(|_| format!("Unexpected PowerMizer value '{}'", output.trim()))?;
+
+    POWERMIZER_MODES
+        .iter()
+        .find(|(_, v)| *v == value)
+        .map(|(name, _)| name.to_string())
+        .ok_or_else(|| format!("Unknown PowerMizer mode {}", value))
+}
+
+/// Set the PowerMizer preferred mode for GPU 0
+pub fn set_powermizer_mode(mode: &str) -> Result<(), String> {
+    let value = mode_value(mode).ok_or_else(|| {
+        format!(
+            "Unknown PowerMizer mode '{}' (expected auto, max or adaptive)",
+            mode
+        )
+    })?;
+    let assignment = format!("{}={}", POWERMIZER_ATTRIBUTE, value);
+    run_nvidia_settings("PowerMizer", &["-a", &assignment]).map(|_| ())
+}
+
+/// Allow or forbid VRR (G-SYNC / G-SYNC Compatible) driver-wide
+pub fn set_gsync_allowed(enabled: bool) -> Result<(), String> {
+    let assignment = format!("AllowVRR={}", u8::from(enabled));
+    run_nvidia_settings("G-SYNC (AllowVRR)", &["-a", &assignment]).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_error_names_the_feature() {
+        let error = settings_error("G-SYNC (AllowVRR)", "ERROR: attribute 'AllowVRR' is read-only");
+        assert_eq!(error, "G-SYNC (AllowVRR) can't be changed on this GPU or driver");
+
+        let error = settings_error("PowerMizer", "ERROR: NV-CONTROL extension not present");
+        assert!(error.ends_with("PowerMizer needs an X11 or XWayland session"));
+    }
+}
@@ -0,0 +1,122 @@
+//! Rescue from a game that left the desktop in a bad state
+//!
+//! A snapshot of the monitor layout and PowerMizer mode is taken when the app
+//! starts. panic_restore puts both back, resets GPU clocks and the power limit
+//! and switches LACT to its default profile, whether or not a launch is being
+//! tracked. Fan curves live in the LACT profile, so that also resets the fans.
+//! Every step is idempotent, so calling it with nothing changed is harmless.
+
+use crate::lact::set_lact_profile;
+use crate::nvidia::SharedGpuState;
+use crate::nvidia_settings;
+use crate::profiles::is_lact_available;
+use crate::screen::{self, Monitor};
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Desktop state to return to
+#[derive(Debug, Clone, Default)]
+struct Snapshot {
+    monitors: Vec<Monitor>,
+    powermizer_mode: Option<String>,
+}
+
+/// LACT's built-in profile: stock clocks and power, automatic fans
+const LACT_DEFAULT_PROFILE: &str = "Default";
+
+static SNAPSHOT: Mutex<Option<Snapshot>> = Mutex::new(None);
+
+/// What panic_restore managed to put back
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PanicRestoreReport {
+    pub restored: Vec<String>,
+    pub failed: Vec<String>, // "step: reason"
+}
+
+impl PanicRestoreReport {
+    fn record(&mut self, step: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.restored.push(step.to_string()),
+            Err(e) => self.failed.push(format!("{}: {}", step, e)),
+        }
+    }
+}
+
+/// A layout with no monitor on would black out every screen
+fn usable_layout(monitors: &[Monitor]) -> bool {
+    monitors.iter().any(|m| m.active)
+}
+
+/// Remember the current desktop state; called once at startup
+pub fn take_snapshot() {
+    let snapshot = Snapshot {
+        monitors: screen::list_monitors()
+            .ok()
+            .filter(|m| usable_layout(m))
+            .unwrap_or_default(),
+        powermizer_mode: nvidia_settings::get_powermizer_mode().ok(),
+    };
+    if let Ok(mut guard) = SNAPSHOT.lock() {
+        *guard = Some(snapshot);
+    }
+}
+
+/// Restore monitors, colors, GPU clocks, power limit, PowerMizer and LACT
+pub async fn panic_restore(gpu_state: SharedGpuState) -> PanicRestoreReport {
+    let snapshot = SNAPSHOT
+        .lock()
+        .ok()
+        .and_then(|s| s.clone())
+        .unwrap_or_default();
+    let mut report = PanicRestoreReport::default();
+
+    if usable_layout(&snapshot.monitors) {
+        for (name, result) in screen::apply_each_monitor(&snapshot.monitors) {
+            report.record(&format!("monitor {}", name), result);
+        }
+    }
+    report.record("monitor colors", screen::reset_all_monitor_colors());
+
+    if let Some(monitor) = &gpu_state.read().await.monitor {
+        report.record("GPU clocks", monitor.unlock_gpu_clocks());
+        report.record("power limit", monitor.reset_power_limit());
+    }
+
+    if let Some(mode) = &snapshot.powermizer_mode {
+        report.record("PowerMizer", nvidia_settings::set_powermizer_mode(mode));
+    }
+    if is_lact_available() {
+        report.record("LACT profile", set_lact_profile(LACT_DEFAULT_PROFILE));
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_needs_an_active_monitor() {
+        let monitor = |active| Monitor {
+            id: 0,
+            name: "DP-1".to_string(),
+            description: String::new(),
+            width: 2560,
+            height: 1440,
+            refresh_rate: 144.0,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            active,
+            focused: false,
+        };
+        assert!(usable_layout(&[monitor(false), monitor(true)]));
+        assert!(!usable_layout(&[monitor(false)]));
+        assert!(!usable_layout(&[]));
+
+        let mut report = PanicRestoreReport::default();
+        report.record("power limit", Err("Permission denied".to_string()));
+        assert_eq!(report.failed, vec!["power limit: Permission denied"]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Pre-launch sanity checks, aggregated into one call for the UI
+//!
+//! Unlike check_profile_warnings, these look at the game and the system the
+//! launch happens on: whether the game ships DLSS at all, whether settings
+//! suit a 32-bit executable, whether the gamescope output fits the monitor,
+//! and whether every wrapper is installed. All of them are warnings; none
+//! blocks the launch.
+
+use crate::game_settings;
+use crate::games::{detect_exe_bitness, GameDetector};
+use crate::profiles::{self, GameProfile, ProfileManager};
+use crate::system;
+
+/// Settings that make the DLSS overrides do something (the indicator alone doesn't)
+fn dlss_overrides_enabled(profile: &GameProfile) -> bool {
+    let d = &profile.dlss;
+    d.upgrade
+        || d.ngx_updater
+        || d.sr_override
+        || d.rr_override
+        || d.fg_override
+        || d.sr_preset.is_some()
+        || d.rr_preset.is_some()
+        || d.fg_multi_frame.is_some()
+}
+
+/// Warn only when both sources agree the game has no DLSS: no nvngx_dlss.dll
+/// in the install dir and a wiki page listing upscalers without DLSS
+fn dlss_warning(dll_found: bool, wiki_upscalers: Option<&[String]>) -> Option<String> {
+    let wiki_has_dlss = wiki_upscalers?.iter().any(|u| u == "DLSS");
+    (!dll_found && !wiki_has_dlss).then(|| {
+        "DLSS overrides are enabled, but the game ships no nvngx_dlss.dll and PCGamingWiki lists no DLSS support"
+            .to_string()
+    })
+}
+
+/// Program a wrapper command runs, e.g. "lact" for `lact cli profile set "x" &&`
+fn wrapper_program(wrapper: &[String]) -> Option<&str> {
+    wrapper.first()?.split_whitespace().next()
+}
+
+/// Sanity warnings for launching `profile`; `steam_appid` defaults to the profile's
+pub async fn precheck_launch(
+    manager: &ProfileManager,
+    profile: &GameProfile,
+    steam_appid: Option<u32>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if let Some(appid) = steam_appid
+        .or(profile.steam_appid)
+        .filter(|_| dlss_overrides_enabled(profile))
+    {
+        let dll_found = GameDetector::find_steam_install_path(appid)
+            .and_then(|dir| GameDetector::find_executable(&dir, "nvngx_dlss.dll"))
+            .is_some();
+        let upscalers = match dll_found {
+            true => None,
+            false => game_settings::detect_game_upscalers(appid).await,
+        };
+        warnings.extend(dlss_warning(dll_found, upscalers.as_deref()));
+    }
+
+    // Finding the executable can walk the whole install dir, so only here
+    if let Some(bitness) =
+        profiles::profile_executable(profile).and_then(|exe| detect_exe_bitness(&exe))
+    {
+        warnings.extend(profiles::bitness_warnings(profile, &bitness));
+    }
+
+    let gs = &profile.wrappers.gamescope;
+    if gs.enabled && (gs.width.is_some() || gs.dsr_enabled) {
+        if let Some(monitor) = profiles::gamescope_target_monitor(profile) {
+            warnings.extend(profiles::validate_gamescope_resolution(profile, &monitor));
+        }
+    }
+
+    for wrapper in manager.build_wrappers(profile) {
+        if let Some(program) = wrapper_program(&wrapper) {
+            if !system::is_tool_available(program) {
+                warnings.push(format!("Wrapper '{}' is not installed", program));
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dlss_warning_needs_both_sources() {
+        let without = vec!["FSR".to_string()];
+        let with = vec!["DLSS".to_string(), "FSR".to_string()];
+
+        assert!(dlss_warning(false, Some(&without)).is_some());
+        assert!(dlss_warning(false, Some(&with)).is_none());
+        assert!(dlss_warning(true, Some(&without)).is_none());
+        // Unknown wiki status is not evidence
+        assert!(dlss_warning(false, None).is_none());
+
+        let lact = vec!["lact cli profile set \"Quiet\" &&".to_string()];
+        assert_eq!(wrapper_program(&lact), Some("lact"));
+        assert!(!dlss_overrides_enabled(&GameProfile::default()));
+    }
+}
@@ -0,0 +1,424 @@
+//! Wine prefix discovery for non-Steam games
+//!
+//! Steam prefixes live under compatdata; Lutris, Bottles, Faugus and plain
+//! Wine keep theirs elsewhere. This scans the usual locations so save-path
+//! and DLL inspection can target those games too.
+
+use crate::games::{Game, GameDetector, GameSource};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A detected Wine prefix
+#[derive(Debug, Clone, Serialize)]
+pub struct PrefixInfo {
+    pub path: PathBuf,
+    pub source: String,          // "Wine", "Games", "Lutris", "Bottles", "Faugus"
+    pub arch: Option<String>,    // "win64" or "win32", from system.reg
+    pub version: Option<String>, // Proton/Wine runner that created or last updated it
+}
+
+/// Scan known prefix locations
+pub fn list_wine_prefixes() -> Vec<PrefixInfo> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let data_dir = dirs::data_dir().unwrap_or_else(|| home.join(".local/share"));
+    let config_dir = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+
+    let mut candidates: Vec<(PathBuf, &str)> = vec![(home.join(".wine"), "Wine")];
+
+    for (root, source) in [
+        (home.join("Games"), "Games"),
+        (home.join("Faugus"), "Faugus"),
+        (data_dir.join("bottles/bottles"), "Bottles"),
+        (
+            home.join(".var/app/com.usebottles.bottles/data/bottles/bottles"),
+            "Bottles",
+        ),
+    ] {
+        for dir in subdirs(&root) {
+            // Lutris installs into ~/Games/<game>/ with the prefix nested one level down
+            candidates.extend(subdirs(&dir).into_iter().map(|d| (d, source)));
+            candidates.push((dir, source));
+        }
+    }
+
+    candidates.extend(
+        lutris_prefix_paths(&config_dir.join("lutris/games"))
+            .into_iter()
+            .map(|p| (p, "Lutris")),
+    );
+
+    let mut seen = HashSet::new();
+    let mut prefixes: Vec<PrefixInfo> = candidates
+        .into_iter()
+        .filter(|(path, _)| is_wine_prefix(path))
+        .filter(|(path, _)| seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone())))
+        .map(|(path, source)| PrefixInfo {
+            arch: prefix_arch(&path),
+            version: prefix_version(&path),
+            source: source.to_string(),
+            path,
+        })
+        .collect();
+
+    prefixes.sort_by(|a, b| a.path.cmp(&b.path));
+    prefixes
+}
+
+/// Folders Wine (or common runtimes) create under Program Files on its own
+const STOCK_PROGRAM_DIRS: &[&str] = &[
+    "common files",
+    "internet explorer",
+    "windows media player",
+    "windows nt",
+    "windowspowershell",
+    "microsoft.net",
+    "dotnet",
+    "windows defender",
+    "windows mail",
+    "windows photo viewer",
+    "reference assemblies",
+    "msbuild",
+];
+
+/// Prefixes that no launcher's games live in, presented as games to profile.
+/// Lutris and Bottles prefixes are left out since those launchers manage them.
+pub fn detect_orphan_prefixes() -> Vec<Game> {
+    let known: Vec<PathBuf> = GameDetector::detect_all_games()
+        .into_iter()
+        .flat_map(|g| [g.install_path, g.executable])
+        .flatten()
+        .collect();
+
+    list_wine_prefixes()
+        .into_iter()
+        .filter(|p| p.source != "Lutris" && p.source != "Bottles")
+        .filter(|p| !known.iter().any(|k| k.starts_with(&p.path) || p.path.starts_with(k)))
+        .map(|p| {
+            let program_dir = guess_program_dir(&p.path);
+            let name = program_dir
+                .as_ref()
+                .and_then(|d| d.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| prefix_display_name(&p.path));
+            Game {
+                id: format!("wine:{}", p.path.display()),
+                name,
+                executable: program_dir.as_deref().and_then(guess_executable),
+                source: GameSource::Wine,
+                install_path: Some(p.path),
+                icon_url: None,
+            }
+        })
+        .collect()
+}
+
+/// A Proton prefix in steamapps/compatdata whose game is no longer installed
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanPrefix {
+    pub steam_appid: u32,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+/// Appid of a compatdata entry. Non-Steam shortcuts get appids with the top
+/// bit set and have no app manifest, so they never count as orphans.
+fn compatdata_appid(dir_name: &str) -> Option<u32> {
+    dir_name
+        .parse::<u32>()
+        .ok()
+        .filter(|&id| id != 0 && id < 0x8000_0000)
+}
+
+/// compatdata prefixes across all Steam libraries
+fn compatdata_dirs() -> Vec<(u32, PathBuf)> {
+    GameDetector::get_steam_library_paths()
+        .into_iter()
+        .flat_map(|lib| subdirs(&lib.join("steamapps/compatdata")))
+        .filter_map(|dir| {
+            let appid = compatdata_appid(&dir.file_name()?.to_string_lossy())?;
+            Some((appid, dir))
+        })
+        .collect()
+}
+
+fn installed_steam_appids() -> HashSet<u32> {
+    GameDetector::detect_steam_games()
+        .into_iter()
+        .filter_map(|g| g.id.parse().ok())
+        .collect()
+}
+
+/// compatdata prefixes with no installed app manifest, largest first
+pub fn list_orphaned_compatdata() -> Vec<OrphanPrefix> {
+    let installed = installed_steam_appids();
+    let mut orphans: Vec<OrphanPrefix> = compatdata_dirs()
+        .into_iter()
+        .filter(|(appid, _)| !installed.contains(appid))
+        .map(|(steam_appid, path)| OrphanPrefix {
+            steam_appid,
+            size_bytes: crate::shader_cache::dir_size(&path),
+            path,
+        })
+        .collect();
+
+    orphans.sort_by_key(|o| std::cmp::Reverse(o.size_bytes));
+    orphans
+}
+
+/// Delete an orphaned compatdata prefix (saves included); returns bytes freed.
+/// Refuses prefixes of installed games. `path` and `confirm_size_bytes` are
+/// the entry the user was shown by list_orphaned_compatdata, one library's
+/// prefix at a time; nothing is deleted unless its size still matches.
+pub fn delete_compatdata(steam_appid: u32, path: &Path, confirm_size_bytes: u64) -> Result<u64, String> {
+    if installed_steam_appids().contains(&steam_appid) {
+        return Err(format!(
+            "App {} is still installed; uninstall it from Steam instead",
+            steam_appid
+        ));
+    }
+
+    let listed = compatdata_dirs()
+        .into_iter()
+        .any(|(id, dir)| id == steam_appid && dir == path);
+    if !listed {
+        return Err(format!(
+            "{} is not a compatdata prefix of app {}",
+            path.display(),
+            steam_appid
+        ));
+    }
+    remove_confirmed(path, confirm_size_bytes)
+}
+
+/// Remove `path` only if its size is the one the user confirmed
+fn remove_confirmed(path: &Path, confirm_size_bytes: u64) -> Result<u64, String> {
+    let size = crate::shader_cache::dir_size(path);
+    if size != confirm_size_bytes {
+        return Err(format!(
+            "Prefix is now {} bytes, not the confirmed {}; list it again before deleting",
+            size, confirm_size_bytes
+        ));
+    }
+
+    fs::remove_dir_all(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    Ok(size)
+}
+
+/// The single non-stock folder under Program Files, if there's exactly one
+fn guess_program_dir(prefix: &Path) -> Option<PathBuf> {
+    let drive_c = prefix.join("drive_c");
+    let candidates: Vec<PathBuf> = ["Program Files", "Program Files (x86)"]
+        .iter()
+        .flat_map(|pf| subdirs(&drive_c.join(pf)))
+        .filter(|d| {
+            d.file_name()
+                .map(|n| !STOCK_PROGRAM_DIRS.contains(&n.to_string_lossy().to_lowercase().as_str()))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    match candidates.as_slice() {
+        [only] => Some(only.clone()),
+        _ => None,
+    }
+}
+
+/// The only plausible game .exe in the folder or one level below it
+fn guess_executable(dir: &Path) -> Option<PathBuf> {
+    let is_game_exe = |p: &PathBuf| {
+        let name = p
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        name.ends_with(".exe")
+            && !["unins", "setup", "crash", "redist", "vc_redist", "dxsetup"]
+                .iter()
+                .any(|skip| name.starts_with(skip))
+    };
+
+    let files = |d: &Path| -> Vec<PathBuf> {
+        fs::read_dir(d)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file())
+            .collect()
+    };
+
+    let mut exes: Vec<PathBuf> = files(dir).into_iter().filter(is_game_exe).collect();
+    if exes.is_empty() {
+        exes = subdirs(dir)
+            .iter()
+            .flat_map(|d| files(d))
+            .filter(is_game_exe)
+            .collect();
+    }
+
+    match exes.as_slice() {
+        [only] => Some(only.clone()),
+        _ => None,
+    }
+}
+
+/// Steam-style layouts nest the prefix as `<game>/pfx`; name it after the parent
+fn prefix_display_name(path: &Path) -> String {
+    let dir = if path.ends_with("pfx") {
+        path.parent().unwrap_or(path)
+    } else {
+        path
+    };
+    dir.file_name()
+        .map(|n| n.to_string_lossy().trim_start_matches('.').to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+fn is_wine_prefix(path: &Path) -> bool {
+    path.join("system.reg").is_file() && path.join("drive_c").is_dir()
+}
+
+/// Wine records the prefix architecture as `#arch=win64` near the top of system.reg
+fn prefix_arch(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path.join("system.reg")).ok()?;
+    content
+        .lines()
+        .take(10)
+        .find_map(|l| l.strip_prefix("#arch="))
+        .map(|a| a.trim().to_string())
+}
+
+fn prefix_version(path: &Path) -> Option<String> {
+    // Proton (and umu) write the version that last touched the prefix; for
+    // Steam-style layouts the prefix is the `pfx` child of that directory
+    let mut version_files = vec![path.join("version")];
+    if let Some(parent) = path.parent().filter(|_| path.ends_with("pfx")) {
+        version_files.push(parent.join("version"));
+    }
+    let proton_version = version_files
+        .into_iter()
+        .find_map(|p| fs::read_to_string(p).ok())
+        .and_then(|v| v.lines().next().map(|l| l.trim().to_string()))
+        .filter(|v| !v.is_empty());
+    if proton_version.is_some() {
+        return proton_version;
+    }
+
+    // Bottles stores its runner in bottle.yml
+    let bottle = fs::read_to_string(path.join("bottle.yml")).ok()?;
+    Regex::new(r"(?m)^Runner:\s*(\S+)")
+        .ok()?
+        .captures(&bottle)
+        .map(|c| c[1].to_string())
+}
+
+/// Prefix paths from Lutris game configs (`game: prefix: ...`)
+fn lutris_prefix_paths(games_dir: &Path) -> Vec<PathBuf> {
+    let Ok(re) = Regex::new(r"(?m)^\s+prefix:\s*(.+)$") else {
+        return Vec::new();
+    };
+
+    fs::read_dir(games_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| {
+            e.path()
+                .extension()
+                .map(|ext| ext == "yml")
+                .unwrap_or(false)
+        })
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|content| {
+            re.captures(&content).map(|c| {
+                c[1].trim()
+                    .trim_matches(|q| q == '\'' || q == '"')
+                    .to_string()
+            })
+        })
+        .map(|p| match p.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+            None => PathBuf::from(p),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_prefix_arch_and_version() {
+        let prefix = std::env::temp_dir().join("unvcpfl-test-prefix");
+        fs::remove_dir_all(&prefix).ok();
+        fs::create_dir_all(prefix.join("drive_c")).unwrap();
+        fs::write(
+            prefix.join("system.reg"),
+            "WINE REGISTRY Version 2\n;; All keys relative to \\\\Machine\n\n#arch=win64\n",
+        )
+        .unwrap();
+        fs::write(prefix.join("version"), "GE-Proton9-20\n").unwrap();
+
+        assert!(is_wine_prefix(&prefix));
+        assert_eq!(prefix_arch(&prefix).as_deref(), Some("win64"));
+        assert_eq!(prefix_version(&prefix).as_deref(), Some("GE-Proton9-20"));
+    }
+
+    #[test]
+    fn test_guesses_game_from_program_files() {
+        let prefix = std::env::temp_dir().join("unvcpfl-test-orphan/pfx");
+        fs::remove_dir_all(prefix.parent().unwrap()).ok();
+        let program_files = prefix.join("drive_c/Program Files");
+        for dir in ["Common Files", "Internet Explorer", "Some Game/bin"] {
+            fs::create_dir_all(program_files.join(dir)).unwrap();
+        }
+        for exe in ["Some Game/bin/SomeGame.exe", "Some Game/unins000.exe"] {
+            fs::write(program_files.join(exe), "").unwrap();
+        }
+
+        let dir = guess_program_dir(&prefix).unwrap();
+        assert!(dir.ends_with("Some Game"));
+        assert_eq!(
+            guess_executable(&dir),
+            Some(program_files.join("Some Game/bin/SomeGame.exe"))
+        );
+        assert_eq!(prefix_display_name(&prefix), "unvcpfl-test-orphan");
+    }
+
+    #[test]
+    fn test_compatdata_appid_skips_shortcuts() {
+        assert_eq!(compatdata_appid("1091500"), Some(1091500));
+        assert_eq!(compatdata_appid("3228089301"), None); // Non-Steam shortcut
+        assert_eq!(compatdata_appid("0"), None);
+        assert_eq!(compatdata_appid("pfx"), None);
+    }
+
+    #[test]
+    fn test_compatdata_delete_needs_confirmed_size() {
+        let prefix = std::env::temp_dir().join("unvcpfl-test-compatdata/1091500");
+        fs::remove_dir_all(prefix.parent().unwrap()).ok();
+        fs::create_dir_all(prefix.join("pfx")).unwrap();
+        fs::write(prefix.join("pfx/user.reg"), "0123456789").unwrap();
+
+        assert!(remove_confirmed(&prefix, 0).is_err());
+        assert!(prefix.exists());
+
+        let size = crate::shader_cache::dir_size(&prefix);
+        assert_eq!(remove_confirmed(&prefix, size), Ok(size));
+        assert!(!prefix.exists());
+    }
+}
@@ -1,7 +1,19 @@
+use crate::desktop::idle_inhibit_wrapper;
+use crate::games::{Game, GameDetector, GameSource};
+use crate::nvidia::{
+    generation_rank, gpu_generation, is_pre_pascal, ResizableBarStatus, GPU_GENERATIONS,
+};
+use crate::nvidia_settings::POWERMIZER_MODES;
+use crate::screen::{
+    self, detect_compositor, integer_scale_factor, shell_quote, Compositor, Monitor,
+    GAMMA_RANGE, TEMPERATURE_RANGE,
+};
+use crate::system::{check_gamescope_hdr_support, is_tool_available};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DlssSettings {
@@ -24,11 +36,52 @@ pub struct DlssSettings {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DxvkSettings {
-    pub hud: Option<String>,
+    pub hud: Option<String>, // Raw DXVK_HUD value; overrides the hud_* toggles when set
     #[serde(default)]
     pub nvapi: bool,
     #[serde(default)]
     pub async_compile: bool,
+    #[serde(default)]
+    pub hud_fps: bool,
+    #[serde(default)]
+    pub hud_frametimes: bool,
+    #[serde(default)]
+    pub hud_gpuload: bool,
+    #[serde(default)]
+    pub hud_memory: bool,
+    #[serde(default)]
+    pub hud_devinfo: bool,
+    #[serde(default)]
+    pub hud_version: bool,
+    #[serde(default)]
+    pub hud_api: bool,
+    #[serde(default)]
+    pub hud_compiler: bool,
+}
+
+impl DxvkSettings {
+    /// DXVK_HUD value: the raw `hud` string if set, else built from the toggles
+    pub fn hud_value(&self) -> Option<String> {
+        if let Some(hud) = self.hud.as_ref().filter(|h| !h.trim().is_empty()) {
+            return Some(hud.clone());
+        }
+
+        let items: Vec<&str> = [
+            (self.hud_devinfo, "devinfo"),
+            (self.hud_fps, "fps"),
+            (self.hud_frametimes, "frametimes"),
+            (self.hud_gpuload, "gpuload"),
+            (self.hud_memory, "memory"),
+            (self.hud_api, "api"),
+            (self.hud_version, "version"),
+            (self.hud_compiler, "compiler"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, item)| enabled.then_some(item))
+        .collect();
+
+        (!items.is_empty()).then(|| items.join(","))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -58,6 +111,18 @@ pub struct NvidiaSettings {
     pub prime: bool,
     #[serde(default)]
     pub smooth_motion: bool, // RTX 40/50 only - NVPRESENT_ENABLE_SMOOTH_MOTION
+    #[serde(default)]
+    pub max_frames_allowed: Option<u32>,        // __GL_MaxFramesAllowed (1-4), lower = less latency
+    #[serde(default)]
+    pub shader_disk_cache: Option<bool>,        // __GL_SHADER_DISK_CACHE
+    #[serde(default)]
+    pub shader_disk_cache_path: Option<String>, // __GL_SHADER_DISK_CACHE_PATH
+    #[serde(default)]
+    pub powermizer_mode: Option<String>,        // "auto", "max", "adaptive"; restored after exit
+    #[serde(default)]
+    pub gsync_allowed: Option<bool>,            // __GL_GSYNC_ALLOWED for this game only
+    #[serde(default)]
+    pub offload_target: Option<String>,         // "igpu" or "nvidia" in a hybrid setup; overrides prime
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -70,6 +135,12 @@ pub struct ProtonSettings {
     pub enable_hdr: bool, // PROTON_ENABLE_HDR=1
     #[serde(default)]
     pub integer_scaling: bool, // WINE_FULLSCREEN_INTEGER_SCALING=1
+    #[serde(default)]
+    pub use_umu: bool, // Launch non-Steam games through umu-run
+    pub version: Option<String>, // Proton build for umu, e.g. "GE-Proton9-20" (PROTONPATH)
+    pub force_display_server: Option<String>, // "x11" (XWayland) or "wayland"
+    #[serde(default)]
+    pub ld_preload: Vec<PathBuf>, // Libraries joined into LD_PRELOAD
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -93,6 +164,7 @@ pub struct GamescopeSettings {
     pub dsr_width: Option<u32>,
     pub dsr_height: Option<u32>,
     pub upscale_filter: Option<String>,
+    pub scaling_mode: Option<String>, // -S: how the image maps to the output, e.g. "integer"
     pub fsr_sharpness: Option<u32>,
     #[serde(default)]
     pub fullscreen: bool,
@@ -100,11 +172,14 @@ pub struct GamescopeSettings {
     pub borderless: bool,
     #[serde(default)]
     pub vrr: bool,
-    pub framelimit: Option<u32>,
+    pub framelimit: Option<u32>,           // -r: nested refresh cap (0 = uncapped)
+    pub unfocused_framelimit: Option<u32>, // -o: cap while the window is unfocused
     #[serde(default)]
     pub mangoapp: bool,
     #[serde(default)]
     pub hdr: bool,
+    #[serde(default)]
+    pub extra_args: Vec<String>, // Passed verbatim before `--`
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -117,7 +192,7 @@ pub struct MangoHudSettings {
     pub fps_limiter_mode: Option<String>, // "early", "late"
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WrapperSettings {
     #[serde(default)]
     pub mangohud: MangoHudSettings,
@@ -134,10 +209,28 @@ pub struct WrapperSettings {
     pub lact_profile: Option<String>, // LACT GPU profile name
     #[serde(default = "default_true")]
     pub lact_restore_after_exit: bool, // Restore previous LACT profile after game exit
+    #[serde(default)]
+    pub inhibit_idle: bool, // Keep the screen from blanking while the game runs
+}
+
+impl Default for WrapperSettings {
+    fn default() -> Self {
+        Self {
+            mangohud: MangoHudSettings::default(),
+            gamemode: false,
+            game_performance: false,
+            dlss_swapper: false,
+            gamescope: GamescopeSettings::default(),
+            frame_limiter: FrameLimiterSettings::default(),
+            lact_profile: None,
+            lact_restore_after_exit: true,
+            inhibit_idle: false,
+        }
+    }
 }
 
 /// Settings for per-game screen/monitor configuration (Hyprland/Sway)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScreenSettings {
     pub target_monitor: Option<String>,      // Monitor name for game (e.g., "DP-1")
     #[serde(default)]
@@ -146,6 +239,73 @@ pub struct ScreenSettings {
     pub disable_other_monitors: bool,        // Turn off other monitors during gameplay
     #[serde(default = "default_true")]
     pub restore_monitors_after_exit: bool,   // Restore monitors after game exit
+    pub target_workspace: Option<String>,    // Hyprland workspace for the game (e.g., "5", "name:games")
+    #[serde(default)]
+    pub night_mode: bool,                    // Warm/dim the target monitor (or all) while the game runs
+    pub night_temperature: Option<u32>,      // Kelvin, 1000-6500 (4000 if unset)
+    pub night_gamma: Option<f32>,            // 0.5-2.0 (1.0 if unset)
+    #[serde(default)]
+    pub allow_tearing: bool,                 // Hyprland "immediate" rule / gamescope immediate flips
+}
+
+impl Default for ScreenSettings {
+    fn default() -> Self {
+        Self {
+            target_monitor: None,
+            fullscreen_on_target: false,
+            disable_other_monitors: false,
+            restore_monitors_after_exit: true,
+            target_workspace: None,
+            night_mode: false,
+            night_temperature: None,
+            night_gamma: None,
+            allow_tearing: false,
+        }
+    }
+}
+
+/// Effects built into vkBasalt
+pub const VKBASALT_EFFECTS: &[&str] = &["cas", "dls", "fxaa", "smaa", "lut"];
+
+/// vkBasalt post-processing layer settings
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VkBasaltSettings {
+    #[serde(default)]
+    pub enabled: bool,                  // ENABLE_VKBASALT=1
+    #[serde(default)]
+    pub effects: Vec<String>,           // Applied in order, e.g. ["cas", "smaa"]
+    pub cas_sharpness: Option<f32>,     // casSharpness (-1.0 to 1.0)
+}
+
+impl VkBasaltSettings {
+    /// Enabled with at least one effect; an empty `effects =` line is a broken config
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.effects.is_empty()
+    }
+}
+
+/// Process priority applied to the game via `nice` / `ionice` wrappers
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CpuSettings {
+    pub nice: Option<i32>,          // -20 (highest) to 19; negative values need privileges
+    pub ionice_class: Option<u8>,   // 0 none, 1 realtime, 2 best-effort, 3 idle
+}
+
+/// Settings for per-game audio output switching
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioSettings {
+    pub target_sink: Option<String>,         // Sink name from `pactl list short sinks`
+    #[serde(default = "default_true")]
+    pub restore_sink_after_exit: bool,       // Restore previous default sink after game exit
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            target_sink: None,
+            restore_sink_after_exit: true,
+        }
+    }
 }
 
 fn default_true() -> bool {
@@ -158,6 +318,8 @@ pub struct GameProfile {
     pub description: Option<String>,       // User-provided description
     #[serde(default)]
     pub is_template: bool,                  // True if this is a reusable template, not game-bound
+    #[serde(default)]
+    pub min_gpu_generation: Option<String>, // Oldest GPU generation a template suits, e.g. "ada"
     pub executable_match: Option<String>,
     pub steam_appid: Option<u32>,
 
@@ -175,6 +337,12 @@ pub struct GameProfile {
     pub wrappers: WrapperSettings,
     #[serde(default)]
     pub screen: ScreenSettings,
+    #[serde(default)]
+    pub audio: AudioSettings,
+    #[serde(default)]
+    pub vkbasalt: VkBasaltSettings,
+    #[serde(default)]
+    pub cpu: CpuSettings,
 
     #[serde(default)]
     pub custom_env: HashMap<String, String>,
@@ -187,6 +355,7 @@ impl Default for GameProfile {
             name: String::new(),
             description: None,
             is_template: false,
+            min_gpu_generation: None,
             executable_match: None,
             steam_appid: None,
             dlss: DlssSettings::default(),
@@ -196,12 +365,76 @@ impl Default for GameProfile {
             proton: ProtonSettings::default(),
             wrappers: WrapperSettings::default(),
             screen: ScreenSettings::default(),
+            audio: AudioSettings::default(),
+            vkbasalt: VkBasaltSettings::default(),
+            cpu: CpuSettings::default(),
             custom_env: HashMap::new(),
             custom_args: None,
         }
     }
 }
 
+/// A single setting that differs from the baseline profile
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldDiff {
+    pub field: String,              // Dotted path, e.g. "wrappers.gamescope.width"
+    pub value: serde_json::Value,
+    pub baseline: serde_json::Value,
+}
+
+/// Compact profile overview for the library list
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub game: Option<String>,     // Installed game the profile is bound to
+    pub is_template: bool,
+    pub badges: Vec<String>,      // Enabled features, e.g. "DLSS", "RT", "Gamescope"
+    pub frame_cap: Option<u32>,   // Lowest FPS cap across the limiters
+    pub sync_mode: Option<String>, // "esync", "fsync", "ntsync"; None for Proton's default
+    pub warning_count: usize,
+}
+
+/// Fields that identify a profile rather than configure the game
+const IDENTITY_FIELDS: &[&str] = &[
+    "name",
+    "description",
+    "is_template",
+    "min_gpu_generation",
+    "executable_match",
+    "steam_appid",
+];
+
+/// Result of reconciling profile filenames with their `name` fields
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReconcileReport {
+    pub renamed: Vec<String>,             // "old.toml -> new.toml"
+    pub removed_duplicates: Vec<String>,  // Older copies replaced by a newer file
+    pub skipped: Vec<String>,             // Target name taken by a file that isn't a profile
+}
+
+/// Upper bound for gamescope -r / -o frame limits
+const MAX_GAMESCOPE_FRAMELIMIT: u32 = 1000;
+
+/// Range the driver accepts for __GL_MaxFramesAllowed
+const MAX_FRAMES_ALLOWED_RANGE: std::ops::RangeInclusive<u32> = 1..=4;
+
+/// gamescope -S values
+pub const GAMESCOPE_SCALING_MODES: &[&str] = &["auto", "integer", "fit", "fill", "stretch"];
+
+/// Values for nvidia.offload_target
+pub const OFFLOAD_TARGETS: &[&str] = &["igpu", "nvidia"];
+
+/// A game (by appid or executable) that several profiles are bound to
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateBinding {
+    pub steam_appid: Option<u32>,
+    pub executable: Option<String>,
+    pub profiles: Vec<String>,
+}
+
+/// Maximum number of previous versions kept per profile
+const MAX_PROFILE_HISTORY: usize = 10;
+
 pub struct ProfileManager {
     profiles_dir: PathBuf,
 }
@@ -221,6 +454,12 @@ impl ProfileManager {
         }
     }
 
+    /// Manager rooted at a custom directory (tests only)
+    #[cfg(test)]
+    pub(crate) fn with_profiles_dir(profiles_dir: PathBuf) -> Self {
+        Self { profiles_dir }
+    }
+
     pub fn list_profiles(&self) -> Vec<GameProfile> {
         let mut profiles = Vec::new();
 
@@ -241,7 +480,7 @@ impl ProfileManager {
     }
 
     pub fn get_profile(&self, name: &str) -> Option<GameProfile> {
-        let filename = format!("{}.toml", name.to_lowercase().replace(' ', "_"));
+        let filename = profile_filename(name);
         let path = self.profiles_dir.join(filename);
 
         fs::read_to_string(&path)
@@ -249,30 +488,414 @@ impl ProfileManager {
             .and_then(|content| toml::from_str(&content).ok())
     }
 
+    /// Profiles with their file modification time, for tie-breaking duplicates
+    fn list_profiles_with_mtime(&self) -> Vec<(GameProfile, std::time::SystemTime)> {
+        fs::read_dir(&self.profiles_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().map(|e| e == "toml").unwrap_or(false))
+            .filter_map(|path| {
+                let profile = toml::from_str::<GameProfile>(&fs::read_to_string(&path).ok()?).ok()?;
+                let modified = fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+                Some((profile, modified))
+            })
+            .collect()
+    }
+
+    /// When several profiles claim the same executable: prefer non-templates,
+    /// then the most recently modified, then the name so the result is stable
     pub fn get_profile_by_executable(&self, exe_name: &str) -> Option<GameProfile> {
-        self.list_profiles().into_iter().find(|p| {
-            p.executable_match
-                .as_ref()
-                .map(|e| e == exe_name)
-                .unwrap_or(false)
-        })
+        self.list_profiles_with_mtime()
+            .into_iter()
+            .filter(|(p, _)| p.executable_match.as_deref() == Some(exe_name))
+            .min_by(|(a, a_time), (b, b_time)| {
+                a.is_template
+                    .cmp(&b.is_template)
+                    .then_with(|| b_time.cmp(a_time))
+                    .then_with(|| a.name.cmp(&b.name))
+            })
+            .map(|(p, _)| p)
+    }
+
+    /// Game bindings (appid or executable) claimed by more than one profile
+    pub fn find_duplicate_bindings(&self) -> Vec<DuplicateBinding> {
+        let mut by_appid: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+        let mut by_exe: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for profile in self.list_profiles().into_iter().filter(|p| !p.is_template) {
+            if let Some(appid) = profile.steam_appid {
+                by_appid.entry(appid).or_default().push(profile.name.clone());
+            }
+            if let Some(exe) = profile.executable_match {
+                by_exe.entry(exe).or_default().push(profile.name);
+            }
+        }
+
+        let appid_dups = by_appid.into_iter().map(|(appid, profiles)| DuplicateBinding {
+            steam_appid: Some(appid),
+            executable: None,
+            profiles,
+        });
+        let exe_dups = by_exe.into_iter().map(|(exe, profiles)| DuplicateBinding {
+            steam_appid: None,
+            executable: Some(exe),
+            profiles,
+        });
+
+        appid_dups
+            .chain(exe_dups)
+            .filter(|d| d.profiles.len() > 1)
+            .map(|mut d| {
+                d.profiles.sort();
+                d
+            })
+            .collect()
     }
 
     pub fn save_profile(&self, profile: &GameProfile) -> Result<(), String> {
-        let filename = format!("{}.toml", profile.name.to_lowercase().replace(' ', "_"));
+        let filename = profile_filename(&profile.name);
         let path = self.profiles_dir.join(filename);
 
+        if let Some(effect) = profile
+            .vkbasalt
+            .effects
+            .iter()
+            .find(|e| !VKBASALT_EFFECTS.contains(&e.as_str()))
+        {
+            return Err(format!("Unknown vkBasalt effect '{}'", effect));
+        }
+
+        let gamescope = &profile.wrappers.gamescope;
+        let limits = [
+            ("-r", gamescope.framelimit),
+            ("-o", gamescope.unfocused_framelimit),
+        ];
+        for (flag, limit) in limits {
+            if limit.is_some_and(|l| l > MAX_GAMESCOPE_FRAMELIMIT) {
+                return Err(format!(
+                    "gamescope {} frame limit must be at most {}",
+                    flag, MAX_GAMESCOPE_FRAMELIMIT
+                ));
+            }
+        }
+
+        if let Some(frames) = profile.nvidia.max_frames_allowed {
+            if !MAX_FRAMES_ALLOWED_RANGE.contains(&frames) {
+                return Err(format!(
+                    "Max frames allowed {} is out of range (1 to 4)",
+                    frames
+                ));
+            }
+        }
+
+        if let Some(kelvin) = profile.screen.night_temperature {
+            if !TEMPERATURE_RANGE.contains(&kelvin) {
+                return Err(format!(
+                    "Night mode temperature {}K is out of range (1000 to 6500)",
+                    kelvin
+                ));
+            }
+        }
+        if let Some(gamma) = profile.screen.night_gamma {
+            if !GAMMA_RANGE.contains(&gamma) {
+                return Err(format!("Night mode gamma {} is out of range (0.5 to 2.0)", gamma));
+            }
+        }
+
+        if let Some(generation) = &profile.min_gpu_generation {
+            if generation_rank(generation).is_none() {
+                return Err(format!(
+                    "Unknown GPU generation '{}' (expected one of {})",
+                    generation,
+                    GPU_GENERATIONS.join(", ")
+                ));
+            }
+        }
+
+        if let Some(mode) = &profile.wrappers.gamescope.scaling_mode {
+            if !GAMESCOPE_SCALING_MODES.contains(&mode.as_str()) {
+                return Err(format!(
+                    "Unknown gamescope scaling mode '{}' (expected one of {})",
+                    mode,
+                    GAMESCOPE_SCALING_MODES.join(", ")
+                ));
+            }
+        }
+
+        if let Some(target) = &profile.nvidia.offload_target {
+            if !OFFLOAD_TARGETS.contains(&target.as_str()) {
+                return Err(format!(
+                    "Unknown offload target '{}' (expected igpu or nvidia)",
+                    target
+                ));
+            }
+        }
+
+        if let Some(mode) = &profile.nvidia.powermizer_mode {
+            if !POWERMIZER_MODES.iter().any(|(name, _)| name == mode) {
+                return Err(format!("Unknown PowerMizer mode '{}'", mode));
+            }
+        }
+
+        if let Some(nice) = profile.cpu.nice {
+            if !(-20..=19).contains(&nice) {
+                return Err(format!("Nice value {} is out of range (-20 to 19)", nice));
+            }
+        }
+        if let Some(class) = profile.cpu.ionice_class {
+            if class > 3 {
+                return Err(format!("ionice class {} is out of range (0 to 3)", class));
+            }
+        }
+
         let content = toml::to_string_pretty(profile)
             .map_err(|e| format!("Failed to serialize profile: {}", e))?;
 
-        fs::write(&path, content).map_err(|e| format!("Failed to write profile: {}", e))
+        // Keep the previous version around before overwriting
+        if let Ok(previous) = fs::read_to_string(&path) {
+            if previous != content {
+                self.archive_profile_version(&profile.name, &previous)?;
+            }
+        }
+
+        fs::write(&path, content).map_err(|e| format!("Failed to write profile: {}", e))?;
+
+        self.write_vkbasalt_config(profile)
+    }
+
+    /// JSON sidecar capturing the environment a profile was tested with
+    pub fn lockfile_path(&self, name: &str) -> PathBuf {
+        self.profiles_dir
+            .join(format!("{}.lock.json", profile_stem(name)))
+    }
+
+    /// Per-profile vkBasalt config lives next to the profiles dir
+    fn vkbasalt_config_path(&self, name: &str) -> PathBuf {
+        self.profiles_dir
+            .parent()
+            .unwrap_or(&self.profiles_dir)
+            .join("vkbasalt")
+            .join(format!("{}.conf", profile_stem(name)))
+    }
+
+    /// Write (or remove, when disabled or without effects) the profile's vkBasalt config file
+    fn write_vkbasalt_config(&self, profile: &GameProfile) -> Result<(), String> {
+        let path = self.vkbasalt_config_path(&profile.name);
+
+        if !profile.vkbasalt.is_active() {
+            fs::remove_file(&path).ok();
+            return Ok(());
+        }
+
+        let mut config = format!("effects = {}\n", profile.vkbasalt.effects.join(":"));
+        if let Some(sharpness) = profile.vkbasalt.cas_sharpness {
+            config.push_str(&format!("casSharpness = {:.2}\n", sharpness.clamp(-1.0, 1.0)));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).ok();
+        }
+        fs::write(&path, config).map_err(|e| format!("Failed to write vkBasalt config: {}", e))
+    }
+
+    fn history_dir(&self, name: &str) -> PathBuf {
+        self.profiles_dir.join(".history").join(profile_stem(name))
+    }
+
+    /// Store a previous profile version and prune history beyond MAX_PROFILE_HISTORY
+    fn archive_profile_version(&self, name: &str, content: &str) -> Result<(), String> {
+        let dir = self.history_dir(name);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create history dir: {}", e))?;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        // Two saves within the same millisecond: keep both, in order
+        let timestamp = match self.list_profile_versions(name).first() {
+            Some(&newest) if newest >= now => newest + 1,
+            _ => now,
+        };
+
+        fs::write(dir.join(format!("{}.toml", timestamp)), content)
+            .map_err(|e| format!("Failed to write profile history: {}", e))?;
+
+        for old in self.list_profile_versions(name).into_iter().skip(MAX_PROFILE_HISTORY) {
+            fs::remove_file(dir.join(format!("{}.toml", old))).ok();
+        }
+
+        Ok(())
+    }
+
+    /// List saved versions of a profile as timestamps (ms), newest first
+    pub fn list_profile_versions(&self, name: &str) -> Vec<u64> {
+        let mut versions: Vec<u64> = fs::read_dir(self.history_dir(name))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension().map(|e| e == "toml").unwrap_or(false) {
+                    path.file_stem()?.to_str()?.parse().ok()
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        versions.sort_unstable_by(|a, b| b.cmp(a));
+        versions
+    }
+
+    /// Restore a previous version (the current one is archived, so this can be undone)
+    pub fn restore_profile_version(&self, name: &str, timestamp: u64) -> Result<GameProfile, String> {
+        let path = self.history_dir(name).join(format!("{}.toml", timestamp));
+        let content = fs::read_to_string(&path)
+            .map_err(|_| format!("Version {} of profile '{}' not found", timestamp, name))?;
+        let profile: GameProfile = toml::from_str(&content)
+            .map_err(|e| format!("Failed to parse profile version: {}", e))?;
+
+        self.save_profile(&profile)?;
+        Ok(profile)
     }
 
+    /// Delete a profile with its sidecar files and history, so a new profile
+    /// with the same name starts clean
     pub fn delete_profile(&self, name: &str) -> Result<(), String> {
-        let filename = format!("{}.toml", name.to_lowercase().replace(' ', "_"));
+        let filename = profile_filename(name);
         let path = self.profiles_dir.join(filename);
 
-        fs::remove_file(&path).map_err(|e| format!("Failed to delete profile: {}", e))
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete profile: {}", e))?;
+
+        fs::remove_file(self.vkbasalt_config_path(name)).ok();
+        fs::remove_file(self.lockfile_path(name)).ok();
+        fs::remove_dir_all(self.history_dir(name)).ok();
+        Ok(())
+    }
+
+    /// Fix profiles whose filename no longer matches their `name` (e.g. after a
+    /// hand edit). Files are renamed to the expected name; when several files
+    /// claim the same profile, the most recently modified one wins and the
+    /// others are archived to history and removed.
+    pub fn reconcile_profiles(&self) -> Result<ReconcileReport, String> {
+        self.recover_reconcile_leftovers()?;
+
+        let mut report = ReconcileReport::default();
+        let mut by_name: BTreeMap<
+            String,
+            Vec<(PathBuf, std::time::SystemTime, GameProfile, String)>,
+        > = BTreeMap::new();
+
+        for entry in fs::read_dir(&self.profiles_dir)
+            .map_err(|e| format!("Failed to read profiles dir: {}", e))?
+            .flatten()
+        {
+            let path = entry.path();
+            if path.extension().map(|e| e != "toml").unwrap_or(true) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(profile) = toml::from_str::<GameProfile>(&content) else {
+                continue;
+            };
+            let modified = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::UNIX_EPOCH);
+
+            by_name
+                .entry(profile_filename(&profile.name))
+                .or_default()
+                .push((path, modified, profile, content));
+        }
+
+        // Winners are first moved aside under temporary names so that swapped
+        // names (a.toml holding "b" and b.toml holding "a") can't clobber each other
+        let mut moves = Vec::new();
+        for (filename, mut files) in by_name {
+            let expected = self.profiles_dir.join(&filename);
+            if files.len() == 1 && files[0].0 == expected {
+                continue;
+            }
+
+            // Newest first; the winner is the one we keep
+            files.sort_by_key(|f| std::cmp::Reverse(f.1));
+            let (winner_path, _, winner, _) = files.remove(0);
+
+            for (path, _, profile, content) in files {
+                self.archive_profile_version(&profile.name, &content)?;
+                fs::remove_file(&path)
+                    .map_err(|e| format!("Failed to remove duplicate profile: {}", e))?;
+                report.removed_duplicates.push(display_filename(&path));
+            }
+
+            if winner_path != expected {
+                let temp = winner_path.with_extension("toml.reconcile");
+                fs::rename(&winner_path, &temp)
+                    .map_err(|e| format!("Failed to move profile file: {}", e))?;
+                moves.push((winner_path, temp, expected, filename, winner));
+            }
+        }
+
+        for (original, temp, expected, filename, winner) in moves {
+            // Whatever still sits at the target wasn't a readable profile; leave it alone
+            let target = if expected.exists() { &original } else { &expected };
+            fs::rename(&temp, target)
+                .map_err(|e| format!("Failed to rename profile file: {}", e))?;
+            if target == &original {
+                report.skipped.push(display_filename(&original));
+                continue;
+            }
+            report
+                .renamed
+                .push(format!("{} -> {}", display_filename(&original), filename));
+            self.write_vkbasalt_config(&winner)?;
+        }
+
+        report.renamed.sort();
+        report.removed_duplicates.sort();
+        report.skipped.sort();
+        Ok(report)
+    }
+
+    /// Put back `*.toml.reconcile` files left by an interrupted reconcile, so
+    /// the pass below sees them as profiles again
+    fn recover_reconcile_leftovers(&self) -> Result<(), String> {
+        for entry in fs::read_dir(&self.profiles_dir)
+            .map_err(|e| format!("Failed to read profiles dir: {}", e))?
+            .flatten()
+        {
+            let path = entry.path();
+            if path.extension().map(|e| e != "reconcile").unwrap_or(true) {
+                continue;
+            }
+            let Some(stem) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.strip_suffix(".toml"))
+            else {
+                continue;
+            };
+
+            // The filename doesn't matter, the pass below renames by profile name
+            let mut target = self.profiles_dir.join(format!("{}.toml", stem));
+            let mut n = 2;
+            while target.exists() {
+                target = self
+                    .profiles_dir
+                    .join(format!("{}-recovered-{}.toml", stem, n));
+                n += 1;
+            }
+            fs::rename(&path, &target)
+                .map_err(|e| format!("Failed to recover profile file: {}", e))?;
+        }
+        Ok(())
     }
 
     /// Duplicate an existing profile with a new name
@@ -290,6 +913,190 @@ impl ProfileManager {
         self.save_profile(&profile)
     }
 
+    /// Per profile name, whether its bound game (by appid or executable) is
+    /// installed. Profiles not bound to a game (templates, global) report false.
+    pub fn check_profiles_bound_status(&self, games: &[Game]) -> HashMap<String, bool> {
+        self.list_profiles()
+            .into_iter()
+            .map(|p| {
+                let bound = p.steam_appid.is_some() || p.executable_match.is_some();
+                let installed = bound && !is_orphaned(&p, games);
+                (p.name, installed)
+            })
+            .collect()
+    }
+
+    /// Summary of one profile for the library list
+    pub fn get_profile_summary(&self, name: &str, games: &[Game]) -> Result<ProfileSummary, String> {
+        let profile = self
+            .get_profile(name)
+            .ok_or_else(|| format!("Profile '{}' not found", name))?;
+        let warnings = self.check_profile_warnings(&profile, None);
+        Ok(summarize_profile(&profile, games, warnings.len()))
+    }
+
+    /// Summaries of every profile, sharing one game scan and one set of system probes
+    pub fn list_profile_summaries(&self, games: &[Game]) -> Vec<ProfileSummary> {
+        let probes = WarningProbes::default();
+        self.list_profiles()
+            .iter()
+            .map(|p| {
+                let warnings = self.check_profile_warnings_with(p, None, &probes);
+                summarize_profile(p, games, warnings.len())
+            })
+            .collect()
+    }
+
+    /// Executable names of a profile's Steam or Faugus game, to fill
+    /// executable_match with. A profile with an appid is looked up among
+    /// Steam games; Faugus games have no appid, so they are matched by name
+    /// like bound_game does. Empty when no installed game matches.
+    pub fn suggest_executable_matches(
+        &self,
+        name: &str,
+        games: &[Game],
+    ) -> Result<Vec<String>, String> {
+        let profile = self
+            .get_profile(name)
+            .ok_or_else(|| format!("Profile '{}' not found", name))?;
+        let game = match profile.steam_appid.map(|id| id.to_string()) {
+            Some(appid) => games
+                .iter()
+                .find(|g| g.source == GameSource::Steam && g.id == appid),
+            None => games
+                .iter()
+                .find(|g| g.source == GameSource::Faugus && g.name.eq_ignore_ascii_case(&profile.name)),
+        };
+        let Some(game) = game else {
+            return Ok(Vec::new());
+        };
+
+        if let Some(exe) = game.executable.as_ref().and_then(|e| e.file_name()) {
+            return Ok(vec![exe.to_string_lossy().to_string()]);
+        }
+        // Faugus reports the Wine prefix as the install path, not the game folder
+        if game.source != GameSource::Steam {
+            return Ok(Vec::new());
+        }
+        Ok(game
+            .install_path
+            .as_deref()
+            .map(GameDetector::find_game_executables)
+            .unwrap_or_default())
+    }
+
+    /// List non-template profiles whose game is no longer installed
+    pub fn list_orphaned_profiles(&self, games: &[Game]) -> Vec<GameProfile> {
+        self.list_profiles()
+            .into_iter()
+            .filter(|p| !p.is_template && is_orphaned(p, games))
+            .collect()
+    }
+
+    /// Delete the named profiles, as confirmed by the user from
+    /// list_orphaned_profiles, returning the names that were removed. Names
+    /// that are no longer orphaned are skipped. An unmounted library or a
+    /// missing Steam install makes every bound game look uninstalled, so
+    /// nothing is pruned when detection came back without the games needed.
+    pub fn prune_orphaned_profiles(
+        &self,
+        names: &[String],
+        games: &[Game],
+    ) -> Result<Vec<String>, String> {
+        if games.is_empty() {
+            return Err("No games were detected; refusing to prune profiles".to_string());
+        }
+        let steam_detected = games.iter().any(|g| g.source == GameSource::Steam);
+
+        let orphaned: Vec<GameProfile> = self
+            .list_orphaned_profiles(games)
+            .into_iter()
+            .filter(|p| names.contains(&p.name))
+            .collect();
+        if !steam_detected && orphaned.iter().any(|p| p.steam_appid.is_some()) {
+            return Err(
+                "No Steam games were detected; refusing to prune Steam profiles".to_string(),
+            );
+        }
+
+        let mut removed = Vec::new();
+        for profile in orphaned {
+            self.delete_profile(&profile.name)?;
+            removed.push(profile.name);
+        }
+        Ok(removed)
+    }
+
+    /// Set a single boolean field by dotted path (e.g. `wrappers.gamemode`)
+    /// without the caller round-tripping the whole profile. An unset
+    /// `Option<bool>` (e.g. `nvidia.gsync_allowed`) serializes as null, so
+    /// null is accepted when the field parses back as a boolean.
+    pub fn set_profile_flag(&self, name: &str, field_path: &str, value: bool) -> Result<GameProfile, String> {
+        let profile = self
+            .get_profile(name)
+            .ok_or_else(|| format!("Profile '{}' not found", name))?;
+        let mut json = serde_json::to_value(&profile)
+            .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+
+        let field = field_path
+            .split('.')
+            .try_fold(&mut json, |node, key| node.get_mut(key))
+            .ok_or_else(|| format!("Unknown profile field '{}'", field_path))?;
+        let unset = field.is_null();
+        if !field.is_boolean() && !unset {
+            return Err(format!("Profile field '{}' is not a boolean", field_path));
+        }
+        *field = serde_json::Value::Bool(value);
+
+        let updated: GameProfile = serde_json::from_value(json).map_err(|e| {
+            if unset {
+                // An unset Option of some other type, e.g. dxvk.hud
+                format!("Profile field '{}' is not a boolean", field_path)
+            } else {
+                format!("Failed to update profile: {}", e)
+            }
+        })?;
+        self.save_profile(&updated)?;
+        Ok(updated)
+    }
+
+    /// Load a profile file, re-serialize and re-parse it, and report every
+    /// value from the file that didn't survive (unknown keys, lossy types,
+    /// defaults overriding what was written). Empty means safe to edit.
+    pub fn verify_profile_roundtrip(&self, name: &str) -> Result<Vec<String>, String> {
+        let filename = profile_filename(name);
+        let content = fs::read_to_string(self.profiles_dir.join(filename))
+            .map_err(|_| format!("Profile '{}' not found", name))?;
+
+        let original: toml::Value =
+            toml::from_str(&content).map_err(|e| format!("Invalid TOML: {}", e))?;
+        let profile: GameProfile =
+            toml::from_str(&content).map_err(|e| format!("Failed to parse profile: {}", e))?;
+        let serialized = toml::to_string_pretty(&profile)
+            .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+        let reparsed: toml::Value = toml::from_str(&serialized)
+            .map_err(|e| format!("Re-serialized profile is not valid TOML: {}", e))?;
+
+        let mut issues = Vec::new();
+        collect_lost_fields("", &original, &reparsed, &mut issues);
+        Ok(issues)
+    }
+
+    /// Settings where a profile differs from the defaults (or from a named template)
+    pub fn diff_from_default(&self, name: &str, baseline: Option<&str>) -> Result<Vec<FieldDiff>, String> {
+        let profile = self
+            .get_profile(name)
+            .ok_or_else(|| format!("Profile '{}' not found", name))?;
+        let baseline = match baseline {
+            Some(template) => self
+                .get_profile(template)
+                .ok_or_else(|| format!("Template '{}' not found", template))?,
+            None => GameProfile::default(),
+        };
+
+        Ok(diff_profiles(&profile, &baseline))
+    }
+
     /// List only template profiles (is_template = true)
     pub fn list_template_profiles(&self) -> Vec<GameProfile> {
         self.list_profiles()
@@ -298,6 +1105,15 @@ impl ProfileManager {
             .collect()
     }
 
+    /// Templates suited to the GPU named `gpu_name` (NVML device name)
+    pub fn list_applicable_templates(&self, gpu_name: Option<&str>) -> Vec<GameProfile> {
+        let generation = gpu_name.and_then(gpu_generation);
+        self.list_template_profiles()
+            .into_iter()
+            .filter(|t| template_applies(t, generation))
+            .collect()
+    }
+
     /// Apply a template to a game profile
     pub fn apply_template(&self, template_name: &str, game_name: &str) -> Result<GameProfile, String> {
         let template = self
@@ -307,70 +1123,130 @@ impl ProfileManager {
         let mut profile = template.clone();
         profile.name = game_name.to_string();
         profile.is_template = false;
+        profile.min_gpu_generation = None;
 
         Ok(profile)
     }
 
+    /// Save a `<name>-safe` copy with crash-prone options turned off, for A/B
+    /// testing a crashing game. An existing copy is never overwritten
+    /// (`-safe-2`, `-safe-3`, ...).
+    ///
+    /// The copy is deliberately an unbound template rather than a second
+    /// profile bound to the game: a game resolves to a single profile, so a
+    /// bound copy would be reported as a duplicate binding and, being the
+    /// newest, would win executable lookups over the original for every later
+    /// launch. Run it for one launch with `unvcpfl --profile "<name>-safe"`.
+    pub fn make_safe(&self, name: &str) -> Result<GameProfile, String> {
+        let profile = self
+            .get_profile(name)
+            .ok_or_else(|| format!("Profile '{}' not found", name))?;
+
+        let mut safe = safe_copy(&profile);
+        let base = safe.name.clone();
+        let mut n = 2;
+        while self.profiles_dir.join(profile_filename(&safe.name)).exists() {
+            safe.name = format!("{}-{}", base, n);
+            n += 1;
+        }
+        self.save_profile(&safe)?;
+        Ok(safe)
+    }
+
     /// Generate environment variables from a profile
     pub fn build_env_vars(&self, profile: &GameProfile) -> HashMap<String, String> {
-        let mut env = HashMap::new();
+        self.build_env(profile).vars
+    }
+
+    /// Map each env var to the profile field that produced it, e.g.
+    /// `DXVK_FRAME_RATE -> wrappers.frame_limiter.target_fps`. When several
+    /// settings write the same var, the one that wins is reported.
+    pub fn trace_env_sources(&self, profile: &GameProfile) -> HashMap<String, String> {
+        self.build_env(profile).sources
+    }
+
+    /// `export KEY=VALUE` lines grouped under `# DLSS`, `# DXVK`, ... headers,
+    /// sorted so the output is stable for copy/paste and diffing
+    pub fn build_env_export_block(&self, profile: &GameProfile) -> String {
+        let env = self.build_env(profile);
+        let mut groups: Vec<EnvGroup> = Vec::new();
+
+        for (key, value) in &env.vars {
+            let (order, header) = env_category(&env.sources[key]);
+            match groups.iter_mut().find(|(o, _, _)| *o == order) {
+                Some((_, _, vars)) => vars.push((key, value)),
+                None => groups.push((order, header, vec![(key, value)])),
+            }
+        }
+        groups.sort_by_key(|(order, _, _)| *order);
+
+        groups
+            .into_iter()
+            .map(|(_, header, mut vars)| {
+                vars.sort();
+                let lines: Vec<String> = vars
+                    .into_iter()
+                    .map(|(key, value)| format!("export {}={}", key, shell_quote(value)))
+                    .collect();
+                format!("# {}\n{}\n", header, lines.join("\n"))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn build_env(&self, profile: &GameProfile) -> EnvBuilder {
+        let mut env = EnvBuilder::default();
 
         // DLSS settings
         if profile.dlss.upgrade {
-            env.insert("PROTON_DLSS_UPGRADE".to_string(), "1".to_string());
+            env.set("PROTON_DLSS_UPGRADE", "1", "dlss.upgrade");
         }
         if profile.dlss.indicator {
-            env.insert("PROTON_DLSS_INDICATOR".to_string(), "1".to_string());
+            env.set("PROTON_DLSS_INDICATOR", "1", "dlss.indicator");
         }
         if profile.dlss.ngx_updater {
-            env.insert("PROTON_ENABLE_NGX_UPDATER".to_string(), "1".to_string());
+            env.set("PROTON_ENABLE_NGX_UPDATER", "1", "dlss.ngx_updater");
         }
         if profile.dlss.sr_override {
-            env.insert(
-                "DXVK_NVAPI_DRS_NGX_DLSS_SR_OVERRIDE".to_string(),
-                "on".to_string(),
-            );
+            env.set("DXVK_NVAPI_DRS_NGX_DLSS_SR_OVERRIDE", "on", "dlss.sr_override");
         }
         if profile.dlss.rr_override {
-            env.insert(
-                "DXVK_NVAPI_DRS_NGX_DLSS_RR_OVERRIDE".to_string(),
-                "on".to_string(),
-            );
+            env.set("DXVK_NVAPI_DRS_NGX_DLSS_RR_OVERRIDE", "on", "dlss.rr_override");
         }
         if profile.dlss.fg_override {
-            env.insert(
-                "DXVK_NVAPI_DRS_NGX_DLSS_FG_OVERRIDE".to_string(),
-                "on".to_string(),
-            );
+            env.set("DXVK_NVAPI_DRS_NGX_DLSS_FG_OVERRIDE", "on", "dlss.fg_override");
         }
         if let Some(preset) = &profile.dlss.sr_preset {
-            env.insert(
-                "DXVK_NVAPI_DRS_NGX_DLSS_SR_OVERRIDE_RENDER_PRESET_SELECTION".to_string(),
-                preset.clone(),
+            env.set(
+                "DXVK_NVAPI_DRS_NGX_DLSS_SR_OVERRIDE_RENDER_PRESET_SELECTION",
+                preset,
+                "dlss.sr_preset",
             );
         }
         if let Some(preset) = &profile.dlss.rr_preset {
-            env.insert(
-                "DXVK_NVAPI_DRS_NGX_DLSS_RR_OVERRIDE_RENDER_PRESET_SELECTION".to_string(),
-                preset.clone(),
+            env.set(
+                "DXVK_NVAPI_DRS_NGX_DLSS_RR_OVERRIDE_RENDER_PRESET_SELECTION",
+                preset,
+                "dlss.rr_preset",
             );
         }
         if let Some(count) = &profile.dlss.fg_multi_frame {
-            env.insert(
-                "DXVK_NVAPI_DRS_NGX_DLSSG_MULTI_FRAME_COUNT".to_string(),
-                count.clone(),
+            env.set(
+                "DXVK_NVAPI_DRS_NGX_DLSSG_MULTI_FRAME_COUNT",
+                count,
+                "dlss.fg_multi_frame",
             );
         }
 
         // DXVK settings
-        if let Some(hud) = &profile.dxvk.hud {
-            env.insert("DXVK_HUD".to_string(), hud.clone());
+        if let Some(hud) = profile.dxvk.hud_value() {
+            env.set("DXVK_HUD", hud, "dxvk.hud");
         }
         if profile.dxvk.nvapi {
-            env.insert("DXVK_ENABLE_NVAPI".to_string(), "1".to_string());
+            env.set("DXVK_ENABLE_NVAPI", "1", "dxvk.nvapi");
         }
         if profile.dxvk.async_compile {
-            env.insert("DXVK_ASYNC".to_string(), "1".to_string());
+            env.set("DXVK_ASYNC", "1", "dxvk.async_compile");
         }
 
         // VKD3D settings
@@ -394,93 +1270,187 @@ impl ProfileManager {
             vkd3d_config.push("no_upload_hvv");
         }
         if !vkd3d_config.is_empty() {
-            env.insert("VKD3D_CONFIG".to_string(), vkd3d_config.join(","));
+            env.set("VKD3D_CONFIG", vkd3d_config.join(","), "vkd3d");
         }
         if profile.vkd3d.frame_rate > 0 {
-            env.insert(
-                "VKD3D_FRAME_RATE".to_string(),
+            env.set(
+                "VKD3D_FRAME_RATE",
                 profile.vkd3d.frame_rate.to_string(),
+                "vkd3d.frame_rate",
             );
         }
 
         // NVIDIA driver settings
         if let Some(vsync) = &profile.nvidia.vsync {
             let val = if vsync == "on" { "1" } else { "0" };
-            env.insert("__GL_SYNC_TO_VBLANK".to_string(), val.to_string());
+            env.set("__GL_SYNC_TO_VBLANK", val, "nvidia.vsync");
+        }
+        // A tearing window rule does nothing while the driver holds OpenGL
+        // flips for vblank; an explicit vsync setting still wins
+        if profile.screen.allow_tearing && profile.nvidia.vsync.is_none() {
+            env.set("__GL_SYNC_TO_VBLANK", "0", "screen.allow_tearing");
         }
         if profile.nvidia.prime {
-            env.insert("__NV_PRIME_RENDER_OFFLOAD".to_string(), "1".to_string());
-            env.insert(
-                "__VK_LAYER_NV_optimus".to_string(),
-                "NVIDIA_only".to_string(),
-            );
-            env.insert(
-                "__GLX_VENDOR_LIBRARY_NAME".to_string(),
-                "nvidia".to_string(),
-            );
+            env.set("__NV_PRIME_RENDER_OFFLOAD", "1", "nvidia.prime");
+            env.set("__VK_LAYER_NV_optimus", "NVIDIA_only", "nvidia.prime");
+            env.set("__GLX_VENDOR_LIBRARY_NAME", "nvidia", "nvidia.prime");
+        }
+        // Explicit values rather than unsetting, so a global prime-run style
+        // export can't send an iGPU game back to the NVIDIA card
+        match profile.nvidia.offload_target.as_deref() {
+            Some("igpu") => {
+                env.set("__NV_PRIME_RENDER_OFFLOAD", "0", "nvidia.offload_target");
+                env.set("__VK_LAYER_NV_optimus", "non_NVIDIA_only", "nvidia.offload_target");
+                env.set("__GLX_VENDOR_LIBRARY_NAME", "mesa", "nvidia.offload_target");
+                env.set("DRI_PRIME", "0", "nvidia.offload_target");
+            }
+            Some("nvidia") => {
+                env.set("__NV_PRIME_RENDER_OFFLOAD", "1", "nvidia.offload_target");
+                env.set("__VK_LAYER_NV_optimus", "NVIDIA_only", "nvidia.offload_target");
+                env.set("__GLX_VENDOR_LIBRARY_NAME", "nvidia", "nvidia.offload_target");
+            }
+            _ => {}
         }
         if profile.nvidia.smooth_motion {
-            env.insert(
-                "NVPRESENT_ENABLE_SMOOTH_MOTION".to_string(),
-                "1".to_string(),
+            env.set("NVPRESENT_ENABLE_SMOOTH_MOTION", "1", "nvidia.smooth_motion");
+        }
+        if let Some(frames) = profile
+            .nvidia
+            .max_frames_allowed
+            .filter(|f| MAX_FRAMES_ALLOWED_RANGE.contains(f))
+        {
+            env.set(
+                "__GL_MaxFramesAllowed",
+                frames.to_string(),
+                "nvidia.max_frames_allowed",
             );
         }
-
+        if let Some(enabled) = profile.nvidia.shader_disk_cache {
+            let val = if enabled { "1" } else { "0" };
+            env.set("__GL_SHADER_DISK_CACHE", val, "nvidia.shader_disk_cache");
+        }
+        if let Some(allowed) = profile.nvidia.gsync_allowed {
+            let val = if allowed { "1" } else { "0" };
+            env.set("__GL_GSYNC_ALLOWED", val, "nvidia.gsync_allowed");
+        }
+        if let Some(path) = &profile.nvidia.shader_disk_cache_path {
+            env.set(
+                "__GL_SHADER_DISK_CACHE_PATH",
+                path,
+                "nvidia.shader_disk_cache_path",
+            );
+        }
+
         // Proton settings
         if let Some(verb) = &profile.proton.verb {
-            env.insert("PROTON_VERB".to_string(), verb.clone());
+            env.set("PROTON_VERB", verb, "proton.verb");
         }
 
         // Sync mode
         if let Some(sync_mode) = &profile.proton.sync_mode {
             match sync_mode.as_str() {
                 "esync" => {
-                    env.insert("PROTON_NO_FSYNC".to_string(), "1".to_string());
+                    env.set("PROTON_NO_FSYNC", "1", "proton.sync_mode");
                 }
                 "fsync" => {
-                    env.insert("PROTON_NO_ESYNC".to_string(), "1".to_string());
+                    env.set("PROTON_NO_ESYNC", "1", "proton.sync_mode");
                 }
                 "ntsync" => {
                     // ntsync uses WINEFSYNC_FUTEX2 (kernel 6.3+)
-                    env.insert("WINEFSYNC_FUTEX2".to_string(), "1".to_string());
+                    env.set("WINEFSYNC_FUTEX2", "1", "proton.sync_mode");
                 }
                 _ => {} // "default" - let Proton decide
             }
         }
 
         if profile.proton.enable_wayland {
-            env.insert("PROTON_ENABLE_WAYLAND".to_string(), "1".to_string());
+            env.set("PROTON_ENABLE_WAYLAND", "1", "proton.enable_wayland");
+        }
+
+        // Force X11 (XWayland) or native Wayland; overrides enable_wayland
+        const DISPLAY_SERVER: &str = "proton.force_display_server";
+        match profile.proton.force_display_server.as_deref() {
+            Some("x11") => {
+                env.set("SDL_VIDEODRIVER", "x11", DISPLAY_SERVER);
+                env.set("PROTON_ENABLE_WAYLAND", "0", DISPLAY_SERVER);
+                // Blank WAYLAND_DISPLAY so toolkits don't pick Wayland on their own
+                env.set("WAYLAND_DISPLAY", "", DISPLAY_SERVER);
+                env.set(
+                    "DISPLAY",
+                    std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string()),
+                    DISPLAY_SERVER,
+                );
+            }
+            // Wayland can't be forced from an X11 session; check_profile_warnings reports it
+            Some("wayland") => {
+                if let Ok(wayland_display) = std::env::var("WAYLAND_DISPLAY") {
+                    env.set("SDL_VIDEODRIVER", "wayland", DISPLAY_SERVER);
+                    env.set("PROTON_ENABLE_WAYLAND", "1", DISPLAY_SERVER);
+                    env.set("WAYLAND_DISPLAY", wayland_display, DISPLAY_SERVER);
+                }
+            }
+            _ => {}
+        }
+
+        // Missing libraries are skipped (check_profile_warnings reports them);
+        // the inherited LD_PRELOAD (e.g. the Steam overlay) stays last
+        let inherited = std::env::var("LD_PRELOAD").ok();
+        if let Some(preload) =
+            ld_preload_value(&profile.proton.ld_preload, inherited.as_deref(), |p| p.is_file())
+        {
+            env.set("LD_PRELOAD", preload, "proton.ld_preload");
         }
 
         // HDR and integer scaling
         if profile.proton.enable_hdr {
-            env.insert("PROTON_ENABLE_HDR".to_string(), "1".to_string());
+            env.set("PROTON_ENABLE_HDR", "1", "proton.enable_hdr");
         }
         if profile.proton.integer_scaling {
-            env.insert("WINE_FULLSCREEN_INTEGER_SCALING".to_string(), "1".to_string());
+            env.set("WINE_FULLSCREEN_INTEGER_SCALING", "1", "proton.integer_scaling");
         }
 
         // Frame limiter (applies to both DXVK and VKD3D)
         if profile.wrappers.frame_limiter.enabled {
             if let Some(fps) = profile.wrappers.frame_limiter.target_fps {
-                env.insert("DXVK_FRAME_RATE".to_string(), fps.to_string());
-                env.insert("VKD3D_FRAME_RATE".to_string(), fps.to_string());
+                let source = "wrappers.frame_limiter.target_fps";
+                env.set("DXVK_FRAME_RATE", fps.to_string(), source);
+                env.set("VKD3D_FRAME_RATE", fps.to_string(), source);
             }
             if let Some(latency) = profile.wrappers.frame_limiter.swapchain_latency {
-                env.insert("VKD3D_SWAPCHAIN_LATENCY_FRAMES".to_string(), latency.to_string());
+                env.set(
+                    "VKD3D_SWAPCHAIN_LATENCY_FRAMES",
+                    latency.to_string(),
+                    "wrappers.frame_limiter.swapchain_latency",
+                );
             }
         }
 
         // MangoHud fps limiter
         if profile.wrappers.mangohud.enabled && profile.wrappers.mangohud.fps_limit_enabled {
             if let Some(fps) = profile.wrappers.mangohud.fps_limit {
-                env.insert("MANGOHUD_CONFIG".to_string(), format!("fps_limit={}", fps));
+                env.set(
+                    "MANGOHUD_CONFIG",
+                    format!("fps_limit={}", fps),
+                    "wrappers.mangohud.fps_limit",
+                );
             }
         }
 
+        // vkBasalt post-processing
+        if profile.vkbasalt.is_active() {
+            env.set("ENABLE_VKBASALT", "1", "vkbasalt.enabled");
+            env.set(
+                "VKBASALT_CONFIG_FILE",
+                self.vkbasalt_config_path(&profile.name)
+                    .to_string_lossy()
+                    .to_string(),
+                "vkbasalt.enabled",
+            );
+        }
+
         // Custom environment variables
         for (key, value) in &profile.custom_env {
-            env.insert(key.clone(), value.clone());
+            env.set(key, value, &format!("custom_env.{}", key));
         }
 
         env
@@ -488,11 +1458,20 @@ impl ProfileManager {
 
     /// Build wrapper command prefix
     pub fn build_wrapper_cmd(&self, profile: &GameProfile) -> Vec<String> {
-        let mut wrappers = Vec::new();
+        self.build_wrappers(profile).into_iter().flatten().collect()
+    }
+
+    /// Wrapper commands in launch order, one per wrapper
+    pub(crate) fn build_wrappers(&self, profile: &GameProfile) -> Vec<Vec<String>> {
+        let mut wrappers: Vec<Vec<String>> = Vec::new();
+
+        // MangoHud can't hook into gamescope's nested session; use mangoapp instead
+        let mangohud_in_gamescope =
+            profile.wrappers.gamescope.enabled && profile.wrappers.mangohud.enabled;
 
         // LACT profile switch (prepend as a command)
         if let Some(lact_profile) = &profile.wrappers.lact_profile {
-            wrappers.push(format!("lact cli profile set \"{}\" &&", lact_profile));
+            wrappers.push(vec![format!("lact cli profile set \"{}\" &&", lact_profile)]);
         }
 
         if profile.wrappers.gamescope.enabled {
@@ -530,6 +1509,17 @@ impl ProfileManager {
                 gs.push("-F".to_string());
                 gs.push(filter.clone());
             }
+            // Hand-edited files skip save_profile's validation
+            if let Some(mode) = profile
+                .wrappers
+                .gamescope
+                .scaling_mode
+                .as_ref()
+                .filter(|m| GAMESCOPE_SCALING_MODES.contains(&m.as_str()))
+            {
+                gs.push("-S".to_string());
+                gs.push(mode.clone());
+            }
             if let Some(sharp) = profile.wrappers.gamescope.fsr_sharpness {
                 gs.push("--fsr-sharpness".to_string());
                 gs.push(sharp.to_string());
@@ -549,37 +1539,709 @@ impl ProfileManager {
                     gs.push(limit.to_string());
                 }
             }
-            if profile.wrappers.gamescope.mangoapp {
+            if let Some(limit) = profile.wrappers.gamescope.unfocused_framelimit {
+                if limit > 0 {
+                    gs.push("-o".to_string());
+                    gs.push(limit.to_string());
+                }
+            }
+            if profile.wrappers.gamescope.mangoapp || mangohud_in_gamescope {
                 gs.push("--mangoapp".to_string());
             }
             if profile.wrappers.gamescope.hdr {
                 gs.push("--hdr-enabled".to_string());
             }
+            if profile.screen.allow_tearing {
+                gs.push("--immediate-flips".to_string());
+            }
+            // A stray `--` would cut off the game command
+            gs.extend(
+                profile
+                    .wrappers
+                    .gamescope
+                    .extra_args
+                    .iter()
+                    .filter(|arg| arg.trim() != "--")
+                    .cloned(),
+            );
             gs.push("--".to_string());
 
-            wrappers.extend(gs);
+            wrappers.push(gs);
         }
 
-        if profile.wrappers.mangohud.enabled {
-            wrappers.push("mangohud".to_string());
+        if profile.wrappers.mangohud.enabled && !mangohud_in_gamescope {
+            wrappers.push(vec!["mangohud".to_string()]);
         }
 
         if profile.wrappers.gamemode {
-            wrappers.push("gamemoderun".to_string());
+            wrappers.push(vec!["gamemoderun".to_string()]);
         }
 
         if profile.wrappers.game_performance {
-            wrappers.push("game-performance".to_string());
+            wrappers.push(vec!["game-performance".to_string()]);
         }
 
         if profile.wrappers.dlss_swapper {
-            wrappers.push("dlss-swapper".to_string());
+            wrappers.push(vec!["dlss-swapper".to_string()]);
+        }
+
+        if profile.wrappers.inhibit_idle {
+            wrappers.push(idle_inhibit_wrapper(detect_compositor()));
+        }
+
+        if let Some(nice) = profile.cpu.nice {
+            wrappers.push(vec!["nice".to_string(), "-n".to_string(), nice.to_string()]);
+        }
+
+        // -t: don't abort the launch if the class needs privileges we don't have
+        if let Some(class) = profile.cpu.ionice_class {
+            wrappers.push(vec![
+                "ionice".to_string(),
+                "-t".to_string(),
+                "-c".to_string(),
+                class.to_string(),
+            ]);
+        }
+
+        normalize_wrapper_order(wrappers)
+    }
+
+    /// Flag settings that conflict or won't behave as the user expects
+    /// `rebar` is the detected resizable BAR state, when a GPU is available
+    pub fn check_profile_warnings(
+        &self,
+        profile: &GameProfile,
+        rebar: Option<&ResizableBarStatus>,
+    ) -> Vec<String> {
+        self.check_profile_warnings_with(profile, rebar, &WarningProbes::default())
+    }
+
+    /// check_profile_warnings against shared probes, for checking many profiles
+    fn check_profile_warnings_with(
+        &self,
+        profile: &GameProfile,
+        rebar: Option<&ResizableBarStatus>,
+        probes: &WarningProbes,
+    ) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let w = &profile.wrappers;
+
+        if w.gamescope.enabled && w.mangohud.enabled {
+            warnings.push(
+                "MangoHud does not work inside Gamescope; --mangoapp will be used instead"
+                    .to_string(),
+            );
+        }
+        if w.gamescope.extra_args.iter().any(|arg| arg.trim() == "--") {
+            warnings.push("Gamescope extra args must not contain '--'; it will be ignored".to_string());
+        }
+        if !w.gamescope.enabled && w.gamescope.mangoapp {
+            warnings.push("mangoapp only works with Gamescope enabled".to_string());
+        }
+        if w.gamescope.enabled && probes.compositor() == Compositor::GamescopeSession {
+            warnings.push(
+                "The session already runs under Gamescope (Game Mode); the Gamescope wrapper will nest a second instance"
+                    .to_string(),
+            );
+        }
+        if w.mangohud.fps_limit_enabled && w.mangohud.fps_limit.is_some() && !w.mangohud.enabled {
+            warnings.push("MangoHud FPS limit is set but MangoHud is disabled".to_string());
+        }
+
+        // Multiple frame limiters fight each other and add latency
+        let limiters = [
+            w.frame_limiter.enabled && w.frame_limiter.target_fps.is_some(),
+            w.mangohud.enabled && w.mangohud.fps_limit_enabled && w.mangohud.fps_limit.is_some(),
+            w.gamescope.enabled && w.gamescope.framelimit.map(|l| l > 0).unwrap_or(false),
+        ];
+        if limiters.iter().filter(|&&on| on).count() > 1 {
+            warnings.push(
+                "Multiple frame limiters are enabled (DXVK/VKD3D, MangoHud, Gamescope); use only one"
+                    .to_string(),
+            );
+        }
+        warnings.extend(detect_framecap_conflicts(profile));
+
+        match profile.proton.force_display_server.as_deref() {
+            None | Some("x11") => {}
+            Some("wayland") => {
+                if std::env::var("WAYLAND_DISPLAY").is_err() {
+                    warnings.push(
+                        "Wayland can't be forced from an X11 session; the setting will be ignored"
+                            .to_string(),
+                    );
+                }
+            }
+            Some(other) => {
+                warnings.push(format!(
+                    "Unknown display server '{}' (expected \"x11\" or \"wayland\")",
+                    other
+                ));
+            }
+        }
+
+        if profile.screen.allow_tearing
+            && !w.gamescope.enabled
+            && probes.compositor() != Compositor::Hyprland
+        {
+            warnings.push(
+                "Allow tearing needs Hyprland (or Gamescope); it has no effect on this compositor"
+                    .to_string(),
+            );
+        }
+        if profile.nvidia.prime && profile.nvidia.offload_target.as_deref() == Some("igpu") {
+            warnings.push(
+                "PRIME offload is on but the offload target is the iGPU; the game will run on the iGPU"
+                    .to_string(),
+            );
+        }
+
+        for lib in profile.proton.ld_preload.iter().filter(|p| !p.is_file()) {
+            warnings.push(format!("LD_PRELOAD library not found: {}", lib.display()));
+        }
+
+        if w.gamescope.enabled && w.gamescope.hdr && probes.gamescope_lacks_hdr() {
+            warnings.push(
+                "The installed gamescope doesn't support --hdr-enabled; HDR will stay off until gamescope is updated"
+                    .to_string(),
+            );
+        }
+
+        // Compare the gamescope output against the monitor the game will run on
+        if w.gamescope.enabled && (w.gamescope.width.is_some() || w.gamescope.dsr_enabled) {
+            if let Some(monitor) = target_monitor(profile, probes.monitors()) {
+                warnings.extend(validate_gamescope_resolution(profile, monitor));
+            }
+        }
+
+        if profile.vkd3d.no_dxr && profile.vkd3d.force_dxr {
+            warnings.push("VKD3D: both 'nodxr' and 'dxr' are set".to_string());
+        }
+        if let Some(rebar) = rebar {
+            match (recommend_no_upload_hvv(rebar), profile.vkd3d.no_upload_hvv) {
+                (true, false) => warnings.push(
+                    "Resizable BAR is off: consider VKD3D 'no_upload_hvv' so D3D12 uploads don't exhaust the 256 MiB BAR"
+                        .to_string(),
+                ),
+                (false, true) => warnings.push(
+                    "Resizable BAR is on: VKD3D 'no_upload_hvv' gives up faster uploads to VRAM"
+                        .to_string(),
+                ),
+                _ => {}
+            }
+        }
+
+        // Wine scales fullscreen modes on its own; only a Gamescope render
+        // resolution that isn't a whole fraction of its output can be checked here
+        let gs = &w.gamescope;
+        if profile.proton.integer_scaling && gs.enabled {
+            if let (Some(render), Some(output)) =
+                (gs.internal_width.zip(gs.internal_height), gs.width.zip(gs.height))
+            {
+                if integer_scale_factor(output, render).is_none() {
+                    warnings.push(format!(
+                        "Integer scaling: {}x{} is not an integer fraction of the {}x{} output",
+                        render.0, render.1, output.0, output.1
+                    ));
+                }
+            }
         }
 
-        wrappers
+        warnings
+    }
+}
+
+/// System state profile warnings depend on. Each probe runs a command or
+/// queries the compositor, so it runs at most once per WarningProbes and a
+/// batch of profiles shares one.
+#[derive(Default)]
+struct WarningProbes {
+    compositor: OnceCell<Compositor>,
+    gamescope_lacks_hdr: OnceCell<bool>,
+    monitors: OnceCell<Vec<Monitor>>,
+}
+
+impl WarningProbes {
+    fn compositor(&self) -> Compositor {
+        *self.compositor.get_or_init(detect_compositor)
+    }
+
+    /// gamescope is installed but too old for --hdr-enabled
+    fn gamescope_lacks_hdr(&self) -> bool {
+        *self
+            .gamescope_lacks_hdr
+            .get_or_init(|| is_tool_available("gamescope") && !check_gamescope_hdr_support())
+    }
+
+    fn monitors(&self) -> &[Monitor] {
+        self.monitors.get_or_init(|| screen::list_monitors().unwrap_or_default())
+    }
+}
+
+/// The profile's target monitor, else the focused one, else the first active one
+fn target_monitor<'a>(profile: &GameProfile, monitors: &'a [Monitor]) -> Option<&'a Monitor> {
+    let target = profile.screen.target_monitor.as_deref();
+    monitors
+        .iter()
+        .find(|m| Some(m.name.as_str()) == target)
+        .or_else(|| monitors.iter().find(|m| m.focused))
+        .or_else(|| monitors.iter().find(|m| m.active))
+}
+
+pub(crate) fn gamescope_target_monitor(profile: &GameProfile) -> Option<Monitor> {
+    target_monitor(profile, &screen::list_monitors().ok()?).cloned()
+}
+
+/// Check the gamescope output resolution against the monitor's native mode.
+/// A DSR render resolution above the output is intentional supersampling.
+pub fn validate_gamescope_resolution(profile: &GameProfile, monitor: &Monitor) -> Vec<String> {
+    let gs = &profile.wrappers.gamescope;
+    let mut warnings = Vec::new();
+    if !gs.enabled {
+        return warnings;
+    }
+    let native = (monitor.width, monitor.height);
+
+    if let Some(output) = gs.width.zip(gs.height) {
+        if output.0 > native.0 || output.1 > native.1 {
+            warnings.push(format!(
+                "Gamescope output {}x{} is larger than {} ({}x{}); it will be cropped or fail to start",
+                output.0, output.1, monitor.name, native.0, native.1
+            ));
+        } else if output != native {
+            warnings.push(format!(
+                "Gamescope output {}x{} doesn't match {} ({}x{}); the image will be scaled or letterboxed",
+                output.0, output.1, monitor.name, native.0, native.1
+            ));
+        }
+    }
+
+    if gs.dsr_enabled {
+        let output = gs.width.zip(gs.height).unwrap_or(native);
+        match gs.dsr_width.zip(gs.dsr_height) {
+            Some(render) if render.0 <= output.0 || render.1 <= output.1 => {
+                warnings.push(format!(
+                    "DSR render resolution {}x{} should be above the {}x{} output",
+                    render.0, render.1, output.0, output.1
+                ));
+            }
+            Some(_) => {}
+            None => warnings.push("DSR is enabled without a render resolution".to_string()),
+        }
+    }
+
+    warnings
+}
+
+/// Every frame cap the profile applies, per layer, in launch order. Custom env
+/// vars win over the profile fields, and the frame limiter over vkd3d.frame_rate.
+fn frame_caps(profile: &GameProfile) -> Vec<(&'static str, u32)> {
+    let w = &profile.wrappers;
+    let custom = |key: &str| {
+        profile
+            .custom_env
+            .get(key)
+            .and_then(|v| v.trim().parse::<u32>().ok())
+    };
+    let limiter = w.frame_limiter.target_fps.filter(|_| w.frame_limiter.enabled);
+    let vkd3d = (profile.vkd3d.frame_rate > 0).then_some(profile.vkd3d.frame_rate);
+
+    [
+        ("DXVK_FRAME_RATE", custom("DXVK_FRAME_RATE").or(limiter)),
+        ("VKD3D_FRAME_RATE", custom("VKD3D_FRAME_RATE").or(limiter).or(vkd3d)),
+        (
+            "MangoHud fps_limit",
+            w.mangohud
+                .fps_limit
+                .filter(|_| w.mangohud.enabled && w.mangohud.fps_limit_enabled),
+        ),
+        ("Gamescope -r", w.gamescope.framelimit.filter(|_| w.gamescope.enabled)),
+    ]
+    .into_iter()
+    .filter_map(|(layer, fps)| Some((layer, fps.filter(|&f| f > 0)?)))
+    .collect()
+}
+
+/// Warn when the layers capping the frame rate disagree on the value
+pub fn detect_framecap_conflicts(profile: &GameProfile) -> Vec<String> {
+    let caps = frame_caps(profile);
+    let Some(lowest) = caps.iter().map(|(_, fps)| *fps).min() else {
+        return Vec::new();
+    };
+    if caps.iter().all(|(_, fps)| *fps == lowest) {
+        return Vec::new();
+    }
+
+    let listed: Vec<String> = caps
+        .iter()
+        .map(|(layer, fps)| format!("{} {}", layer, fps))
+        .collect();
+    vec![format!(
+        "Frame caps disagree ({}); the lowest, {} fps, wins. Set the cap in one place, e.g. only the frame limiter",
+        listed.join(", "),
+        lowest
+    )]
+}
+
+/// The profile's game executable on disk: `executable_match` as an absolute
+/// path, or found by name in the Steam install dir
+pub(crate) fn profile_executable(profile: &GameProfile) -> Option<PathBuf> {
+    let exe = profile.executable_match.as_deref()?;
+    if Path::new(exe).is_absolute() {
+        return Some(PathBuf::from(exe)).filter(|p| p.is_file());
+    }
+    let install_dir = GameDetector::find_steam_install_path(profile.steam_appid?)?;
+    GameDetector::find_executable(&install_dir, exe)
+}
+
+/// Settings that do nothing for a 32-bit game
+pub fn bitness_warnings(profile: &GameProfile, bitness: &str) -> Vec<String> {
+    if bitness != "32" {
+        return Vec::new();
+    }
+    let changed = diff_profiles(profile, &GameProfile::default());
+    let sets = |prefix: &str| changed.iter().any(|d| d.field.starts_with(prefix));
+
+    let mut warnings = Vec::new();
+    if sets("dlss.") {
+        warnings.push(
+            "The game executable is 32-bit; DLSS is 64-bit only, so the DLSS settings have no effect"
+                .to_string(),
+        );
+    }
+    if sets("vkd3d.") {
+        warnings.push(
+            "The game executable is 32-bit; D3D12 games are almost always 64-bit, so the VKD3D settings likely have no effect"
+                .to_string(),
+        );
+    }
+    warnings
+}
+
+/// Whether VKD3D should avoid host-visible VRAM for uploads: only worth it
+/// when the BAR window is small, with ReBAR it's the fast path
+pub fn recommend_no_upload_hvv(rebar: &ResizableBarStatus) -> bool {
+    !rebar.enabled
+}
+
+/// `:`-joined LD_PRELOAD of the existing profile libraries, followed by the
+/// inherited value; None when no profile library exists
+fn ld_preload_value(
+    libs: &[PathBuf],
+    inherited: Option<&str>,
+    exists: impl Fn(&Path) -> bool,
+) -> Option<String> {
+    let mut entries: Vec<String> = libs
+        .iter()
+        .filter(|lib| exists(lib))
+        .map(|lib| lib.to_string_lossy().to_string())
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+    for entry in inherited.into_iter().flat_map(|value| value.split([':', ' '])) {
+        if !entry.is_empty() && !entries.iter().any(|e| e == entry) {
+            entries.push(entry.to_string());
+        }
+    }
+    Some(entries.join(":"))
+}
+
+/// Copy of a profile with risky tweaks reset to defaults. Overlays and
+/// gamemode are kept so the A/B comparison still shows frame data.
+fn safe_copy(profile: &GameProfile) -> GameProfile {
+    let mut safe = profile.clone();
+    safe.name = format!("{}-safe", profile.name);
+    safe.description = Some(format!("Safe defaults of '{}'", profile.name));
+    // Unbound so it doesn't silently take over the game's launches; see
+    // `ProfileManager::make_safe`
+    safe.steam_appid = None;
+    safe.executable_match = None;
+    safe.is_template = true;
+
+    // Driver/translation-layer hacks
+    safe.vkd3d.force_static_cbv = false;
+    safe.vkd3d.dxr12 = false;
+    safe.vkd3d.force_dxr = false;
+    safe.vkd3d.no_upload_hvv = false;
+    safe.dxvk.async_compile = false;
+    safe.nvidia.smooth_motion = false;
+    safe.nvidia.max_frames_allowed = None;
+    safe.dlss.fg_override = false;
+    safe.dlss.fg_multi_frame = None;
+    safe.dlss.sr_preset = None;
+    safe.dlss.rr_preset = None;
+    safe.nvidia.powermizer_mode = None;
+    safe.nvidia.gsync_allowed = None;
+    safe.dxvk.hud = None;
+
+    // Injected libraries, arbitrary env and gamescope flags can't be vetted
+    safe.proton.ld_preload.clear();
+    safe.custom_env.clear();
+    safe.wrappers.gamescope.extra_args.clear();
+
+    // Clock offsets and power limits live in the LACT profile
+    safe.wrappers.lact_profile = None;
+
+    // Frame caps and swapchain latency tweaks
+    safe.wrappers.frame_limiter = FrameLimiterSettings::default();
+    safe.vkd3d.frame_rate = 0;
+    safe.wrappers.gamescope.framelimit = None;
+    safe.wrappers.gamescope.unfocused_framelimit = None;
+    safe.wrappers.mangohud.fps_limit_enabled = false;
+    safe.wrappers.mangohud.fps_limit = None;
+
+    safe
+}
+
+/// Whether a template suits a GPU of `gpu_generation`. A pre-Pascal GPU fails
+/// every minimum; an unrecognized one may well be newer than the table, so it
+/// gets every template.
+fn template_applies(template: &GameProfile, gpu_generation: Option<&str>) -> bool {
+    let Some(required) = template.min_gpu_generation.as_deref().and_then(generation_rank) else {
+        return true;
+    };
+    match gpu_generation {
+        Some(gpu) if is_pre_pascal(gpu) => false,
+        Some(gpu) => generation_rank(gpu).is_none_or(|gpu| gpu >= required),
+        None => true,
+    }
+}
+
+/// Compare two profiles field by field, skipping identity fields
+pub fn diff_profiles(profile: &GameProfile, baseline: &GameProfile) -> Vec<FieldDiff> {
+    let value = serde_json::to_value(profile).unwrap_or_default();
+    let base = serde_json::to_value(baseline).unwrap_or_default();
+
+    let mut diffs = Vec::new();
+    collect_diffs("", &value, &base, &mut diffs);
+    diffs.retain(|d| {
+        let top = d.field.split('.').next().unwrap_or("");
+        !IDENTITY_FIELDS.contains(&top)
+    });
+    diffs
+}
+
+fn collect_diffs(
+    prefix: &str,
+    value: &serde_json::Value,
+    baseline: &serde_json::Value,
+    diffs: &mut Vec<FieldDiff>,
+) {
+    use serde_json::Value;
+
+    if let (Value::Object(a), Value::Object(b)) = (value, baseline) {
+        let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+        keys.sort();
+        keys.dedup();
+
+        for key in keys {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            collect_diffs(
+                &path,
+                a.get(key).unwrap_or(&Value::Null),
+                b.get(key).unwrap_or(&Value::Null),
+                diffs,
+            );
+        }
+    } else if value != baseline {
+        diffs.push(FieldDiff {
+            field: prefix.to_string(),
+            value: value.clone(),
+            baseline: baseline.clone(),
+        });
+    }
+}
+
+/// Walk the original document and report values missing or changed after a round trip.
+/// Keys only present after the round trip are filled-in defaults, not losses.
+fn collect_lost_fields(
+    prefix: &str,
+    original: &toml::Value,
+    reparsed: &toml::Value,
+    issues: &mut Vec<String>,
+) {
+    match (original, reparsed) {
+        (toml::Value::Table(a), toml::Value::Table(b)) => {
+            let mut keys: Vec<&String> = a.keys().collect();
+            keys.sort();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                match b.get(key) {
+                    Some(value) => collect_lost_fields(&path, &a[key], value, issues),
+                    None => issues.push(format!("{}: dropped (was {})", path, a[key])),
+                }
+            }
+        }
+        _ if original != reparsed => {
+            issues.push(format!("{}: {} became {}", prefix, original, reparsed));
+        }
+        _ => {}
+    }
+}
+
+/// A profile is orphaned if it's bound to a game (by appid or executable) that
+/// no longer shows up in detection. Unbound profiles (e.g. global) never are.
+fn is_orphaned(profile: &GameProfile, games: &[Game]) -> bool {
+    let bound = profile.steam_appid.is_some() || profile.executable_match.is_some();
+    bound && bound_game(profile, games).is_none()
+}
+
+/// The installed game a profile is bound to, by appid or executable
+fn bound_game<'a>(profile: &GameProfile, games: &'a [Game]) -> Option<&'a Game> {
+    if let Some(appid) = profile.steam_appid {
+        let appid = appid.to_string();
+        return games.iter().find(|g| g.id == appid);
+    }
+
+    if let Some(exe) = &profile.executable_match {
+        // Most launchers don't report executables, so also accept a name match
+        return games.iter().find(|g| {
+            g.name.eq_ignore_ascii_case(&profile.name)
+                || g
+                    .executable
+                    .as_ref()
+                    .and_then(|path| path.file_name())
+                    .map(|name| name.to_string_lossy() == exe.as_str())
+                    .unwrap_or(false)
+        });
+    }
+
+    None
+}
+
+/// Compact "what does this profile do" for a library card
+fn summarize_profile(profile: &GameProfile, games: &[Game], warning_count: usize) -> ProfileSummary {
+    let w = &profile.wrappers;
+    let dlss = &profile.dlss;
+    let badges = [
+        (dlss.upgrade || dlss.sr_override || dlss.rr_override, "DLSS"),
+        (dlss.fg_override || profile.nvidia.smooth_motion, "Frame Gen"),
+        ((profile.vkd3d.force_dxr || profile.vkd3d.dxr12) && !profile.vkd3d.no_dxr, "RT"),
+        (profile.proton.enable_hdr, "HDR"),
+        (w.gamescope.enabled, "Gamescope"),
+        (w.mangohud.enabled, "MangoHud"),
+        (w.gamemode, "GameMode"),
+        (profile.vkbasalt.is_active(), "vkBasalt"),
+        (w.lact_profile.is_some(), "LACT"),
+    ]
+    .into_iter()
+    .filter(|(on, _)| *on)
+    .map(|(_, badge)| badge.to_string())
+    .collect();
+
+    // With several layers capping, the lowest cap is the one the game runs at
+    let frame_cap = frame_caps(profile).into_iter().map(|(_, fps)| fps).min();
+
+    ProfileSummary {
+        name: profile.name.clone(),
+        game: bound_game(profile, games).map(|g| g.name.clone()),
+        is_template: profile.is_template,
+        badges,
+        frame_cap,
+        sync_mode: profile
+            .proton
+            .sync_mode
+            .clone()
+            .filter(|mode| mode != "default"),
+        warning_count,
+    }
+}
+
+/// Env vars together with the profile field that set each one
+#[derive(Default)]
+struct EnvBuilder {
+    vars: HashMap<String, String>,
+    sources: HashMap<String, String>,
+}
+
+impl EnvBuilder {
+    fn set(&mut self, key: &str, value: impl Into<String>, source: &str) {
+        self.vars.insert(key.to_string(), value.into());
+        self.sources.insert(key.to_string(), source.to_string());
+    }
+}
+
+/// Export block section: category order, header and its `(key, value)` vars
+type EnvGroup<'a> = (usize, &'static str, Vec<(&'a String, &'a String)>);
+
+/// Export block section for an env var, from the field that set it
+fn env_category(source: &str) -> (usize, &'static str) {
+    const CATEGORIES: &[(&str, &str)] = &[
+        ("dlss", "DLSS"),
+        ("dxvk", "DXVK"),
+        ("vkd3d", "VKD3D"),
+        ("nvidia", "NVIDIA"),
+        ("proton", "Proton"),
+        ("wrappers.frame_limiter", "Frame limiter"),
+        ("wrappers.mangohud", "MangoHud"),
+        ("vkbasalt", "vkBasalt"),
+        ("custom_env", "Custom"),
+    ];
+
+    CATEGORIES
+        .iter()
+        .position(|(prefix, _)| {
+            source == *prefix || source.starts_with(&format!("{}.", prefix))
+        })
+        .map(|i| (i, CATEGORIES[i].1))
+        .unwrap_or((CATEGORIES.len(), "Other"))
+}
+
+/// Base name a profile's files are stored under (profile, lockfile, history, vkBasalt)
+pub(crate) fn profile_stem(name: &str) -> String {
+    name.to_lowercase().replace(' ', "_")
+}
+
+/// Filename a profile is stored under
+fn profile_filename(name: &str) -> String {
+    format!("{}.toml", profile_stem(name))
+}
+
+fn display_filename(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Position of a wrapper in the launch chain (lower = outermost)
+fn wrapper_rank(cmd: &str) -> u8 {
+    match cmd {
+        "lact" => 0,
+        "gamescope" => 1,
+        "mangohud" => 2,
+        "game-performance" => 3,
+        "dlss-swapper" => 4,
+        "systemd-inhibit" | "gnome-session-inhibit" => 5,
+        "gamemoderun" => 6,
+        "nice" => 7,
+        "ionice" => 8,
+        _ => 5,
     }
 }
 
+/// Reorder wrapper segments into the known-correct nesting:
+/// LACT switch, gamescope (outermost), mangohud, ..., gamemoderun, then nice/ionice
+/// so the priority change only applies to the game process tree
+pub fn normalize_wrapper_order(mut wrappers: Vec<Vec<String>>) -> Vec<Vec<String>> {
+    wrappers.sort_by_key(|segment| {
+        segment
+            .first()
+            .and_then(|cmd| cmd.split_whitespace().next())
+            .map(wrapper_rank)
+            .unwrap_or(u8::MAX)
+    });
+    wrappers
+}
+
 /// Check if LACT is installed
 pub fn is_lact_available() -> bool {
     std::process::Command::new("which")
@@ -615,3 +2277,846 @@ pub fn get_lact_profiles() -> Vec<String> {
         })
         .unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> ProfileManager {
+        ProfileManager::with_profiles_dir(std::env::temp_dir().join("unvcpfl-test-profiles"))
+    }
+
+    /// Fresh, empty profiles dir for tests that look at every file in it
+    fn isolated_manager(test: &str) -> ProfileManager {
+        let dir = std::env::temp_dir().join(format!("unvcpfl-test-{}", test));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        ProfileManager::with_profiles_dir(dir)
+    }
+
+    #[test]
+    fn test_suggest_executable_matches_for_faugus_games() {
+        let manager = isolated_manager("suggest-exe");
+        let profile = GameProfile {
+            name: "Hollow Knight".to_string(),
+            ..Default::default()
+        };
+        manager.save_profile(&profile).unwrap();
+
+        let games = vec![Game {
+            id: "faugus-hollow-knight".to_string(),
+            name: "Hollow Knight".to_string(),
+            executable: Some(PathBuf::from("/games/Hollow Knight/hollow_knight.exe")),
+            source: GameSource::Faugus,
+            install_path: Some(PathBuf::from("/home/u/Faugus/hollow-knight")),
+            icon_url: None,
+        }];
+        assert_eq!(
+            manager.suggest_executable_matches("Hollow Knight", &games).unwrap(),
+            vec!["hollow_knight.exe"]
+        );
+        assert!(manager.suggest_executable_matches("Hollow Knight", &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_prune_needs_confirmation_and_detected_games() {
+        let manager = isolated_manager("prune");
+        for (name, appid) in [("Gone", 1u32), ("Kept", 2)] {
+            let profile = GameProfile {
+                name: name.to_string(),
+                steam_appid: Some(appid),
+                ..Default::default()
+            };
+            manager.save_profile(&profile).unwrap();
+        }
+        let names = vec!["Gone".to_string(), "Kept".to_string()];
+        let steam_game = |id: &str| Game {
+            id: id.to_string(),
+            name: String::new(),
+            executable: None,
+            source: GameSource::Steam,
+            install_path: None,
+            icon_url: None,
+        };
+
+        assert!(manager.prune_orphaned_profiles(&names, &[]).is_err());
+        let lutris_only = Game {
+            source: GameSource::Lutris,
+            ..steam_game("lutris-1")
+        };
+        assert!(manager.prune_orphaned_profiles(&names, &[lutris_only]).is_err());
+
+        let games = vec![steam_game("2")];
+        assert!(manager.prune_orphaned_profiles(&[], &games).unwrap().is_empty());
+        assert_eq!(manager.prune_orphaned_profiles(&names, &games).unwrap(), vec!["Gone"]);
+        assert!(manager.get_profile("Kept").is_some());
+    }
+
+    fn write_profile_file(manager: &ProfileManager, file: &str, name: &str, age_secs: u64) {
+        let profile = GameProfile {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        write_profile_as(manager, file, &profile, age_secs);
+    }
+
+    /// Write a profile under any filename, last modified `age_secs` ago
+    fn write_profile_as(manager: &ProfileManager, file: &str, profile: &GameProfile, age_secs: u64) {
+        let path = manager.profiles_dir.join(file);
+        fs::write(&path, toml::to_string_pretty(&profile).unwrap()).unwrap();
+        let mtime = std::time::SystemTime::now() - std::time::Duration::from_secs(age_secs);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_duplicate_bindings_resolve_deterministically() {
+        let manager = isolated_manager("duplicate-bindings");
+        let bind = |file: &str, name: &str, is_template: bool, age_secs: u64| {
+            let profile = GameProfile {
+                name: name.to_string(),
+                is_template,
+                steam_appid: Some(292030),
+                executable_match: Some("witcher3.exe".to_string()),
+                ..Default::default()
+            };
+            write_profile_as(&manager, file, &profile, age_secs);
+        };
+        bind("older.toml", "Older", false, 100);
+        bind("newer.toml", "Newer", false, 10);
+        bind("template.toml", "Template", true, 0);
+
+        let chosen = manager.get_profile_by_executable("witcher3.exe").unwrap();
+        assert_eq!(chosen.name, "Newer");
+
+        let dups = manager.find_duplicate_bindings();
+        assert_eq!(dups.len(), 2);
+        assert_eq!(dups[0].steam_appid, Some(292030));
+        assert_eq!(dups[0].profiles, vec!["Newer", "Older"]);
+        assert_eq!(dups[1].executable.as_deref(), Some("witcher3.exe"));
+    }
+
+    #[test]
+    fn test_profile_history_is_capped_and_newest_first() {
+        let manager = isolated_manager("history-cap");
+        let mut profile = GameProfile {
+            name: "Hades".to_string(),
+            ..Default::default()
+        };
+        // Saves land within the same millisecond; none may overwrite another
+        for i in 0..MAX_PROFILE_HISTORY + 3 {
+            profile.custom_args = Some(format!("-run {}", i));
+            manager.save_profile(&profile).unwrap();
+        }
+
+        let versions = manager.list_profile_versions("Hades");
+        assert_eq!(versions.len(), MAX_PROFILE_HISTORY);
+        assert!(versions.windows(2).all(|w| w[0] > w[1]));
+
+        // Restoring archives the current version, which prunes the oldest one
+        let restore = |version| manager.restore_profile_version("Hades", version).unwrap();
+        let oldest = *versions.last().unwrap();
+        assert_eq!(restore(oldest).custom_args.as_deref(), Some("-run 2"));
+        assert_eq!(restore(versions[0]).custom_args.as_deref(), Some("-run 11"));
+        assert!(manager.restore_profile_version("Hades", 1).is_err());
+    }
+
+    #[test]
+    fn test_restore_profile_version_can_be_undone() {
+        let manager = isolated_manager("history-restore");
+        let mut profile = GameProfile {
+            name: "Celeste".to_string(),
+            custom_args: Some("-old".to_string()),
+            ..Default::default()
+        };
+        manager.save_profile(&profile).unwrap();
+        profile.custom_args = Some("-new".to_string());
+        manager.save_profile(&profile).unwrap();
+
+        let restore_newest = || {
+            let version = manager.list_profile_versions("Celeste")[0];
+            manager.restore_profile_version("Celeste", version).unwrap();
+            manager.get_profile("Celeste").unwrap().custom_args
+        };
+        assert_eq!(manager.list_profile_versions("Celeste").len(), 1);
+        assert_eq!(restore_newest().as_deref(), Some("-old"));
+
+        // The replaced "-new" version was archived
+        assert_eq!(manager.list_profile_versions("Celeste").len(), 2);
+        assert_eq!(restore_newest().as_deref(), Some("-new"));
+
+        manager.delete_profile("Celeste").unwrap();
+        assert!(manager.list_profile_versions("Celeste").is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_renames_mismatched_file() {
+        let manager = isolated_manager("reconcile-rename");
+        write_profile_file(&manager, "old_name.toml", "New Name", 0);
+
+        let report = manager.reconcile_profiles().unwrap();
+
+        assert_eq!(report.renamed, vec!["old_name.toml -> new_name.toml"]);
+        assert!(report.removed_duplicates.is_empty());
+        assert!(!manager.profiles_dir.join("old_name.toml").exists());
+        assert_eq!(manager.get_profile("New Name").unwrap().name, "New Name");
+    }
+
+    #[test]
+    fn test_reconcile_keeps_newest_duplicate() {
+        let manager = isolated_manager("reconcile-merge");
+        write_profile_file(&manager, "cyberpunk.toml", "Cyberpunk", 60);
+        write_profile_file(&manager, "cyberpunk_2077.toml", "Cyberpunk", 0);
+
+        let report = manager.reconcile_profiles().unwrap();
+
+        assert_eq!(report.removed_duplicates, vec!["cyberpunk.toml"]);
+        assert_eq!(report.renamed, vec!["cyberpunk_2077.toml -> cyberpunk.toml"]);
+        assert_eq!(manager.list_profiles().len(), 1);
+        assert_eq!(manager.list_profile_versions("Cyberpunk").len(), 1);
+    }
+
+    #[test]
+    fn test_reconcile_leaves_matching_files_alone() {
+        let manager = isolated_manager("reconcile-noop");
+        write_profile_file(&manager, "global_settings.toml", "Global Settings", 0);
+
+        let report = manager.reconcile_profiles().unwrap();
+
+        assert!(report.renamed.is_empty());
+        assert!(report.removed_duplicates.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_swapped_names() {
+        let manager = isolated_manager("reconcile-swap");
+        write_profile_file(&manager, "alpha.toml", "Beta", 0);
+        write_profile_file(&manager, "beta.toml", "Alpha", 0);
+        fs::write(manager.profiles_dir.join("gamma.toml"), "not a profile").unwrap();
+        write_profile_file(&manager, "delta.toml", "Gamma", 0);
+
+        let report = manager.reconcile_profiles().unwrap();
+
+        assert_eq!(
+            report.renamed,
+            vec!["alpha.toml -> beta.toml", "beta.toml -> alpha.toml"]
+        );
+        assert_eq!(report.skipped, vec!["delta.toml"]);
+        assert_eq!(manager.get_profile("Alpha").unwrap().name, "Alpha");
+        assert_eq!(manager.get_profile("Beta").unwrap().name, "Beta");
+        assert_eq!(
+            fs::read_to_string(manager.profiles_dir.join("gamma.toml")).unwrap(),
+            "not a profile"
+        );
+    }
+
+    #[test]
+    fn test_reconcile_recovers_interrupted_moves() {
+        let manager = isolated_manager("reconcile-leftover");
+        write_profile_file(&manager, "alpha.toml", "Alpha", 0);
+        fs::rename(
+            manager.profiles_dir.join("alpha.toml"),
+            manager.profiles_dir.join("alpha.toml.reconcile"),
+        )
+        .unwrap();
+        write_profile_file(&manager, "beta.toml", "Beta", 0);
+        write_profile_file(&manager, "gamma.toml.reconcile", "Gamma", 0);
+        // A leftover whose old filename has been taken meanwhile
+        write_profile_file(&manager, "delta.toml", "Delta", 0);
+        write_profile_file(&manager, "delta.toml.reconcile", "Epsilon", 0);
+        assert_eq!(manager.list_profiles().len(), 2);
+
+        manager.reconcile_profiles().unwrap();
+
+        for name in ["Alpha", "Beta", "Gamma", "Delta", "Epsilon"] {
+            assert_eq!(manager.get_profile(name).unwrap().name, name);
+        }
+        assert_eq!(manager.list_profiles().len(), 5);
+    }
+
+    #[test]
+    fn test_trace_env_sources_last_writer_wins() {
+        let mut profile = GameProfile::default();
+        profile.wrappers.frame_limiter.enabled = true;
+        profile.wrappers.frame_limiter.target_fps = Some(120);
+        profile
+            .custom_env
+            .insert("DXVK_FRAME_RATE".to_string(), "60".to_string());
+
+        let sources = manager().trace_env_sources(&profile);
+
+        assert_eq!(sources["DXVK_FRAME_RATE"], "custom_env.DXVK_FRAME_RATE");
+        assert_eq!(sources["VKD3D_FRAME_RATE"], "wrappers.frame_limiter.target_fps");
+    }
+
+    #[test]
+    fn test_set_profile_flag_validates_path() {
+        let manager = isolated_manager("set-flag");
+        let profile = GameProfile {
+            name: "Flag Test".to_string(),
+            ..Default::default()
+        };
+        manager.save_profile(&profile).unwrap();
+
+        let updated = manager
+            .set_profile_flag("Flag Test", "wrappers.gamemode", true)
+            .unwrap();
+        assert!(updated.wrappers.gamemode);
+        assert!(manager.get_profile("Flag Test").unwrap().wrappers.gamemode);
+
+        let updated = manager
+            .set_profile_flag("Flag Test", "nvidia.gsync_allowed", false)
+            .unwrap();
+        assert_eq!(updated.nvidia.gsync_allowed, Some(false));
+        let updated = manager
+            .set_profile_flag("Flag Test", "nvidia.shader_disk_cache", true)
+            .unwrap();
+        assert_eq!(updated.nvidia.shader_disk_cache, Some(true));
+
+        assert!(manager.set_profile_flag("Flag Test", "wrappers.nope", true).is_err());
+        assert!(manager.set_profile_flag("Flag Test", "dxvk.hud", true).is_err());
+        assert!(manager.set_profile_flag("Flag Test", "wrappers", true).is_err());
+    }
+
+    #[test]
+    fn test_roundtrip_reports_unknown_fields() {
+        let manager = isolated_manager("roundtrip");
+        fs::write(
+            manager.profiles_dir.join("roundtrip.toml"),
+            "name = \"Roundtrip\"\nlegacy_flag = true\n\n[wrappers]\ngamemode = true\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manager.verify_profile_roundtrip("Roundtrip").unwrap(),
+            vec!["legacy_flag: dropped (was true)"]
+        );
+    }
+
+    #[test]
+    fn test_dxvk_hud_composed_from_toggles() {
+        let mut dxvk = DxvkSettings {
+            hud_fps: true,
+            hud_gpuload: true,
+            hud_version: true,
+            ..Default::default()
+        };
+        assert_eq!(dxvk.hud_value().as_deref(), Some("fps,gpuload,version"));
+
+        // The raw string wins over the toggles
+        dxvk.hud = Some("full".to_string());
+        assert_eq!(dxvk.hud_value().as_deref(), Some("full"));
+
+        assert_eq!(DxvkSettings::default().hud_value(), None);
+    }
+
+    #[test]
+    fn test_safe_copy_drops_risky_options() {
+        let mut profile = GameProfile {
+            name: "Cyberpunk".to_string(),
+            steam_appid: Some(1091500),
+            ..Default::default()
+        };
+        profile.vkd3d.force_static_cbv = true;
+        profile.dxvk.async_compile = true;
+        profile.nvidia.smooth_motion = true;
+        profile.wrappers.lact_profile = Some("oc".to_string());
+        profile.wrappers.frame_limiter.target_fps = Some(30);
+        profile.wrappers.mangohud.enabled = true;
+        profile.wrappers.mangohud.fps_limit_enabled = true;
+        profile.wrappers.mangohud.fps_limit = Some(30);
+        profile.wrappers.gamemode = true;
+        profile.dlss.sr_preset = Some("render_preset_k".to_string());
+        profile.dlss.rr_preset = Some("render_preset_d".to_string());
+        profile.nvidia.powermizer_mode = Some("max".to_string());
+        profile.nvidia.gsync_allowed = Some(false);
+        profile.dxvk.hud = Some("full".to_string());
+        profile.proton.ld_preload = vec![PathBuf::from("/usr/lib/libhook.so")];
+        profile
+            .custom_env
+            .insert("DXVK_CONFIG".to_string(), "d3d11.maxFeatureLevel = 12_1".to_string());
+        profile.wrappers.gamescope.extra_args = vec!["--force-grab-cursor".to_string()];
+
+        let safe = safe_copy(&profile);
+        assert_eq!(safe.name, "Cyberpunk-safe");
+        assert_eq!(safe.steam_appid, None);
+        assert!(safe.is_template);
+        assert!(!safe.vkd3d.force_static_cbv);
+        assert!(!safe.dxvk.async_compile);
+        assert!(!safe.nvidia.smooth_motion);
+        assert_eq!(safe.wrappers.lact_profile, None);
+        assert_eq!(safe.wrappers.frame_limiter.target_fps, None);
+        assert!(safe.wrappers.mangohud.enabled);
+        assert!(!safe.wrappers.mangohud.fps_limit_enabled);
+        assert_eq!(safe.wrappers.mangohud.fps_limit, None);
+        assert!(safe.wrappers.gamemode);
+        assert_eq!(safe.dlss.sr_preset, None);
+        assert_eq!(safe.dlss.rr_preset, None);
+        assert_eq!(safe.nvidia.powermizer_mode, None);
+        assert_eq!(safe.nvidia.gsync_allowed, None);
+        assert_eq!(safe.dxvk.hud, None);
+        assert!(safe.proton.ld_preload.is_empty());
+        assert!(safe.custom_env.is_empty());
+        assert!(safe.wrappers.gamescope.extra_args.is_empty());
+    }
+
+    #[test]
+    fn test_make_safe_never_overwrites() {
+        let manager = isolated_manager("make-safe");
+        let profile = GameProfile {
+            name: "Cyberpunk".to_string(),
+            executable_match: Some("Cyberpunk2077.exe".to_string()),
+            ..Default::default()
+        };
+        manager.save_profile(&profile).unwrap();
+
+        assert_eq!(manager.make_safe("Cyberpunk").unwrap().name, "Cyberpunk-safe");
+        assert_eq!(manager.make_safe("Cyberpunk").unwrap().name, "Cyberpunk-safe-2");
+        assert!(manager.get_profile("Cyberpunk-safe").is_some());
+
+        // The copies don't take the game's launches over from the original
+        assert_eq!(
+            manager.get_profile_by_executable("Cyberpunk2077.exe").unwrap().name,
+            "Cyberpunk"
+        );
+        assert!(manager.find_duplicate_bindings().is_empty());
+    }
+
+    #[test]
+    fn test_env_export_block_grouped_and_sorted() {
+        let mut profile = GameProfile::default();
+        profile.dlss.upgrade = true;
+        profile.dlss.indicator = true;
+        profile.dxvk.hud = Some("fps,gpuload".to_string());
+        profile
+            .custom_env
+            .insert("MY_VAR".to_string(), "has space".to_string());
+
+        assert_eq!(
+            manager().build_env_export_block(&profile),
+            "# DLSS\n\
+             export PROTON_DLSS_INDICATOR=1\n\
+             export PROTON_DLSS_UPGRADE=1\n\
+             \n\
+             # DXVK\n\
+             export DXVK_HUD=fps,gpuload\n\
+             \n\
+             # Custom\n\
+             export MY_VAR='has space'\n"
+        );
+    }
+
+    #[test]
+    fn test_wrapper_order_without_gamescope() {
+        let mut profile = GameProfile::default();
+        profile.wrappers.gamemode = true;
+        profile.wrappers.mangohud.enabled = true;
+        profile.wrappers.game_performance = true;
+
+        assert_eq!(
+            manager().build_wrapper_cmd(&profile),
+            vec!["mangohud", "game-performance", "gamemoderun"]
+        );
+    }
+
+    #[test]
+    fn test_priority_wrappers_innermost() {
+        let mut profile = GameProfile::default();
+        profile.wrappers.gamemode = true;
+        profile.cpu.nice = Some(-5);
+        profile.cpu.ionice_class = Some(2);
+
+        assert_eq!(
+            manager().build_wrapper_cmd(&profile),
+            vec!["gamemoderun", "nice", "-n", "-5", "ionice", "-t", "-c", "2"]
+        );
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_nice() {
+        let profile = GameProfile {
+            name: "Nice Test".to_string(),
+            cpu: CpuSettings {
+                nice: Some(25),
+                ionice_class: None,
+            },
+            ..Default::default()
+        };
+
+        assert!(manager().save_profile(&profile).is_err());
+    }
+
+    #[test]
+    fn test_wrapper_order_gamescope_outermost() {
+        let mut profile = GameProfile::default();
+        profile.wrappers.gamemode = true;
+        profile.wrappers.mangohud.enabled = true;
+        profile.wrappers.gamescope.enabled = true;
+        profile.wrappers.lact_profile = Some("Gaming".to_string());
+
+        assert_eq!(
+            manager().build_wrapper_cmd(&profile),
+            vec![
+                "lact cli profile set \"Gaming\" &&",
+                "gamescope",
+                "--mangoapp",
+                "--",
+                "gamemoderun",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gamescope_nested_and_unfocused_framelimit() {
+        let mut profile = GameProfile::default();
+        profile.wrappers.gamescope.enabled = true;
+        profile.wrappers.gamescope.framelimit = Some(144);
+        profile.wrappers.gamescope.unfocused_framelimit = Some(30);
+
+        let cmd = manager().build_wrapper_cmd(&profile);
+        assert!(cmd.windows(2).any(|w| w == ["-r", "144"]));
+        assert!(cmd.windows(2).any(|w| w == ["-o", "30"]));
+    }
+
+    #[test]
+    fn test_gamescope_scaling_mode() {
+        let mut profile = GameProfile::default();
+        profile.wrappers.gamescope.enabled = true;
+        profile.wrappers.gamescope.scaling_mode = Some("integer".to_string());
+        let cmd = manager().build_wrapper_cmd(&profile);
+        assert!(cmd.windows(2).any(|w| w == ["-S", "integer"]));
+
+        profile.wrappers.gamescope.scaling_mode = Some("zoom".to_string());
+        let cmd = manager().build_wrapper_cmd(&profile);
+        assert!(!cmd.contains(&"-S".to_string()));
+        profile.name = "Scaling Test".to_string();
+        assert!(manager().save_profile(&profile).is_err());
+    }
+
+    #[test]
+    fn test_nvidia_gl_frames_and_shader_cache_env() {
+        let mut profile = GameProfile::default();
+        profile.nvidia.max_frames_allowed = Some(1);
+        profile.nvidia.shader_disk_cache = Some(false);
+        profile.nvidia.shader_disk_cache_path = Some("/mnt/fast/glcache".to_string());
+
+        let env = manager().build_env_vars(&profile);
+        assert_eq!(env["__GL_MaxFramesAllowed"], "1");
+        assert_eq!(env["__GL_SHADER_DISK_CACHE"], "0");
+        assert_eq!(env["__GL_SHADER_DISK_CACHE_PATH"], "/mnt/fast/glcache");
+
+        profile.nvidia.max_frames_allowed = Some(8);
+        assert!(!manager().build_env_vars(&profile).contains_key("__GL_MaxFramesAllowed"));
+    }
+
+    #[test]
+    fn test_vkbasalt_without_effects_is_off() {
+        let mut profile = GameProfile::default();
+        profile.vkbasalt.enabled = true;
+        assert!(!profile.vkbasalt.is_active());
+        assert!(!manager().build_env_vars(&profile).contains_key("ENABLE_VKBASALT"));
+
+        profile.vkbasalt.effects = vec!["cas".to_string()];
+        assert_eq!(manager().build_env_vars(&profile)["ENABLE_VKBASALT"], "1");
+    }
+
+    #[test]
+    fn test_allow_tearing_turns_off_gl_vsync() {
+        let mut profile = GameProfile::default();
+        profile.screen.allow_tearing = true;
+        assert_eq!(manager().build_env_vars(&profile)["__GL_SYNC_TO_VBLANK"], "0");
+
+        profile.nvidia.vsync = Some("on".to_string());
+        assert_eq!(manager().build_env_vars(&profile)["__GL_SYNC_TO_VBLANK"], "1");
+    }
+
+    #[test]
+    fn test_gsync_allowed_env() {
+        let mut profile = GameProfile::default();
+        assert!(!manager().build_env_vars(&profile).contains_key("__GL_GSYNC_ALLOWED"));
+
+        profile.nvidia.gsync_allowed = Some(true);
+        assert_eq!(manager().build_env_vars(&profile)["__GL_GSYNC_ALLOWED"], "1");
+        profile.nvidia.gsync_allowed = Some(false);
+        assert_eq!(manager().build_env_vars(&profile)["__GL_GSYNC_ALLOWED"], "0");
+    }
+
+    #[test]
+    fn test_bitness_warnings_for_32_bit_games() {
+        let mut profile = GameProfile::default();
+        profile.dlss.upgrade = true;
+        profile.vkd3d.no_upload_hvv = true;
+
+        assert_eq!(bitness_warnings(&profile, "32").len(), 2);
+        assert!(bitness_warnings(&profile, "64").is_empty());
+
+        profile.vkd3d.no_upload_hvv = false;
+        let warnings = bitness_warnings(&profile, "32");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("DLSS"));
+    }
+
+    #[test]
+    fn test_templates_scoped_by_gpu_generation() {
+        assert_eq!(gpu_generation("NVIDIA GeForce RTX 4080"), Some("ada"));
+        assert_eq!(gpu_generation("NVIDIA GeForce RTX 5070 Ti Laptop GPU"), Some("blackwell"));
+        assert_eq!(gpu_generation("Quadro RTX 4000"), Some("turing"));
+        assert_eq!(gpu_generation("NVIDIA RTX 6000 Ada Generation"), Some("ada"));
+        assert_eq!(gpu_generation("NVIDIA GeForce GTX 1660 SUPER"), Some("turing"));
+        assert_eq!(gpu_generation("NVIDIA TITAN Xp"), Some("pascal"));
+        assert_eq!(gpu_generation("NVIDIA TITAN X (Pascal)"), Some("pascal"));
+        assert_eq!(gpu_generation("NVIDIA GeForce GTX TITAN X"), Some("maxwell"));
+        assert_eq!(gpu_generation("NVIDIA GeForce GTX TITAN Black"), Some("kepler"));
+        assert_eq!(gpu_generation("NVIDIA GeForce GTX 970"), Some("maxwell"));
+        assert_eq!(gpu_generation("NVIDIA GeForce GTX 750 Ti"), Some("maxwell"));
+        assert_eq!(gpu_generation("NVIDIA GeForce GTX 780"), Some("kepler"));
+        assert_eq!(gpu_generation("NVIDIA GeForce MX130"), Some("maxwell"));
+        assert_eq!(gpu_generation("NVIDIA GeForce MX150"), Some("pascal"));
+        assert_eq!(gpu_generation("Tesla K80"), Some("kepler"));
+        assert_eq!(gpu_generation("NVIDIA GeForce GT 710"), None);
+
+        let smooth = GameProfile {
+            is_template: true,
+            min_gpu_generation: Some("ada".to_string()),
+            ..Default::default()
+        };
+        assert!(template_applies(&smooth, Some("blackwell")));
+        assert!(!template_applies(&smooth, Some("ampere")));
+        assert!(template_applies(&smooth, None));
+        assert!(template_applies(&GameProfile::default(), Some("pascal")));
+        let pascal = GameProfile {
+            min_gpu_generation: Some("pascal".to_string()),
+            ..smooth.clone()
+        };
+        assert!(!template_applies(&pascal, Some("maxwell")));
+        assert!(template_applies(&GameProfile::default(), Some("kepler")));
+
+        let unknown = GameProfile {
+            name: "Generation Test".to_string(),
+            min_gpu_generation: Some("kepler".to_string()),
+            ..Default::default()
+        };
+        assert!(manager().save_profile(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_offload_target_overrides_prime() {
+        let mut profile = GameProfile::default();
+        profile.nvidia.prime = true;
+        profile.nvidia.offload_target = Some("igpu".to_string());
+
+        let env = manager().build_env_vars(&profile);
+        assert_eq!(env["__NV_PRIME_RENDER_OFFLOAD"], "0");
+        assert_eq!(env["__VK_LAYER_NV_optimus"], "non_NVIDIA_only");
+        assert_eq!(env["DRI_PRIME"], "0");
+
+        profile.nvidia.offload_target = Some("dgpu".to_string());
+        assert!(manager().save_profile(&profile).is_err());
+    }
+
+    #[test]
+    fn test_ld_preload_keeps_inherited_entries_last() {
+        let libs = vec![PathBuf::from("/opt/unlocker.so"), PathBuf::from("/missing.so")];
+        let exists = |p: &Path| p != Path::new("/missing.so");
+
+        assert_eq!(
+            ld_preload_value(&libs, Some("/steam/gameoverlayrenderer.so"), exists).as_deref(),
+            Some("/opt/unlocker.so:/steam/gameoverlayrenderer.so")
+        );
+        assert_eq!(ld_preload_value(&libs[1..], Some("/a.so"), exists), None);
+    }
+
+    #[test]
+    fn test_gamescope_extra_args_before_separator() {
+        let mut profile = GameProfile::default();
+        profile.wrappers.gamescope.enabled = true;
+        profile.wrappers.gamescope.extra_args = vec![
+            "--force-grab-cursor".to_string(),
+            "--".to_string(),
+            "--expose-wayland".to_string(),
+        ];
+
+        assert_eq!(
+            manager().build_wrapper_cmd(&profile),
+            vec!["gamescope", "--force-grab-cursor", "--expose-wayland", "--"]
+        );
+    }
+
+    #[test]
+    fn test_diff_from_default_reports_changed_fields() {
+        let mut profile = GameProfile {
+            name: "Some Game".to_string(),
+            ..Default::default()
+        };
+        profile.dlss.upgrade = true;
+        profile.wrappers.gamescope.width = Some(2560);
+
+        let fields: Vec<String> = diff_profiles(&profile, &GameProfile::default())
+            .into_iter()
+            .map(|d| d.field)
+            .collect();
+        assert_eq!(fields, vec!["dlss.upgrade", "wrappers.gamescope.width"]);
+    }
+
+    #[test]
+    fn test_framecap_conflicts() {
+        let mut profile = GameProfile::default();
+        profile.wrappers.frame_limiter.enabled = true;
+        profile.wrappers.frame_limiter.target_fps = Some(117);
+        // DXVK and VKD3D both get the limiter value: no conflict
+        assert!(detect_framecap_conflicts(&profile).is_empty());
+
+        // The limiter overrides vkd3d.frame_rate, so that one isn't applied
+        profile.vkd3d.frame_rate = 60;
+        assert!(detect_framecap_conflicts(&profile).is_empty());
+
+        // A custom env var splits DXVK from VKD3D
+        profile
+            .custom_env
+            .insert("DXVK_FRAME_RATE".to_string(), "60".to_string());
+        let conflicts = detect_framecap_conflicts(&profile);
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].contains("DXVK_FRAME_RATE 60, VKD3D_FRAME_RATE 117"));
+
+        // Gamescope and MangoHud against each other, no limiter
+        let mut profile = GameProfile::default();
+        let w = &mut profile.wrappers;
+        w.mangohud.enabled = true;
+        w.mangohud.fps_limit_enabled = true;
+        w.mangohud.fps_limit = Some(141);
+        w.gamescope.enabled = true;
+        w.gamescope.framelimit = Some(144);
+        let conflicts = detect_framecap_conflicts(&profile);
+        assert!(conflicts[0].contains("the lowest, 141 fps"));
+
+        // A disabled layer doesn't count
+        profile.wrappers.gamescope.enabled = false;
+        assert!(detect_framecap_conflicts(&profile).is_empty());
+
+        // vkd3d.frame_rate alone against MangoHud
+        profile.vkd3d.frame_rate = 120;
+        assert!(detect_framecap_conflicts(&profile)[0].contains("VKD3D_FRAME_RATE 120"));
+    }
+
+    #[test]
+    fn test_warns_on_mangohud_inside_gamescope() {
+        let mut profile = GameProfile::default();
+        profile.wrappers.mangohud.enabled = true;
+        profile.wrappers.gamescope.enabled = true;
+
+        let warnings = manager().check_profile_warnings(&profile, None);
+        assert!(warnings.iter().any(|w| w.contains("mangoapp")));
+    }
+
+    #[test]
+    fn test_warns_on_non_integer_render_resolution() {
+        let mut profile = GameProfile::default();
+        profile.proton.integer_scaling = true;
+        let gs = &mut profile.wrappers.gamescope;
+        gs.enabled = true;
+        (gs.width, gs.height) = (Some(2560), Some(1440));
+        (gs.internal_width, gs.internal_height) = (Some(1920), Some(1080));
+        assert!(manager().check_profile_warnings(&profile, None).iter().any(|w| w.contains("1920x1080")));
+
+        profile.wrappers.gamescope.internal_width = Some(1280);
+        profile.wrappers.gamescope.internal_height = Some(720);
+        assert!(!manager().check_profile_warnings(&profile, None).iter().any(|w| w.contains("Integer")));
+
+        // WINE_FULLSCREEN_INTEGER_SCALING works without Gamescope
+        profile.wrappers.gamescope.enabled = false;
+        assert!(!manager().check_profile_warnings(&profile, None).iter().any(|w| w.contains("Integer")));
+    }
+
+    #[test]
+    fn test_no_upload_hvv_follows_resizable_bar() {
+        const MIB: u64 = 1024 * 1024;
+        let small_bar = ResizableBarStatus::from_sizes(256 * MIB, 12288 * MIB);
+        let rebar = ResizableBarStatus::from_sizes(16384 * MIB, 12288 * MIB);
+        let mut profile = GameProfile::default();
+
+        let warns = |profile: &GameProfile, rebar| {
+            manager()
+                .check_profile_warnings(profile, Some(rebar))
+                .iter()
+                .any(|w| w.contains("no_upload_hvv"))
+        };
+        assert!(warns(&profile, &small_bar));
+        assert!(!warns(&profile, &rebar));
+
+        profile.vkd3d.no_upload_hvv = true;
+        assert!(!warns(&profile, &small_bar));
+        assert!(warns(&profile, &rebar));
+    }
+
+    #[test]
+    fn test_gamescope_resolution_against_monitor() {
+        let monitor = Monitor {
+            id: 0,
+            name: "DP-1".to_string(),
+            description: String::new(),
+            width: 2560,
+            height: 1440,
+            refresh_rate: 165.0,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            active: true,
+            focused: true,
+        };
+        let mut profile = GameProfile::default();
+        let gs = &mut profile.wrappers.gamescope;
+        gs.enabled = true;
+        (gs.width, gs.height) = (Some(3840), Some(2160));
+        assert!(validate_gamescope_resolution(&profile, &monitor)[0].contains("larger than DP-1"));
+
+        // DSR: rendering above the native output is the point
+        let gs = &mut profile.wrappers.gamescope;
+        (gs.width, gs.height) = (Some(2560), Some(1440));
+        gs.dsr_enabled = true;
+        (gs.dsr_width, gs.dsr_height) = (Some(3840), Some(2160));
+        assert!(validate_gamescope_resolution(&profile, &monitor).is_empty());
+
+        profile.wrappers.gamescope.dsr_width = Some(1920);
+        profile.wrappers.gamescope.dsr_height = Some(1080);
+        assert_eq!(validate_gamescope_resolution(&profile, &monitor).len(), 1);
+    }
+
+    #[test]
+    fn test_profile_summary_badges_and_cap() {
+        let mut profile = GameProfile {
+            name: "Cyberpunk 2077".to_string(),
+            steam_appid: Some(1091500),
+            ..Default::default()
+        };
+        profile.dlss.upgrade = true;
+        profile.vkd3d.force_dxr = true;
+        profile.wrappers.mangohud.enabled = true;
+        profile.wrappers.mangohud.fps_limit_enabled = true;
+        profile.wrappers.mangohud.fps_limit = Some(117);
+        profile.proton.sync_mode = Some("ntsync".to_string());
+
+        let games = vec![Game {
+            id: "1091500".to_string(),
+            name: "Cyberpunk 2077".to_string(),
+            executable: None,
+            source: crate::games::GameSource::Steam,
+            install_path: None,
+            icon_url: None,
+        }];
+        let summary = summarize_profile(&profile, &games, 2);
+        assert_eq!(summary.badges, vec!["DLSS", "RT", "MangoHud"]);
+        assert_eq!(summary.frame_cap, Some(117));
+        assert_eq!(summary.sync_mode.as_deref(), Some("ntsync"));
+
+        let mut capped = profile.clone();
+        capped.custom_env.insert("DXVK_FRAME_RATE".to_string(), "60".to_string());
+        assert_eq!(summarize_profile(&capped, &games, 0).frame_cap, Some(60));
+        assert_eq!(summary.game.as_deref(), Some("Cyberpunk 2077"));
+
+        assert!(summarize_profile(&profile, &[], 0).game.is_none());
+    }
+}
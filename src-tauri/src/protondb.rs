@@ -0,0 +1,245 @@
+//! ProtonDB integration for community launch recommendations
+//! Fetches the compatibility tier and the launch options players report most,
+//! and turns env-var tokens from them into a suggested `custom_env`
+
+use crate::steam_import::split_words;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Responses are reused for a day; tiers and reports change slowly. Unlike
+/// the PCGamingWiki lookups, which only hold wikitext for the session, the
+/// cache is on disk: ProtonDB is asked again for the same game across
+/// restarts, and the TTL needs the fetch time an in-memory map doesn't keep.
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const MAX_LAUNCH_OPTIONS: usize = 5;
+
+/// A launch option string and how many reports used it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchOptionTip {
+    pub options: String,
+    pub count: usize,
+}
+
+/// ProtonDB recommendation for a game
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProtonTips {
+    pub steam_appid: u32,
+    pub tier: Option<String>,                    // "platinum", "gold", ...
+    pub total_reports: u32,
+    pub launch_options: Vec<LaunchOptionTip>,    // Most common first
+    pub suggested_env: HashMap<String, String>,  // Partial profile: merge into custom_env
+    pub error: Option<String>,
+}
+
+/// Response from the ProtonDB summary endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SummaryResponse {
+    tier: Option<String>,
+    total: Option<u32>,
+}
+
+/// Fetch ProtonDB tips, served from the on-disk cache when fresh.
+/// Network or parse failures yield empty tips with `error` set.
+pub async fn fetch_protondb_tips(steam_appid: u32) -> ProtonTips {
+    if let Some(cached) = read_cache(steam_appid) {
+        return cached;
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (X11; Linux x86_64) uNVCPfL/1.0")
+        .timeout(Duration::from_secs(15))
+        .build()
+        .unwrap_or_default();
+
+    let summary = match get_summary(&client, steam_appid).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            return ProtonTips {
+                steam_appid,
+                error: Some(e),
+                ..Default::default()
+            }
+        }
+    };
+
+    // Reports are best-effort: a tier on its own is still worth showing, but
+    // isn't cached, so the reports are tried again next time
+    let reports = get_reports(&client, steam_appid).await;
+    let complete = reports.is_ok();
+    let reports = reports.unwrap_or_default();
+    let launch_options = most_common_launch_options(&reports);
+    let suggested_env = launch_options
+        .first()
+        .map(|tip| parse_env_tokens(&tip.options))
+        .unwrap_or_default();
+
+    let tips = ProtonTips {
+        steam_appid,
+        tier: summary.tier,
+        total_reports: summary.total.unwrap_or(0),
+        launch_options,
+        suggested_env,
+        error: None,
+    };
+    if complete {
+        write_cache(&tips);
+    }
+    tips
+}
+
+async fn get_summary(client: &reqwest::Client, steam_appid: u32) -> Result<SummaryResponse, String> {
+    let url = format!(
+        "https://www.protondb.com/api/v1/reports/summaries/{}.json",
+        steam_appid
+    );
+
+    client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP error: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("ProtonDB has no summary for this game: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("JSON parse error: {}", e))
+}
+
+/// ProtonDB itself only serves summaries; individual reports come from
+/// protondb.max-p.me, an unofficial community mirror of its data dump. It can
+/// lag behind or go away, which is why reports are optional.
+async fn get_reports(client: &reqwest::Client, steam_appid: u32) -> Result<Vec<serde_json::Value>, String> {
+    let url = format!("https://protondb.max-p.me/games/{}/reports/", steam_appid);
+
+    client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP error: {}", e))?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| format!("JSON parse error: {}", e))
+}
+
+/// Launch options from reports, most common first. Newer reports carry
+/// `responses.launchOptions`; older ones only mention `%command%` in notes.
+fn most_common_launch_options(reports: &[serde_json::Value]) -> Vec<LaunchOptionTip> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for report in reports {
+        let options = report
+            .pointer("/responses/launchOptions")
+            .or_else(|| report.get("launchOptions"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| {
+                report
+                    .get("notes")
+                    .and_then(|v| v.as_str())
+                    .and_then(|notes| notes.lines().find(|l| l.contains("%command%")))
+                    .map(str::to_string)
+            });
+
+        if let Some(options) = options {
+            let normalized = options.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !normalized.is_empty() {
+                *counts.entry(normalized).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut tips: Vec<LaunchOptionTip> = counts
+        .into_iter()
+        .map(|(options, count)| LaunchOptionTip { options, count })
+        .collect();
+    tips.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.options.cmp(&b.options)));
+    tips.truncate(MAX_LAUNCH_OPTIONS);
+    tips
+}
+
+/// `KEY=value` words preceding `%command%` (or anywhere, if it's absent),
+/// split the way a shell would so quoted values keep their spaces
+fn parse_env_tokens(options: &str) -> HashMap<String, String> {
+    let Ok(key_re) = Regex::new(r"^[A-Z_][A-Z0-9_]*$") else {
+        return HashMap::new();
+    };
+
+    split_words(options)
+        .into_iter()
+        .take_while(|word| word != "%command%")
+        .filter_map(|word| {
+            let (key, value) = word.split_once('=')?;
+            key_re
+                .is_match(key)
+                .then(|| (key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn cache_path(steam_appid: u32) -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join(format!("unvcpfl/protondb/{}.json", steam_appid)))
+}
+
+fn read_cache(steam_appid: u32) -> Option<ProtonTips> {
+    let path = cache_path(steam_appid)?;
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+    if age > CACHE_TTL {
+        return None;
+    }
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn write_cache(tips: &ProtonTips) {
+    let Some(path) = cache_path(tips.steam_appid) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    if let Ok(json) = serde_json::to_string(tips) {
+        fs::write(path, json).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_options_and_env_tokens() {
+        let reports: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[
+                {"responses": {"launchOptions": "PROTON_ENABLE_NVAPI=1 DXVK_ASYNC=1 %command%"}},
+                {"responses": {"launchOptions": "PROTON_ENABLE_NVAPI=1  DXVK_ASYNC=1 %command%"}},
+                {"launchOptions": "gamemoderun %command% -dx11"},
+                {"notes": "Works great.\nUse WINEDLLOVERRIDES=\"dinput8=n,b\" %command%"},
+                {"notes": "No tweaks needed"}
+            ]"#,
+        )
+        .unwrap();
+
+        let tips = most_common_launch_options(&reports);
+        assert_eq!(tips.len(), 3);
+        assert_eq!(tips[0].options, "PROTON_ENABLE_NVAPI=1 DXVK_ASYNC=1 %command%");
+        assert_eq!(tips[0].count, 2);
+
+        let env = parse_env_tokens(&tips[0].options);
+        assert_eq!(env.get("PROTON_ENABLE_NVAPI").map(String::as_str), Some("1"));
+        assert_eq!(env.get("DXVK_ASYNC").map(String::as_str), Some("1"));
+
+        let env = parse_env_tokens("Use WINEDLLOVERRIDES=\"dinput8=n,b\" %command% FOO=1");
+        assert_eq!(env.len(), 1);
+        assert_eq!(env["WINEDLLOVERRIDES"], "dinput8=n,b");
+
+        let env = parse_env_tokens("PROTON_LOG=1 DXVK_CONFIG='dxgi.maxFrameRate = 60' %command%");
+        assert_eq!(env.len(), 2);
+        assert_eq!(env["DXVK_CONFIG"], "dxgi.maxFrameRate = 60");
+    }
+}
@@ -0,0 +1,145 @@
+//! Apply a profile to a game that was started without the app
+//!
+//! Only window placement is applied: it acts on the game's own window and goes
+//! away with it. The launch wrapper restores monitors, night mode, the LACT
+//! profile, PowerMizer and the audio sink, and releases idle inhibit, when the
+//! game exits; there is no wrapper here to do that, so those settings are
+//! reported as skipped. Environment variables and wrappers are fixed at
+//! launch, so they are skipped too.
+
+use crate::profiles::{diff_profiles, GameProfile, ProfileManager};
+use crate::screen;
+use serde::Serialize;
+
+/// What apply_to_running_game did with each changed profile field
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunningGameResult {
+    pub applied: Vec<String>,
+    pub skipped: Vec<String>, // "field: reason"
+}
+
+impl RunningGameResult {
+    fn record(&mut self, field: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.applied.push(field.to_string()),
+            Err(e) => self.skipped.push(format!("{}: {}", field, e)),
+        }
+    }
+}
+
+/// Fields applied (or explicitly skipped) by apply_to_running_game itself
+const RUNTIME_FIELDS: &[&str] = &[
+    "screen.",
+    "wrappers.lact_profile",
+    "wrappers.lact_restore_after_exit",
+    "wrappers.inhibit_idle",
+    "nvidia.powermizer_mode",
+    "audio.",
+];
+
+/// Fields that change state outside the game's window, which only the launch
+/// wrapper puts back once the game exits
+const RESTORED_ON_EXIT: &[&str] = &[
+    "screen.disable_other_monitors",
+    "screen.night_mode",
+    "wrappers.lact_profile",
+    "wrappers.inhibit_idle",
+    "nvidia.powermizer_mode",
+    "audio.target_sink",
+];
+
+/// Why a launch-time field can't be applied to a running game
+fn launch_only_reason(field: &str) -> &'static str {
+    if field.starts_with("wrappers.") || field.starts_with("cpu.") {
+        "wrapper command, needs a relaunch"
+    } else if field == "custom_args" {
+        "game argument, needs a relaunch"
+    } else {
+        "environment variable, needs a relaunch"
+    }
+}
+
+fn apply_screen(result: &mut RunningGameResult, profile: &GameProfile, window_class: &str) {
+    let compositor = screen::detect_compositor();
+
+    for (field, cmds) in screen::running_window_cmds(compositor, window_class, profile) {
+        let outcome = match cmds {
+            Some(cmds) => cmds
+                .iter()
+                .try_for_each(|cmd| match screen::run_compositor_cmd(cmd)? {
+                    true => Ok(()),
+                    false => Err(format!("{} failed", cmd[0])),
+                }),
+            None => Err(format!(
+                "not possible for an open window on {}",
+                screen::compositor_name(compositor)
+            )),
+        };
+        result.record(field, outcome);
+    }
+}
+
+/// Apply the runtime-changeable parts of `profile_name` to a running game
+pub fn apply_to_running_game(
+    manager: &ProfileManager,
+    window_class: &str,
+    profile_name: &str,
+) -> Result<RunningGameResult, String> {
+    let profile = manager
+        .get_profile(profile_name)
+        .ok_or_else(|| format!("Profile '{}' not found", profile_name))?;
+    let mut result = RunningGameResult::default();
+
+    apply_screen(&mut result, &profile, window_class);
+
+    let changed = diff_profiles(&profile, &GameProfile::default());
+    for field in RESTORED_ON_EXIT {
+        if changed.iter().any(|diff| diff.field == *field) {
+            result.skipped.push(format!(
+                "{}: wouldn't be restored when the game exits, needs a relaunch",
+                field
+            ));
+        }
+    }
+
+    // Everything else the profile changes is fixed at launch
+    for diff in changed {
+        if !RUNTIME_FIELDS.iter().any(|f| diff.field.starts_with(f)) {
+            result.skipped.push(format!(
+                "{}: {}",
+                diff.field,
+                launch_only_reason(&diff.field)
+            ));
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_only_reasons() {
+        assert_eq!(
+            launch_only_reason("dlss.upgrade"),
+            "environment variable, needs a relaunch"
+        );
+        assert_eq!(
+            launch_only_reason("wrappers.gamescope.enabled"),
+            "wrapper command, needs a relaunch"
+        );
+        assert_eq!(
+            launch_only_reason("cpu.nice"),
+            "wrapper command, needs a relaunch"
+        );
+        assert!(RUNTIME_FIELDS
+            .iter()
+            .any(|f| "screen.target_monitor".starts_with(f)));
+        // Reported once, as not restored, rather than again as launch-only
+        assert!(RESTORED_ON_EXIT
+            .iter()
+            .all(|field| RUNTIME_FIELDS.iter().any(|f| field.starts_with(f))));
+    }
+}
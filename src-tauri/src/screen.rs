@@ -2,9 +2,13 @@
 //!
 //! Provides monitor detection, per-game monitor rules, and monitor enable/disable.
 
+use crate::profiles::GameProfile;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::process::Command;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::Mutex;
 
 /// Detected compositor type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -14,6 +18,7 @@ pub enum Compositor {
     Gnome,
     Kde,
     X11,
+    GamescopeSession, // SteamOS Game Mode / gamescope-session, gamescope is the session compositor
     Unknown,
 }
 
@@ -39,6 +44,48 @@ pub struct Monitor {
     pub focused: bool,
 }
 
+/// Compositor-agnostic mode and placement of an enabled monitor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorConfig {
+    pub width: u32,
+    pub height: u32,
+    pub refresh_rate: f32,
+    pub x: i32,
+    pub y: i32,
+    pub scale: f32,
+}
+
+/// Whether HDR can actually be enabled end-to-end
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HdrSupport {
+    pub driver_version: Option<String>,
+    pub driver_supported: bool,             // NVIDIA 565+ exposes HDR on Wayland
+    pub compositor_supported: bool,         // Compositor implements the HDR/color-management protocol
+    /// Monitor name -> HDR can be enabled; None per monitor when the compositor
+    /// only shows whether HDR is on, and None overall when it can't list outputs
+    pub monitors: Option<HashMap<String, Option<bool>>>,
+}
+
+/// Output format a monitor is actually being driven with; `None` where the
+/// compositor doesn't report it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColorInfo {
+    pub name: String,
+    pub bit_depth: Option<u32>,        // Bits per color channel, e.g. 8 or 10
+    pub color_format: Option<String>,  // DRM format, e.g. "XRGB2101010"
+    pub hdr_active: Option<bool>,
+}
+
+/// Minimum NVIDIA driver major version with Wayland HDR support
+const MIN_HDR_DRIVER_MAJOR: u32 = 565;
+
+/// Named snapshot of the full monitor arrangement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorLayout {
+    pub name: String,
+    pub monitors: Vec<Monitor>,
+}
+
 /// Hyprland monitor JSON structure
 #[derive(Debug, Deserialize)]
 struct HyprlandMonitor {
@@ -59,6 +106,14 @@ struct HyprlandMonitor {
 
 /// Detect the current compositor/desktop environment
 pub fn detect_compositor() -> Compositor {
+    // gamescope-session (SteamOS Game Mode) sets the desktop to gamescope. A
+    // nested gamescope only sets GAMESCOPE_WAYLAND_DISPLAY, so that isn't enough
+    if let Ok(desktop) = std::env::var("XDG_CURRENT_DESKTOP") {
+        if desktop.eq_ignore_ascii_case("gamescope") {
+            return Compositor::GamescopeSession;
+        }
+    }
+
     // Check Hyprland first (most specific)
     if std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok() {
         return Compositor::Hyprland;
@@ -105,6 +160,7 @@ pub fn compositor_name(compositor: Compositor) -> &'static str {
         Compositor::Gnome => "GNOME",
         Compositor::Kde => "KDE Plasma",
         Compositor::X11 => "X11",
+        Compositor::GamescopeSession => "Gamescope session",
         Compositor::Unknown => "Unknown",
     }
 }
@@ -197,184 +253,1757 @@ fn list_monitors_sway() -> Result<Vec<Monitor>, String> {
         .collect())
 }
 
+/// Get the currently focused monitor, if the compositor reports focus
+pub fn get_focused_monitor() -> Option<Monitor> {
+    list_monitors().ok()?.into_iter().find(|m| m.focused)
+}
+
 /// Disable a monitor (Hyprland only for now)
 pub fn disable_monitor(name: &str) -> Result<(), String> {
     let compositor = detect_compositor();
+    let cmd = disable_monitor_cmd(compositor, name).ok_or_else(|| {
+        format!(
+            "Monitor disable not supported for {}",
+            compositor_name(compositor)
+        )
+    })?;
 
-    match compositor {
-        Compositor::Hyprland => {
-            let output = Command::new("hyprctl")
-                .args(["keyword", "monitor", &format!("{},disable", name)])
-                .output()
-                .map_err(|e| format!("Failed to run hyprctl: {}", e))?;
+    if !run_compositor_cmd(&cmd)? {
+        return Err(format!("Failed to disable monitor {}", name));
+    }
+    Ok(())
+}
 
-            if !output.status.success() {
-                return Err(format!("Failed to disable monitor {}", name));
-            }
-            Ok(())
-        }
-        Compositor::Sway => {
-            let output = Command::new("swaymsg")
-                .args(["output", name, "disable"])
-                .output()
-                .map_err(|e| format!("Failed to run swaymsg: {}", e))?;
+/// Enable/restore a monitor with a stored config
+pub fn enable_monitor(name: &str, config: &MonitorConfig) -> Result<(), String> {
+    let compositor = detect_compositor();
+    let cmd = enable_monitor_cmd(compositor, name, config).ok_or_else(|| {
+        format!(
+            "Monitor enable not supported for {}",
+            compositor_name(compositor)
+        )
+    })?;
 
-            if !output.status.success() {
-                return Err(format!("Failed to disable monitor {}", name));
-            }
-            Ok(())
-        }
-        _ => Err(format!(
-            "Monitor disable not supported for {}",
+    if !run_compositor_cmd(&cmd)? {
+        return Err(format!("Failed to enable monitor {}", name));
+    }
+    Ok(())
+}
+
+/// String-compat shim for callers holding a "WxH@Hz,XxY,scale" config
+pub fn enable_monitor_config_string(name: &str, config: &str) -> Result<(), String> {
+    enable_monitor(name, &MonitorConfig::parse(config)?)
+}
+
+/// Set a window rule to put a game on a specific monitor
+pub fn set_game_monitor_rule(window_class: &str, monitor_name: &str) -> Result<(), String> {
+    let compositor = detect_compositor();
+    let cmd = monitor_rule_cmd(compositor, window_class, monitor_name).ok_or_else(|| {
+        format!(
+            "Window rules not supported for {}",
             compositor_name(compositor)
-        )),
+        )
+    })?;
+
+    if !run_compositor_cmd(&cmd)? {
+        return Err(format!(
+            "Failed to set monitor rule for {}",
+            window_class
+        ));
     }
+    Ok(())
 }
 
-/// Enable/restore a monitor (requires stored config)
-pub fn enable_monitor(name: &str, config: &str) -> Result<(), String> {
+/// Set fullscreen rule for a game
+pub fn set_game_fullscreen_rule(window_class: &str) -> Result<(), String> {
     let compositor = detect_compositor();
+    let cmd = fullscreen_rule_cmd(compositor, window_class).ok_or_else(|| {
+        format!(
+            "Fullscreen rules not supported for {}",
+            compositor_name(compositor)
+        )
+    })?;
 
-    match compositor {
+    if !run_compositor_cmd(&cmd)? {
+        return Err(format!(
+            "Failed to set fullscreen rule for {}",
+            window_class
+        ));
+    }
+    Ok(())
+}
+
+/// Class of the Hyprland client owned by `pid`, from `hyprctl clients -j`
+fn hyprland_class_for_pid(clients: &serde_json::Value, pid: u32) -> Option<String> {
+    clients
+        .as_array()?
+        .iter()
+        .find(|c| c["pid"].as_u64() == Some(pid as u64))
+        .and_then(|c| c["class"].as_str())
+        .filter(|class| !class.is_empty())
+        .map(String::from)
+}
+
+/// Sway criteria for the window owned by `pid`, from `swaymsg -t get_tree`:
+/// ("app_id", id) for Wayland windows, ("class", class) for XWayland ones
+fn sway_criteria_for_pid(node: &serde_json::Value, pid: u32) -> Option<(&'static str, String)> {
+    if node["pid"].as_u64() == Some(pid as u64) {
+        if let Some(app_id) = node["app_id"].as_str().filter(|id| !id.is_empty()) {
+            return Some(("app_id", app_id.to_string()));
+        }
+        if let Some(class) = node["window_properties"]["class"].as_str() {
+            return Some(("class", class.to_string()));
+        }
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .flat_map(|key| node[*key].as_array().into_iter().flatten())
+        .find_map(|child| sway_criteria_for_pid(child, pid))
+}
+
+/// JSON output of a compositor query command
+fn command_json(program: &str, args: &[&str]) -> Result<serde_json::Value, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed", program));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse JSON: {}", e))
+}
+
+/// Rule criteria matching the app's own window, found by our pid in the
+/// compositor's client list
+fn self_window_criteria(compositor: Compositor) -> Result<(&'static str, String), String> {
+    let pid = std::process::id();
+    let found = match compositor {
         Compositor::Hyprland => {
-            // config format: "1920x1080@144,0x0,1" (resolution@hz,position,scale)
-            let output = Command::new("hyprctl")
-                .args(["keyword", "monitor", &format!("{},{}", name, config)])
-                .output()
-                .map_err(|e| format!("Failed to run hyprctl: {}", e))?;
-
-            if !output.status.success() {
-                return Err(format!("Failed to enable monitor {}", name));
-            }
-            Ok(())
+            let clients = command_json("hyprctl", &["clients", "-j"])?;
+            hyprland_class_for_pid(&clients, pid).map(|class| ("class", class))
         }
         Compositor::Sway => {
-            let output = Command::new("swaymsg")
-                .args(["output", name, "enable"])
-                .output()
-                .map_err(|e| format!("Failed to run swaymsg: {}", e))?;
-
-            if !output.status.success() {
-                return Err(format!("Failed to enable monitor {}", name));
-            }
-            Ok(())
+            let tree = command_json("swaymsg", &["-t", "get_tree", "-r"])?;
+            sway_criteria_for_pid(&tree, pid)
         }
-        _ => Err(format!(
-            "Monitor enable not supported for {}",
-            compositor_name(compositor)
-        )),
-    }
+        _ => {
+            return Err(format!(
+                "Window rules not supported for {}",
+                compositor_name(compositor)
+            ))
+        }
+    };
+    found.ok_or_else(|| {
+        "The app's window was not found in the compositor's client list".to_string()
+    })
 }
 
-/// Set a window rule to put a game on a specific monitor
-pub fn set_game_monitor_rule(window_class: &str, monitor_name: &str) -> Result<(), String> {
-    let compositor = detect_compositor();
+/// Window class (Sway: app_id) of the app's own window
+pub fn self_window_class() -> Result<String, String> {
+    self_window_criteria(detect_compositor()).map(|(_, class)| class)
+}
 
+/// Self window rules registered this session, so repeated calls don't stack
+/// duplicate windowrulev2/for_window entries
+static SELF_RULES: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Hyprland dispatcher applying a static rule to the running window `pid`.
+/// Rules without one (e.g. "monitor") only take effect when the window reopens.
+fn hyprland_self_dispatch(rule: &str, pid: u32) -> Option<Vec<String>> {
+    let target = format!("pid:{}", pid);
+    let (dispatcher, arg) = match rule.split_once(' ') {
+        None if rule == "float" => ("setfloating", target),
+        None if rule == "tile" => ("settiled", target),
+        None if rule == "pin" => ("pin", target),
+        Some(("workspace", workspace)) => {
+            let workspace = workspace.trim().trim_end_matches(" silent");
+            ("movetoworkspacesilent", format!("{},{}", workspace, target))
+        }
+        _ => return None,
+    };
+    Some(vec![
+        "hyprctl".to_string(),
+        "dispatch".to_string(),
+        dispatcher.to_string(),
+        arg,
+    ])
+}
+
+/// Commands applying `rule` to the app's own window (process `pid`): on
+/// Hyprland a dispatch on the running window where one exists (e.g. "float")
+/// plus a windowrulev2; on Sway a command (e.g. "floating enable") run now plus
+/// a for_window for when the window is reopened. The persistent rule is only
+/// added when `register` is set.
+fn self_rule_cmds(
+    compositor: Compositor,
+    criteria: (&str, &str),
+    pid: u32,
+    rule: &str,
+    register: bool,
+) -> Option<Vec<Vec<String>>> {
+    let (key, class) = criteria;
+    let mut cmds = Vec::new();
     match compositor {
         Compositor::Hyprland => {
-            // Set window rule for the game class
-            let rule = format!("monitor {},class:^({})$", monitor_name, window_class);
-            let output = Command::new("hyprctl")
-                .args(["keyword", "windowrulev2", &rule])
-                .output()
-                .map_err(|e| format!("Failed to run hyprctl: {}", e))?;
-
-            if !output.status.success() {
-                return Err(format!(
-                    "Failed to set monitor rule for {}",
-                    window_class
-                ));
+            cmds.extend(hyprland_self_dispatch(rule, pid));
+            if register {
+                cmds.push(vec![
+                    "hyprctl".to_string(),
+                    "keyword".to_string(),
+                    "windowrulev2".to_string(),
+                    format!("{},class:^({})$", rule, class),
+                ]);
             }
-            Ok(())
         }
         Compositor::Sway => {
-            // Sway uses for_window rules
-            let rule = format!(
-                "for_window [class=\"{}\"] move container to output {}",
-                window_class, monitor_name
-            );
-            let output = Command::new("swaymsg")
-                .arg(&rule)
-                .output()
-                .map_err(|e| format!("Failed to run swaymsg: {}", e))?;
-
-            if !output.status.success() {
-                return Err(format!(
-                    "Failed to set monitor rule for {}",
-                    window_class
-                ));
+            let selector = format!("[{}=\"{}\"]", key, class);
+            cmds.push(vec!["swaymsg".to_string(), format!("{} {}", selector, rule)]);
+            if register {
+                cmds.push(vec!["swaymsg".to_string(), format!("for_window {} {}", selector, rule)]);
             }
-            Ok(())
         }
-        _ => Err(format!(
-            "Window rules not supported for {}",
-            compositor_name(compositor)
-        )),
+        _ => return None,
     }
+    Some(cmds)
 }
 
-/// Set fullscreen rule for a game
-pub fn set_game_fullscreen_rule(window_class: &str) -> Result<(), String> {
+/// Apply a window rule to the app itself, e.g. to float it or keep it off the
+/// game's monitor while a game runs. On Hyprland, rules with no matching
+/// dispatcher (e.g. "monitor") only apply once the app's window is reopened.
+pub fn set_self_window_rule(rule: &str) -> Result<(), String> {
+    let rule = rule.trim();
+    if rule.is_empty() {
+        return Err("Window rule is empty".to_string());
+    }
     let compositor = detect_compositor();
+    let (key, class) = self_window_criteria(compositor)?;
+
+    let rule_key = format!("{}:{}", class, rule);
+    let mut registered = SELF_RULES.lock().map_err(|e| e.to_string())?;
+    let registered = registered.get_or_insert_with(HashSet::new);
+    let register = !registered.contains(&rule_key);
+    let cmds = self_rule_cmds(compositor, (key, &class), std::process::id(), rule, register)
+        .ok_or_else(|| {
+            format!(
+                "Window rules not supported for {}",
+                compositor_name(compositor)
+            )
+        })?;
+    for cmd in cmds {
+        if !run_compositor_cmd(&cmd)? {
+            return Err(format!("Failed to apply window rule '{}'", rule));
+        }
+    }
+    if register {
+        registered.insert(rule_key);
+    }
+    Ok(())
+}
 
+/// Command line to disable a monitor
+fn disable_monitor_cmd(compositor: Compositor, name: &str) -> Option<Vec<String>> {
     match compositor {
-        Compositor::Hyprland => {
-            let rule = format!("fullscreen,class:^({})$", window_class);
-            let output = Command::new("hyprctl")
-                .args(["keyword", "windowrulev2", &rule])
-                .output()
-                .map_err(|e| format!("Failed to run hyprctl: {}", e))?;
-
-            if !output.status.success() {
-                return Err(format!(
-                    "Failed to set fullscreen rule for {}",
-                    window_class
-                ));
+        Compositor::Hyprland => Some(vec![
+            "hyprctl".to_string(),
+            "keyword".to_string(),
+            "monitor".to_string(),
+            format!("{},disable", name),
+        ]),
+        Compositor::Sway => Some(vec![
+            "swaymsg".to_string(),
+            "output".to_string(),
+            name.to_string(),
+            "disable".to_string(),
+        ]),
+        _ => None,
+    }
+}
+
+/// Command line to enable a monitor with a mode, position and scale
+fn enable_monitor_cmd(
+    compositor: Compositor,
+    name: &str,
+    config: &MonitorConfig,
+) -> Option<Vec<String>> {
+    match compositor {
+        Compositor::Hyprland => Some(vec![
+            "hyprctl".to_string(),
+            "keyword".to_string(),
+            "monitor".to_string(),
+            format!("{},{}", name, config),
+        ]),
+        Compositor::Sway => Some(vec![
+            "swaymsg".to_string(),
+            "output".to_string(),
+            name.to_string(),
+            "enable".to_string(),
+            "mode".to_string(),
+            format!(
+                "{}x{}@{}Hz",
+                config.width,
+                config.height,
+                format_decimal(config.refresh_rate)
+            ),
+            "position".to_string(),
+            config.x.to_string(),
+            config.y.to_string(),
+            "scale".to_string(),
+            format_decimal(config.scale),
+        ]),
+        _ => None,
+    }
+}
+
+/// Command line for a window rule placing a game on a monitor
+fn monitor_rule_cmd(
+    compositor: Compositor,
+    window_class: &str,
+    monitor_name: &str,
+) -> Option<Vec<String>> {
+    match compositor {
+        Compositor::Hyprland => Some(vec![
+            "hyprctl".to_string(),
+            "keyword".to_string(),
+            "windowrulev2".to_string(),
+            format!("monitor {},class:^({})$", monitor_name, window_class),
+        ]),
+        // Sway uses for_window rules
+        Compositor::Sway => Some(vec![
+            "swaymsg".to_string(),
+            format!(
+                "for_window [class=\"{}\"] move container to output {}",
+                window_class, monitor_name
+            ),
+        ]),
+        _ => None,
+    }
+}
+
+/// Command line for a window rule opening a game on a workspace (Hyprland only)
+fn workspace_rule_cmd(
+    compositor: Compositor,
+    window_class: &str,
+    workspace: &str,
+) -> Option<Vec<String>> {
+    match compositor {
+        Compositor::Hyprland => Some(vec![
+            "hyprctl".to_string(),
+            "keyword".to_string(),
+            "windowrulev2".to_string(),
+            workspace_rule(window_class, workspace),
+        ]),
+        _ => None,
+    }
+}
+
+fn workspace_rule(window_class: &str, workspace: &str) -> String {
+    format!("workspace {},class:^({})$", workspace, window_class)
+}
+
+/// Command line for a window rule making a game fullscreen
+fn fullscreen_rule_cmd(compositor: Compositor, window_class: &str) -> Option<Vec<String>> {
+    match compositor {
+        Compositor::Hyprland => Some(vec![
+            "hyprctl".to_string(),
+            "keyword".to_string(),
+            "windowrulev2".to_string(),
+            format!("fullscreen,class:^({})$", window_class),
+        ]),
+        Compositor::Sway => Some(vec![
+            "swaymsg".to_string(),
+            format!("for_window [class=\"{}\"] fullscreen enable", window_class),
+        ]),
+        _ => None,
+    }
+}
+
+/// Commands allowing tearing globally and for a game's windows (Hyprland only)
+fn tearing_rule_cmds(compositor: Compositor, window_class: &str) -> Option<Vec<Vec<String>>> {
+    match compositor {
+        Compositor::Hyprland => Some(vec![
+            vec![
+                "hyprctl".to_string(),
+                "keyword".to_string(),
+                "general:allow_tearing".to_string(),
+                "true".to_string(),
+            ],
+            vec![
+                "hyprctl".to_string(),
+                "keyword".to_string(),
+                "windowrulev2".to_string(),
+                tearing_rule(window_class),
+            ],
+        ]),
+        _ => None,
+    }
+}
+
+/// Run a compositor command; Ok(false) means it ran but reported failure
+pub(crate) fn run_compositor_cmd(cmd: &[String]) -> Result<bool, String> {
+    let (program, args) = cmd.split_first().ok_or("Empty command")?;
+    Command::new(program)
+        .args(args)
+        .output()
+        .map(|o| o.status.success())
+        .map_err(|e| format!("Failed to run {}: {}", program, e))
+}
+
+/// Window class a game is expected to use (Proton games are "steam_app_<appid>")
+fn game_window_class(profile: &GameProfile) -> Option<String> {
+    profile
+        .steam_appid
+        .map(|id| format!("steam_app_{}", id))
+        .or_else(|| profile.executable_match.clone())
+}
+
+/// Commands needed to apply a profile's screen settings, in execution order
+fn plan_screen_commands(
+    compositor: Compositor,
+    profile: &GameProfile,
+    monitors: &[Monitor],
+) -> Vec<Vec<String>> {
+    let settings = &profile.screen;
+    let class = game_window_class(profile);
+    let mut cmds = Vec::new();
+
+    if let Some(target) = &settings.target_monitor {
+        if settings.disable_other_monitors {
+            for m in monitors.iter().filter(|m| m.active && &m.name != target) {
+                cmds.extend(disable_monitor_cmd(compositor, &m.name));
             }
-            Ok(())
         }
-        Compositor::Sway => {
-            let rule = format!("for_window [class=\"{}\"] fullscreen enable", window_class);
-            let output = Command::new("swaymsg")
-                .arg(&rule)
-                .output()
-                .map_err(|e| format!("Failed to run swaymsg: {}", e))?;
-
-            if !output.status.success() {
-                return Err(format!(
-                    "Failed to set fullscreen rule for {}",
-                    window_class
-                ));
-            }
-            Ok(())
+        if let Some(class) = &class {
+            cmds.extend(monitor_rule_cmd(compositor, class, target));
         }
-        _ => Err(format!(
-            "Fullscreen rules not supported for {}",
+    }
+
+    if settings.fullscreen_on_target {
+        if let Some(class) = &class {
+            cmds.extend(fullscreen_rule_cmd(compositor, class));
+        }
+    }
+
+    if let (Some(workspace), Some(class)) = (&settings.target_workspace, &class) {
+        cmds.extend(workspace_rule_cmd(compositor, class, workspace));
+    }
+
+    if let (true, Some(class)) = (settings.allow_tearing, &class) {
+        cmds.extend(tearing_rule_cmds(compositor, class).unwrap_or_default());
+    }
+
+    cmds
+}
+
+/// Commands putting an already-open window where the profile wants it, per
+/// settings field. Window rules only match new windows, so these act on the
+/// existing one; None means the compositor can't do it for an open window.
+/// Only the window itself is touched, so nothing outlives the game.
+pub(crate) fn running_window_cmds(
+    compositor: Compositor,
+    window_class: &str,
+    profile: &GameProfile,
+) -> Vec<(&'static str, Option<Vec<Vec<String>>>)> {
+    let settings = &profile.screen;
+    let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<String>>();
+    let hypr_window = format!("class:^({})$", window_class);
+    let sway_window = format!("[class=\"{}\"]", window_class);
+    let mut cmds = Vec::new();
+
+    if let Some(target) = &settings.target_monitor {
+        let moved = match compositor {
+            // movewindow acts on the focused window
+            Compositor::Hyprland => Some(vec![
+                args(&["hyprctl", "dispatch", "focuswindow", &hypr_window]),
+                args(&["hyprctl", "dispatch", "movewindow", &format!("mon:{}", target)]),
+            ]),
+            Compositor::Sway => Some(vec![args(&[
+                "swaymsg",
+                &format!("{} move container to output {}", sway_window, target),
+            ])]),
+            _ => None,
+        };
+        cmds.push(("screen.target_monitor", moved));
+    }
+
+    if let Some(workspace) = &settings.target_workspace {
+        let moved = match compositor {
+            Compositor::Hyprland => Some(vec![args(&[
+                "hyprctl",
+                "dispatch",
+                "movetoworkspacesilent",
+                &format!("{},{}", workspace, hypr_window),
+            ])]),
+            Compositor::Sway => Some(vec![args(&[
+                "swaymsg",
+                &format!("{} move container to workspace {}", sway_window, workspace),
+            ])]),
+            _ => None,
+        };
+        cmds.push(("screen.target_workspace", moved));
+    }
+
+    if settings.fullscreen_on_target {
+        // Hyprland's fullscreen dispatcher toggles, which could undo an existing fullscreen
+        let fullscreen = match compositor {
+            Compositor::Sway => Some(vec![args(&[
+                "swaymsg",
+                &format!("{} fullscreen enable", sway_window),
+            ])]),
+            _ => None,
+        };
+        cmds.push(("screen.fullscreen_on_target", fullscreen));
+    }
+
+    cmds
+}
+
+/// Apply a profile's screen settings (monitor disables, window rules)
+pub fn apply_screen_settings(profile: &GameProfile) -> Result<(), String> {
+    let compositor = detect_compositor();
+    if !is_screen_config_supported() {
+        return Err(format!(
+            "Screen configuration not supported for {}",
             compositor_name(compositor)
-        )),
+        ));
+    }
+
+    for cmd in plan_screen_commands(compositor, profile, &list_monitors()?) {
+        if !run_compositor_cmd(&cmd)? {
+            return Err(format!("Command failed: {}", shell_join(&cmd)));
+        }
+    }
+    Ok(())
+}
+
+/// The literal commands apply_screen_settings would run, without running them
+pub fn preview_screen_commands(profile: &GameProfile) -> Vec<String> {
+    let compositor = detect_compositor();
+    let monitors = list_monitors().unwrap_or_default();
+
+    plan_screen_commands(compositor, profile, &monitors)
+        .iter()
+        .chain(profile_night_mode_cmd(profile).as_ref())
+        .map(|cmd| shell_join(cmd))
+        .collect()
+}
+
+/// Join args for display, single-quoting anything the shell would split
+fn shell_join(cmd: &[String]) -> String {
+    cmd.iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Single-quote an argument if the shell would split or expand it
+pub(crate) fn shell_quote(arg: &str) -> String {
+    const SPECIAL: &str = "\"'$[]()^;&|<>*?`\\";
+    if arg.is_empty() || arg.chars().any(|c| c.is_whitespace() || SPECIAL.contains(c)) {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    } else {
+        arg.to_string()
     }
 }
 
 /// Get current monitor configurations for restoration
-pub fn get_monitor_configs() -> Result<HashMap<String, String>, String> {
+pub fn get_monitor_configs() -> Result<HashMap<String, MonitorConfig>, String> {
     let monitors = list_monitors()?;
     let mut configs = HashMap::new();
 
     for m in monitors {
         if m.active {
-            // Format: resolution@hz,position,scale
-            let config = format!(
-                "{}x{}@{:.0},{}x{},{:.1}",
-                m.width, m.height, m.refresh_rate, m.x, m.y, m.scale
-            );
-            configs.insert(m.name, config);
+            configs.insert(m.name.clone(), MonitorConfig::from(&m));
         }
     }
 
     Ok(configs)
 }
 
+/// String-compat shim: configs as "WxH@Hz,XxY,scale"
+pub fn get_monitor_config_strings() -> Result<HashMap<String, String>, String> {
+    Ok(get_monitor_configs()?
+        .into_iter()
+        .map(|(name, config)| (name, config.to_string()))
+        .collect())
+}
+
+/// Combine driver, compositor and per-monitor HDR capability
+pub fn get_hdr_support() -> HdrSupport {
+    let compositor = detect_compositor();
+    let driver_version = crate::system::nvidia_driver_version();
+    let driver_supported = driver_version
+        .as_deref()
+        .and_then(|v| v.split('.').next())
+        .and_then(|major| major.parse::<u32>().ok())
+        .map(|major| major >= MIN_HDR_DRIVER_MAJOR)
+        .unwrap_or(false);
+
+    // HDR needs a Wayland compositor with the color-management protocol; X11 has none
+    let compositor_supported = std::env::var("WAYLAND_DISPLAY").is_ok()
+        && matches!(
+            compositor,
+            Compositor::Hyprland | Compositor::Kde | Compositor::Gnome | Compositor::Sway
+        );
+
+    let monitors = monitor_hdr_capabilities(compositor).ok().map(|monitors| {
+        monitors
+            .into_iter()
+            .map(|(name, capable)| {
+                // Without driver and compositor support no monitor can do HDR
+                let capable = match driver_supported && compositor_supported {
+                    true => capable,
+                    false => Some(false),
+                };
+                (name, capable)
+            })
+            .collect()
+    });
+
+    HdrSupport {
+        driver_version,
+        driver_supported,
+        compositor_supported,
+        monitors,
+    }
+}
+
+/// Per-monitor HDR capability from compositor output JSON. Compositors report
+/// whether HDR is active, not whether the monitor supports it, so an output
+/// already in HDR counts as capable and any other one as unknown.
+fn output_hdr_capability(compositor: Compositor, output: &serde_json::Value) -> Option<bool> {
+    let active = match compositor {
+        // Hyprland: HDR color preset, or a 10-bit output format
+        Compositor::Hyprland => {
+            output["colorManagementPreset"]
+                .as_str()
+                .is_some_and(|p| p.starts_with("hdr"))
+                || output["currentFormat"]
+                    .as_str()
+                    .is_some_and(|f| f.contains("2101010"))
+        }
+        // Sway 1.11+ reports an "hdr" flag per output
+        _ => output["hdr"].as_bool().unwrap_or(false),
+    };
+    active.then_some(true)
+}
+
+fn monitor_hdr_capabilities(
+    compositor: Compositor,
+) -> Result<HashMap<String, Option<bool>>, String> {
+    let (program, args): (&str, &[&str]) = match compositor {
+        Compositor::Hyprland => ("hyprctl", &["monitors", "all", "-j"]),
+        Compositor::Sway => ("swaymsg", &["-t", "get_outputs", "-r"]),
+        _ => {
+            return Err(format!(
+                "HDR detection not supported for {}",
+                compositor_name(compositor)
+            ))
+        }
+    };
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if !output.status.success() {
+        return Err(format!("{} failed", program));
+    }
+
+    let outputs: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+    Ok(outputs
+        .iter()
+        .filter_map(|o| {
+            let name = o["name"].as_str()?.to_string();
+            Some((name, output_hdr_capability(compositor, o)))
+        })
+        .collect())
+}
+
+/// Bits per channel from a DRM fourcc name (XRGB8888, XBGR2101010, ...)
+fn bit_depth_from_format(format: &str) -> Option<u32> {
+    if format.contains("16161616") {
+        Some(16)
+    } else if format.contains("2101010") {
+        Some(10)
+    } else if format.contains("8888") {
+        Some(8)
+    } else {
+        None
+    }
+}
+
+/// Color info for one monitor from `hyprctl monitors all -j` output
+fn hyprland_color_info(output: &serde_json::Value) -> ColorInfo {
+    let format = output["currentFormat"].as_str().filter(|f| !f.is_empty());
+    ColorInfo {
+        name: output["name"].as_str().unwrap_or_default().to_string(),
+        bit_depth: format.and_then(bit_depth_from_format),
+        color_format: format.map(String::from),
+        hdr_active: output["colorManagementPreset"]
+            .as_str()
+            .map(|p| p.starts_with("hdr")),
+    }
+}
+
+/// Color info for one output from `kscreen-doctor -j` output. It reports
+/// whether HDR is on but neither the bit depth nor the pixel format.
+fn kde_color_info(output: &serde_json::Value) -> ColorInfo {
+    ColorInfo {
+        name: output["name"].as_str().unwrap_or_default().to_string(),
+        bit_depth: None,
+        color_format: None,
+        hdr_active: output["hdr"].as_bool(),
+    }
+}
+
+/// Current bit depth and color format of a monitor, to confirm HDR/10-bit
+/// actually took effect. On KDE only the HDR state is known.
+pub fn get_monitor_color_info(name: &str) -> Result<ColorInfo, String> {
+    let compositor = detect_compositor();
+    let (program, args): (&str, &[&str]) = match compositor {
+        Compositor::Hyprland => ("hyprctl", &["monitors", "all", "-j"]),
+        Compositor::Kde => ("kscreen-doctor", &["-j"]),
+        _ => {
+            return Err(format!(
+                "Color info not supported for {}",
+                compositor_name(compositor)
+            ))
+        }
+    };
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if !output.status.success() {
+        return Err(format!("{} failed", program));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    let outputs = match compositor {
+        Compositor::Kde => json["outputs"].as_array(),
+        _ => json.as_array(),
+    }
+    .cloned()
+    .unwrap_or_default();
+
+    let output = outputs
+        .iter()
+        .find(|o| o["name"].as_str() == Some(name))
+        .ok_or_else(|| format!("Monitor '{}' not found", name))?;
+
+    Ok(match compositor {
+        Compositor::Kde => kde_color_info(output),
+        _ => hyprland_color_info(output),
+    })
+}
+
+impl From<&Monitor> for MonitorConfig {
+    fn from(m: &Monitor) -> Self {
+        Self {
+            width: m.width,
+            height: m.height,
+            refresh_rate: m.refresh_rate,
+            x: m.x,
+            y: m.y,
+            scale: m.scale,
+        }
+    }
+}
+
+impl MonitorConfig {
+    /// Parse a config string: resolution@hz,position,scale ("1920x1080@144,0x0,1")
+    pub fn parse(config: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid monitor config '{}' (expected WxH@Hz,XxY,scale)", config);
+        let parts: Vec<&str> = config.split(',').map(str::trim).collect();
+        let [mode, position, scale] = parts[..] else {
+            return Err(invalid());
+        };
+        let (resolution, refresh) = mode.split_once('@').ok_or_else(invalid)?;
+        let (width, height) = resolution.split_once('x').ok_or_else(invalid)?;
+        let (x, y) = position.split_once('x').ok_or_else(invalid)?;
+
+        Ok(Self {
+            width: width.parse().map_err(|_| invalid())?,
+            height: height.parse().map_err(|_| invalid())?,
+            refresh_rate: refresh.trim_end_matches("Hz").parse().map_err(|_| invalid())?,
+            x: x.parse().map_err(|_| invalid())?,
+            y: y.parse().map_err(|_| invalid())?,
+            scale: scale.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// A stored config string from before MonitorConfig: "WxH@Hz,XxY,scale",
+/// optionally led by the connector as in hyprland.conf ("DP-1,1920x1080@144,0x0,1")
+fn legacy_monitor_config(config: &str) -> Option<MonitorConfig> {
+    MonitorConfig::parse(config)
+        .ok()
+        .or_else(|| MonitorConfig::parse(config.split_once(',')?.1).ok())
+}
+
+/// A `name -> config string` table converted to `name -> MonitorConfig`; None
+/// when it holds no strings. Strings that don't parse ("disable", "preferred")
+/// are dropped since they could never be restored.
+fn migrate_config_table(table: &toml::Table) -> Option<toml::Table> {
+    if !table.values().any(|v| v.is_str()) {
+        return None;
+    }
+    Some(
+        table
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = match value.as_str() {
+                    Some(config) => toml::Value::try_from(legacy_monitor_config(config)?).ok()?,
+                    None => value.clone(),
+                };
+                Some((name.clone(), value))
+            })
+            .collect(),
+    )
+}
+
+/// A layout whose `monitors` is a `name -> config string` table converted to
+/// the Monitor list; None when it already has the list
+fn migrate_layout(layout: &toml::Table) -> Option<toml::Table> {
+    let configs = layout.get("monitors")?.as_table()?;
+    let monitors: Vec<Monitor> = configs
+        .iter()
+        .filter_map(|(name, value)| Some((name, legacy_monitor_config(value.as_str()?)?)))
+        .enumerate()
+        .map(|(id, (name, config))| Monitor {
+            id: id as u32,
+            name: name.clone(),
+            description: String::new(),
+            width: config.width,
+            height: config.height,
+            refresh_rate: config.refresh_rate,
+            x: config.x,
+            y: config.y,
+            scale: config.scale,
+            active: true,
+            focused: false,
+        })
+        .collect();
+
+    let mut layout = layout.clone();
+    layout.insert("monitors".to_string(), toml::Value::try_from(monitors).ok()?);
+    Some(layout)
+}
+
+/// Rewrite a TOML file through `migrate`; false when there was nothing to do
+fn migrate_toml_file(
+    path: &Path,
+    migrate: fn(&toml::Table) -> Option<toml::Table>,
+) -> Result<bool, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(false);
+    };
+    let Some(migrated) = toml::from_str(&content).ok().as_ref().and_then(migrate) else {
+        return Ok(false);
+    };
+    let content = toml::to_string_pretty(&migrated)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(true)
+}
+
+/// Upgrade monitor configs stored as Hyprland-format strings (saved layouts
+/// and the dock's disabled monitors) to MonitorConfig; returns files rewritten
+pub fn migrate_monitor_configs() -> Result<usize, String> {
+    let layouts: Vec<PathBuf> = fs::read_dir(layouts_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "toml"))
+        .collect();
+
+    let mut migrated = 0;
+    for path in &layouts {
+        migrated += usize::from(migrate_toml_file(path, migrate_layout)?);
+    }
+    migrated += usize::from(migrate_toml_file(
+        &crate::dock::disabled_by_dock_path(),
+        migrate_config_table,
+    )?);
+    Ok(migrated)
+}
+
+/// Hyprland config string, also the string-compat format
+impl std::fmt::Display for MonitorConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}x{}@{},{}x{},{}",
+            self.width,
+            self.height,
+            format_decimal(self.refresh_rate),
+            self.x,
+            self.y,
+            format_decimal(self.scale)
+        )
+    }
+}
+
+/// "144", "59.94", "1.25": no trailing zeros, so fractional rates and scales survive
+fn format_decimal(value: f32) -> String {
+    let formatted = format!("{:.3}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Hyprland rules written by unvcpfl; source it from hyprland.conf with
+/// `source = ~/.config/unvcpfl/hyprland-rules.conf` to keep rules across restarts
+fn rules_file_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("unvcpfl")
+        .join("hyprland-rules.conf")
+}
+
+/// Tearing also has to be allowed globally for "immediate" rules to take effect
+const TEARING_OPTION: &str = "general:allow_tearing = true";
+
+/// Rule name of a windowrulev2 value: "workspace" for "workspace 5,class:..."
+fn rule_kind(rule: &str) -> &str {
+    rule.split([' ', ',']).next().unwrap_or_default()
+}
+
+/// Rules file content with any `kind` rule for window_class replaced by `rule`
+fn update_rules_file(content: &str, kind: &str, window_class: &str, rule: Option<&str>) -> String {
+    let suffix = format!(",class:^({})$", window_class);
+    let mut lines: Vec<String> = content
+        .lines()
+        .filter(|l| {
+            let l = l.trim();
+            let replaced = l
+                .strip_prefix("windowrulev2 = ")
+                .map(|r| rule_kind(r) == kind && r.ends_with(&suffix))
+                .unwrap_or(false);
+            !replaced && l != TEARING_OPTION
+        })
+        .map(String::from)
+        .collect();
+
+    if let Some(rule) = rule {
+        lines.push(format!("windowrulev2 = {}", rule));
+    }
+    let tearing = lines
+        .iter()
+        .any(|l| l.trim().strip_prefix("windowrulev2 = ").map(rule_kind) == Some("immediate"));
+    if tearing {
+        lines.insert(0, TEARING_OPTION.to_string());
+    }
+
+    let mut updated = lines.join("\n");
+    if !updated.is_empty() {
+        updated.push('\n');
+    }
+    updated
+}
+
+fn write_rules_file(kind: &str, window_class: &str, rule: Option<&str>) -> Result<(), String> {
+    let path = rules_file_path();
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    fs::write(&path, update_rules_file(&content, kind, window_class, rule))
+        .map_err(|e| format!("Failed to write rules file: {}", e))
+}
+
+/// Open a game on a workspace; `persist` also writes the rule to the rules file
+pub fn set_game_workspace_rule(
+    window_class: &str,
+    workspace: &str,
+    persist: bool,
+) -> Result<(), String> {
+    let compositor = detect_compositor();
+    let cmd = workspace_rule_cmd(compositor, window_class, workspace).ok_or_else(|| {
+        format!(
+            "Workspace rules are only supported on Hyprland, not {}",
+            compositor_name(compositor)
+        )
+    })?;
+
+    if !run_compositor_cmd(&cmd)? {
+        return Err(format!("Failed to set workspace rule for {}", window_class));
+    }
+
+    if persist {
+        write_rules_file("workspace", window_class, Some(&workspace_rule(window_class, workspace)))?;
+    }
+    Ok(())
+}
+
+/// Drop a game's workspace rule from the running session and the rules file
+pub fn remove_game_workspace_rule(window_class: &str) -> Result<(), String> {
+    let compositor = detect_compositor();
+    if compositor != Compositor::Hyprland {
+        return Err(format!(
+            "Workspace rules are only supported on Hyprland, not {}",
+            compositor_name(compositor)
+        ));
+    }
+
+    write_rules_file("workspace", window_class, None)?;
+    reset_class_rules(window_class)
+}
+
+/// `unset` clears every rule for the class, so re-add the ones still in the rules file
+fn reset_class_rules(window_class: &str) -> Result<(), String> {
+    let keyword = |value: String| {
+        vec![
+            "hyprctl".to_string(),
+            "keyword".to_string(),
+            "windowrulev2".to_string(),
+            value,
+        ]
+    };
+    run_compositor_cmd(&keyword(format!("unset,class:^({})$", window_class)))?;
+
+    let suffix = format!(",class:^({})$", window_class);
+    let content = fs::read_to_string(rules_file_path()).unwrap_or_default();
+    for rule in content
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("windowrulev2 = "))
+        .filter(|r| r.ends_with(&suffix))
+    {
+        run_compositor_cmd(&keyword(rule.to_string()))?;
+    }
+    Ok(())
+}
+
+fn tearing_rule(window_class: &str) -> String {
+    format!("immediate,class:^({})$", window_class)
+}
+
+/// Let a game tear (Hyprland's "immediate" rule) for the lowest latency
+/// without VRR. The rule is kept in the rules file so it survives restarts.
+pub fn set_game_tearing_rule(window_class: &str, enabled: bool) -> Result<(), String> {
+    let compositor = detect_compositor();
+    if compositor != Compositor::Hyprland {
+        return Err(format!(
+            "Tearing rules are only supported on Hyprland, not {}",
+            compositor_name(compositor)
+        ));
+    }
+
+    if enabled {
+        for cmd in tearing_rule_cmds(compositor, window_class).unwrap_or_default() {
+            if !run_compositor_cmd(&cmd)? {
+                return Err(format!("Failed to set tearing rule for {}", window_class));
+            }
+        }
+        write_rules_file("immediate", window_class, Some(&tearing_rule(window_class)))
+    } else {
+        write_rules_file("immediate", window_class, None)?;
+        reset_class_rules(window_class)
+    }
+}
+
+/// Global display toggles as the compositor has them right now; None where
+/// the compositor doesn't expose one. Per-game rules only work on top of these.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositorDisplayState {
+    pub compositor: String,
+    pub vrr_mode: Option<String>,    // "off", "on", "fullscreen", "fullscreen-game", "mixed"
+    pub allow_tearing: Option<bool>, // Whether per-game tearing can take effect at all
+    pub hdr_available: bool,         // Driver and compositor both support HDR
+}
+
+/// Hyprland's misc:vrr values
+fn hyprland_vrr_mode(value: i64) -> Option<&'static str> {
+    match value {
+        0 => Some("off"),
+        1 => Some("on"),
+        2 => Some("fullscreen"),
+        3 => Some("fullscreen-game"), // Fullscreen with game or video content type
+        _ => None,
+    }
+}
+
+/// Integer value from `hyprctl getoption <name> -j`
+fn hyprland_int_option(name: &str) -> Option<i64> {
+    command_json("hyprctl", &["getoption", name, "-j"]).ok()?["int"].as_i64()
+}
+
+/// One VRR mode for all enabled outputs, or "mixed"
+fn common_vrr_mode(modes: &[&str]) -> Option<String> {
+    let first = modes.first()?;
+    Some(match modes.iter().all(|m| m == first) {
+        true => first.to_string(),
+        false => "mixed".to_string(),
+    })
+}
+
+/// KWin's per-output vrrPolicy (0 never, 1 always, 2 automatic = fullscreen)
+fn kde_vrr_modes(json: &serde_json::Value) -> Vec<&'static str> {
+    json["outputs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|o| o["enabled"].as_bool().unwrap_or(false))
+        .filter_map(|o| match o["vrrPolicy"].as_i64()? {
+            0 => Some("off"),
+            1 => Some("on"),
+            2 => Some("fullscreen"),
+            _ => None,
+        })
+        .collect()
+}
+
+/// KWin 6 allows tearing unless `[Compositing] AllowTearing=false` in kwinrc
+fn kwin_allow_tearing(kwinrc: &str) -> bool {
+    let mut in_compositing = false;
+    for line in kwinrc.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_compositing = line == "[Compositing]";
+        } else if in_compositing {
+            if let Some(value) = line.strip_prefix("AllowTearing=") {
+                return value.trim() != "false";
+            }
+        }
+    }
+    true
+}
+
+/// Global VRR, tearing and HDR state of the running compositor
+pub fn get_compositor_display_state() -> CompositorDisplayState {
+    let compositor = detect_compositor();
+    let (vrr_mode, allow_tearing) = match compositor {
+        Compositor::Hyprland => (
+            hyprland_int_option("misc:vrr")
+                .and_then(hyprland_vrr_mode)
+                .map(String::from),
+            hyprland_int_option("general:allow_tearing").map(|v| v != 0),
+        ),
+        Compositor::Kde => {
+            let kwinrc = dirs::config_dir()
+                .and_then(|dir| fs::read_to_string(dir.join("kwinrc")).ok())
+                .unwrap_or_default();
+            (
+                command_json("kscreen-doctor", &["-j"])
+                    .ok()
+                    .and_then(|json| common_vrr_mode(&kde_vrr_modes(&json))),
+                Some(kwin_allow_tearing(&kwinrc)),
+            )
+        }
+        Compositor::Sway => {
+            let modes: Vec<&str> = command_json("swaymsg", &["-t", "get_outputs", "-r"])
+                .ok()
+                .and_then(|json| json.as_array().cloned())
+                .unwrap_or_default()
+                .iter()
+                .filter(|o| o["active"].as_bool().unwrap_or(false))
+                .map(|o| match o["adaptive_sync_status"].as_str() {
+                    Some("enabled") => "on",
+                    _ => "off",
+                })
+                .collect();
+            (common_vrr_mode(&modes), None)
+        }
+        _ => (None, None),
+    };
+
+    let hdr = get_hdr_support();
+    CompositorDisplayState {
+        compositor: compositor_name(compositor).to_string(),
+        vrr_mode,
+        allow_tearing,
+        hdr_available: hdr.driver_supported && hdr.compositor_supported,
+    }
+}
+
+/// Frames below refresh to cap at so VRR never hits the vsync ceiling
+pub const DEFAULT_FRAME_CAP_MARGIN: u32 = 3;
+
+/// Frame cap a few frames under a refresh rate (143.98Hz counts as 144)
+fn frame_cap_for_refresh(refresh_rate: f32, margin: u32) -> u32 {
+    (refresh_rate.round() as u32).saturating_sub(margin).max(1)
+}
+
+/// Recommended frame limiter target for a monitor
+pub fn suggest_frame_cap(monitor_name: &str, margin: Option<u32>) -> Result<u32, String> {
+    let monitor = list_monitors()?
+        .into_iter()
+        .find(|m| m.name == monitor_name)
+        .ok_or_else(|| format!("Monitor '{}' not found", monitor_name))?;
+
+    Ok(frame_cap_for_refresh(
+        monitor.refresh_rate,
+        margin.unwrap_or(DEFAULT_FRAME_CAP_MARGIN),
+    ))
+}
+
+/// Smallest render resolution worth suggesting for integer scaling
+const MIN_INTEGER_SCALE_WIDTH: u32 = 640;
+
+/// Integer factor between an output and a render resolution, if there is one
+/// (same whole-number factor on both axes, 2x or more)
+pub fn integer_scale_factor(output: (u32, u32), render: (u32, u32)) -> Option<u32> {
+    let ((out_w, out_h), (w, h)) = (output, render);
+    if w == 0 || h == 0 || !out_w.is_multiple_of(w) || !out_h.is_multiple_of(h) {
+        return None;
+    }
+    let factor = out_w / w;
+    (factor >= 2 && out_h / h == factor).then_some(factor)
+}
+
+/// Render resolutions that divide evenly into an output resolution
+fn integer_scale_resolutions(width: u32, height: u32) -> Vec<(u32, u32)> {
+    (2..=width / MIN_INTEGER_SCALE_WIDTH)
+        .filter(|&f| width.is_multiple_of(f) && height.is_multiple_of(f))
+        .map(|f| (width / f, height / f))
+        .collect()
+}
+
+/// Internal resolutions that integer-scale to a monitor's mode, e.g. "1280x720"
+/// for 2560x1440; largest first
+pub fn suggest_integer_scale_resolutions(monitor_name: &str) -> Result<Vec<String>, String> {
+    let monitor = list_monitors()?
+        .into_iter()
+        .find(|m| m.name == monitor_name)
+        .ok_or_else(|| format!("Monitor '{}' not found", monitor_name))?;
+
+    Ok(integer_scale_resolutions(monitor.width, monitor.height)
+        .into_iter()
+        .map(|(w, h)| format!("{}x{}", w, h))
+        .collect())
+}
+
+/// Accepted gamma multipliers for night mode
+pub const GAMMA_RANGE: std::ops::RangeInclusive<f32> = 0.5..=2.0;
+/// Accepted color temperatures in Kelvin (6500K is neutral)
+pub const TEMPERATURE_RANGE: std::ops::RangeInclusive<u32> = 1000..=6500;
+pub const NEUTRAL_TEMPERATURE: u32 = 6500;
+/// Used when a profile turns night mode on without picking a temperature
+pub const DEFAULT_NIGHT_TEMPERATURE: u32 = 4000;
+
+/// Color override currently held for one monitor
+struct ColorOverride {
+    temperature: u32,
+    gamma: f32,
+    process: Child,
+}
+
+/// wlsunset instances per monitor; gamma resets when the process exits
+static COLOR_OVERRIDES: Mutex<Option<HashMap<String, ColorOverride>>> = Mutex::new(None);
+
+/// wlsunset invocation holding a fixed temperature/gamma on one output (or all).
+/// wlsunset only does day/night cycles, so day is set 1K above night to make
+/// the time of day irrelevant.
+pub fn night_mode_cmd(output: Option<&str>, temperature: u32, gamma: f32) -> Vec<String> {
+    let low = temperature.min(NEUTRAL_TEMPERATURE - 1);
+    let mut cmd = vec![
+        "wlsunset".to_string(),
+        "-t".to_string(),
+        low.to_string(),
+        "-T".to_string(),
+        (low + 1).to_string(),
+        "-g".to_string(),
+        gamma.to_string(),
+        // A location is required; it doesn't matter with day == night
+        "-l".to_string(),
+        "0".to_string(),
+        "-L".to_string(),
+        "0".to_string(),
+    ];
+    if let Some(output) = output {
+        cmd.extend(["-o".to_string(), output.to_string()]);
+    }
+    cmd
+}
+
+pub(crate) fn apply_monitor_color(
+    name: &str,
+    temperature: Option<u32>,
+    gamma: Option<f32>,
+) -> Result<(), String> {
+    let compositor = detect_compositor();
+    if !matches!(compositor, Compositor::Hyprland | Compositor::Sway) {
+        return Err(format!(
+            "Gamma control needs wlr-gamma-control (Hyprland or Sway), not {}",
+            compositor_name(compositor)
+        ));
+    }
+    if !list_monitors()?.iter().any(|m| m.name == name) {
+        return Err(format!("Monitor '{}' not found", name));
+    }
+
+    let mut guard = COLOR_OVERRIDES.lock().map_err(|e| e.to_string())?;
+    let overrides = guard.get_or_insert_with(HashMap::new);
+
+    // Keep the other setting from the running override, if any
+    let (mut current_temp, mut current_gamma) = (NEUTRAL_TEMPERATURE, 1.0);
+    if let Some(mut previous) = overrides.remove(name) {
+        current_temp = previous.temperature;
+        current_gamma = previous.gamma;
+        previous.process.kill().ok();
+        previous.process.wait().ok();
+    }
+    let temperature = temperature.unwrap_or(current_temp);
+    let gamma = gamma.unwrap_or(current_gamma);
+
+    let cmd = night_mode_cmd(Some(name), temperature, gamma);
+    let process = Command::new(&cmd[0])
+        .args(&cmd[1..])
+        .spawn()
+        .map_err(|e| format!("Failed to start wlsunset: {}", e))?;
+    overrides.insert(
+        name.to_string(),
+        ColorOverride {
+            temperature,
+            gamma,
+            process,
+        },
+    );
+    Ok(())
+}
+
+/// Set a monitor's gamma (0.5-2.0), keeping any temperature already applied
+pub fn set_monitor_gamma(name: &str, gamma: f32) -> Result<(), String> {
+    if !GAMMA_RANGE.contains(&gamma) {
+        return Err(format!("Gamma {} is out of range (0.5 to 2.0)", gamma));
+    }
+    apply_monitor_color(name, None, Some(gamma))
+}
+
+/// Set a monitor's color temperature (1000-6500K), keeping any gamma already applied
+pub fn set_monitor_temperature(name: &str, kelvin: u32) -> Result<(), String> {
+    if !TEMPERATURE_RANGE.contains(&kelvin) {
+        return Err(format!("Temperature {}K is out of range (1000 to 6500)", kelvin));
+    }
+    apply_monitor_color(name, Some(kelvin), None)
+}
+
+/// Drop a monitor's gamma/temperature override, restoring normal colors
+pub fn reset_monitor_color(name: &str) -> Result<(), String> {
+    let mut guard = COLOR_OVERRIDES.lock().map_err(|e| e.to_string())?;
+    if let Some(mut previous) = guard.as_mut().and_then(|o| o.remove(name)) {
+        previous.process.kill().ok();
+        previous.process.wait().ok();
+    }
+    Ok(())
+}
+
+/// Drop every gamma/temperature override
+pub fn reset_all_monitor_colors() -> Result<(), String> {
+    let mut guard = COLOR_OVERRIDES.lock().map_err(|e| e.to_string())?;
+    for (_, mut previous) in guard.take().unwrap_or_default() {
+        previous.process.kill().ok();
+        previous.process.wait().ok();
+    }
+    Ok(())
+}
+
+/// Night mode command for a profile: its target monitor, or every output
+pub fn profile_night_mode_cmd(profile: &GameProfile) -> Option<Vec<String>> {
+    let settings = &profile.screen;
+    if !settings.night_mode {
+        return None;
+    }
+    Some(night_mode_cmd(
+        settings.target_monitor.as_deref(),
+        settings.night_temperature.unwrap_or(DEFAULT_NIGHT_TEMPERATURE),
+        settings.night_gamma.unwrap_or(1.0),
+    ))
+}
+
+fn layouts_dir() -> PathBuf {
+    let dir = dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("unvcpfl")
+        .join("layouts");
+
+    fs::create_dir_all(&dir).ok();
+    dir
+}
+
+fn layout_path(name: &str) -> PathBuf {
+    layouts_dir().join(format!("{}.toml", name.to_lowercase().replace(' ', "_")))
+}
+
+/// Snapshot the current monitor arrangement under a name
+pub fn save_monitor_layout(name: &str) -> Result<(), String> {
+    let layout = MonitorLayout {
+        name: name.to_string(),
+        monitors: list_monitors()?,
+    };
+
+    let content = toml::to_string_pretty(&layout)
+        .map_err(|e| format!("Failed to serialize layout: {}", e))?;
+
+    fs::write(layout_path(name), content).map_err(|e| format!("Failed to write layout: {}", e))
+}
+
+/// List all saved monitor layouts
+pub fn list_monitor_layouts() -> Vec<MonitorLayout> {
+    let mut layouts = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(layouts_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map(|e| e == "toml").unwrap_or(false) {
+                if let Ok(content) = fs::read_to_string(&path) {
+                    if let Ok(layout) = toml::from_str::<MonitorLayout>(&content) {
+                        layouts.push(layout);
+                    }
+                }
+            }
+        }
+    }
+
+    layouts.sort_by_key(|l| l.name.to_lowercase());
+    layouts
+}
+
+/// Restore a saved monitor layout
+pub fn apply_monitor_layout(name: &str) -> Result<(), String> {
+    let content = fs::read_to_string(layout_path(name))
+        .map_err(|_| format!("Layout '{}' not found", name))?;
+    let layout: MonitorLayout =
+        toml::from_str(&content).map_err(|e| format!("Failed to parse layout: {}", e))?;
+    apply_monitors(&layout.monitors)
+}
+
+/// Put monitors back as listed: enabled ones with their mode, the rest off
+pub(crate) fn apply_monitors(monitors: &[Monitor]) -> Result<(), String> {
+    let errors: Vec<String> = apply_each_monitor(monitors)
+        .into_iter()
+        .filter_map(|(name, result)| result.err().map(|e| format!("{}: {}", name, e)))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// apply_monitors, trying every monitor and returning each one's outcome
+pub(crate) fn apply_each_monitor(monitors: &[Monitor]) -> Vec<(String, Result<(), String>)> {
+    let mut results: Vec<(String, Result<(), String>)> = monitors
+        .iter()
+        .filter(|m| m.active)
+        .map(|m| (m.name.clone(), enable_monitor(&m.name, &MonitorConfig::from(m))))
+        .collect();
+
+    // Enable first so we never end up with every output switched off
+    let any_enabled = results.iter().any(|(_, result)| result.is_ok());
+    for m in monitors.iter().filter(|m| !m.active) {
+        let result = match any_enabled {
+            true => disable_monitor(&m.name),
+            false => Err("left on, no other monitor could be enabled".to_string()),
+        };
+        results.push((m.name.clone(), result));
+    }
+    results
+}
+
 /// Check if screen configuration is supported for current compositor
 pub fn is_screen_config_supported() -> bool {
     matches!(detect_compositor(), Compositor::Hyprland | Compositor::Sway)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str) -> Monitor {
+        Monitor {
+            id: 0,
+            name: name.to_string(),
+            description: String::new(),
+            width: 2560,
+            height: 1440,
+            refresh_rate: 144.0,
+            x: 0,
+            y: 0,
+            scale: 1.0,
+            active: true,
+            focused: false,
+        }
+    }
+
+    #[test]
+    fn test_preview_hyprland_commands() {
+        let mut profile = GameProfile {
+            steam_appid: Some(1091500),
+            ..Default::default()
+        };
+        profile.screen.target_monitor = Some("DP-1".to_string());
+        profile.screen.disable_other_monitors = true;
+        profile.screen.allow_tearing = true;
+
+        let cmds: Vec<String> = plan_screen_commands(
+            Compositor::Hyprland,
+            &profile,
+            &[monitor("DP-1"), monitor("HDMI-A-1")],
+        )
+        .iter()
+        .map(|cmd| shell_join(cmd))
+        .collect();
+
+        assert_eq!(
+            cmds,
+            vec![
+                "hyprctl keyword monitor HDMI-A-1,disable",
+                "hyprctl keyword windowrulev2 'monitor DP-1,class:^(steam_app_1091500)$'",
+                "hyprctl keyword general:allow_tearing true",
+                "hyprctl keyword windowrulev2 'immediate,class:^(steam_app_1091500)$'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monitor_config_per_compositor() {
+        let config = MonitorConfig::parse("2560x1440@59.94,1920x0,1.25").unwrap();
+        assert_eq!(config.to_string(), "2560x1440@59.94,1920x0,1.25");
+        assert_eq!(MonitorConfig::from(&monitor("DP-1")).to_string(), "2560x1440@144,0x0,1");
+
+        let sway = enable_monitor_cmd(Compositor::Sway, "DP-2", &config).unwrap();
+        assert_eq!(
+            shell_join(&sway),
+            "swaymsg output DP-2 enable mode 2560x1440@59.94Hz position 1920 0 scale 1.25"
+        );
+        assert!(MonitorConfig::parse("1920x1080,0x0").is_err());
+    }
+
+    #[test]
+    fn test_migrate_string_monitor_configs() {
+        let table: toml::Table = toml::from_str(
+            r#"
+            eDP-1 = "1920x1080@60,0x0,1"
+            DP-1 = "DP-1,2560x1440@143.91,1920x0,1.25"
+            HDMI-A-1 = "disable"
+            "#,
+        )
+        .unwrap();
+        let migrated = migrate_config_table(&table).unwrap();
+        let configs: HashMap<String, MonitorConfig> = migrated.try_into().unwrap();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs["eDP-1"].to_string(), "1920x1080@60,0x0,1");
+        assert_eq!(configs["DP-1"].to_string(), "2560x1440@143.91,1920x0,1.25");
+
+        let current: toml::Table = toml::from_str(&toml::to_string(&configs).unwrap()).unwrap();
+        assert!(migrate_config_table(&current).is_none());
+
+        let layout: toml::Table = toml::from_str(
+            r#"
+            name = "Desk"
+            [monitors]
+            DP-2 = "3840x2160@60,0x0,1.5"
+            "#,
+        )
+        .unwrap();
+        let layout: MonitorLayout = migrate_layout(&layout).unwrap().try_into().unwrap();
+        assert_eq!(layout.name, "Desk");
+        assert_eq!(layout.monitors[0].name, "DP-2");
+        assert_eq!(
+            MonitorConfig::from(&layout.monitors[0]).to_string(),
+            "3840x2160@60,0x0,1.5"
+        );
+        assert!(layout.monitors[0].active);
+    }
+
+    #[test]
+    fn test_running_window_cmds() {
+        let mut profile = GameProfile::default();
+        profile.screen.target_monitor = Some("DP-1".to_string());
+        profile.screen.target_workspace = Some("5".to_string());
+        profile.screen.fullscreen_on_target = true;
+        // Not window-scoped, so left to apply_to_running_game to report
+        profile.screen.disable_other_monitors = true;
+
+        let cmds = running_window_cmds(Compositor::Hyprland, "steam_app_1", &profile);
+        let fields: Vec<&str> = cmds.iter().map(|(field, _)| *field).collect();
+        assert_eq!(
+            fields,
+            vec!["screen.target_monitor", "screen.target_workspace", "screen.fullscreen_on_target"]
+        );
+        assert_eq!(
+            shell_join(&cmds[1].1.as_ref().unwrap()[0]),
+            "hyprctl dispatch movetoworkspacesilent '5,class:^(steam_app_1)$'"
+        );
+        assert!(cmds[2].1.is_none());
+
+        let cmds = running_window_cmds(Compositor::Sway, "osu", &profile);
+        assert_eq!(
+            cmds[0].1.as_ref().unwrap()[0],
+            vec!["swaymsg", "[class=\"osu\"] move container to output DP-1"]
+        );
+    }
+
+    #[test]
+    fn test_workspace_rule_replaces_existing() {
+        let content = "windowrulev2 = workspace 3,class:^(steam_app_1)$\n\
+                       windowrulev2 = workspace 4,class:^(steam_app_2)$\n";
+
+        assert_eq!(
+            update_rules_file(
+                content,
+                "workspace",
+                "steam_app_1",
+                Some("workspace 5,class:^(steam_app_1)$")
+            ),
+            "windowrulev2 = workspace 4,class:^(steam_app_2)$\n\
+             windowrulev2 = workspace 5,class:^(steam_app_1)$\n"
+        );
+        assert_eq!(
+            update_rules_file(content, "workspace", "steam_app_2", None),
+            "windowrulev2 = workspace 3,class:^(steam_app_1)$\n"
+        );
+
+        // Tearing rules sit next to workspace rules and bring the global option along
+        let with_tearing = update_rules_file(
+            content,
+            "immediate",
+            "steam_app_2",
+            Some(&tearing_rule("steam_app_2")),
+        );
+        assert_eq!(
+            with_tearing,
+            "general:allow_tearing = true\n\
+             windowrulev2 = workspace 3,class:^(steam_app_1)$\n\
+             windowrulev2 = workspace 4,class:^(steam_app_2)$\n\
+             windowrulev2 = immediate,class:^(steam_app_2)$\n"
+        );
+        assert_eq!(
+            update_rules_file(&with_tearing, "immediate", "steam_app_2", None),
+            content
+        );
+    }
+
+    #[test]
+    fn test_night_mode_cmd_fixed_temperature() {
+        let mut profile = GameProfile::default();
+        assert!(profile_night_mode_cmd(&profile).is_none());
+
+        profile.screen.night_mode = true;
+        profile.screen.target_monitor = Some("DP-1".to_string());
+        profile.screen.night_gamma = Some(0.8);
+        let cmd = profile_night_mode_cmd(&profile).unwrap();
+        assert!(cmd.windows(2).any(|w| w == ["-t", "4000"]));
+        assert!(cmd.windows(2).any(|w| w == ["-T", "4001"]));
+        assert!(cmd.windows(2).any(|w| w == ["-g", "0.8"]));
+        assert!(cmd.windows(2).any(|w| w == ["-o", "DP-1"]));
+
+        // Neutral still needs day > night
+        let cmd = night_mode_cmd(None, NEUTRAL_TEMPERATURE, 1.0);
+        assert!(cmd.windows(2).any(|w| w == ["-T", "6500"]));
+        assert!(!cmd.contains(&"-o".to_string()));
+    }
+
+    #[test]
+    fn test_frame_cap_below_refresh() {
+        assert_eq!(frame_cap_for_refresh(143.998, DEFAULT_FRAME_CAP_MARGIN), 141);
+        assert_eq!(frame_cap_for_refresh(59.94, 0), 60);
+        assert_eq!(frame_cap_for_refresh(2.0, 5), 1);
+    }
+
+    #[test]
+    fn test_self_window_rule() {
+        let clients = serde_json::json!([
+            { "class": "firefox", "pid": 100 },
+            { "class": "unvcpfl", "pid": 4242 },
+        ]);
+        assert_eq!(hyprland_class_for_pid(&clients, 4242).as_deref(), Some("unvcpfl"));
+        assert_eq!(hyprland_class_for_pid(&clients, 1), None);
+
+        let tree = serde_json::json!({ "nodes": [{ "nodes": [], "floating_nodes": [
+            { "pid": 4242, "app_id": null, "window_properties": { "class": "unvcpfl" } },
+        ]}]});
+        assert_eq!(
+            sway_criteria_for_pid(&tree, 4242),
+            Some(("class", "unvcpfl".to_string()))
+        );
+
+        let sway = ("app_id", "unvcpfl");
+        let cmds = self_rule_cmds(Compositor::Sway, sway, 4242, "floating enable", true).unwrap();
+        assert_eq!(cmds[0][1], "[app_id=\"unvcpfl\"] floating enable");
+        assert_eq!(cmds[1][1], "for_window [app_id=\"unvcpfl\"] floating enable");
+        let cmds = self_rule_cmds(Compositor::Sway, sway, 4242, "floating enable", false).unwrap();
+        assert_eq!(cmds.len(), 1);
+
+        let hypr = ("class", "unvcpfl");
+        let cmds = self_rule_cmds(Compositor::Hyprland, hypr, 4242, "float", true).unwrap();
+        assert_eq!(cmds[0], ["hyprctl", "dispatch", "setfloating", "pid:4242"]);
+        assert_eq!(cmds[1][3], "float,class:^(unvcpfl)$");
+        let cmds = self_rule_cmds(Compositor::Hyprland, hypr, 4242, "workspace 3 silent", false).unwrap();
+        assert_eq!(cmds, vec![vec!["hyprctl", "dispatch", "movetoworkspacesilent", "3,pid:4242"]]);
+        let cmds = self_rule_cmds(Compositor::Hyprland, hypr, 4242, "monitor DP-1", true).unwrap();
+        assert_eq!(cmds.len(), 1);
+        assert!(self_rule_cmds(Compositor::Kde, hypr, 4242, "float", true).is_none());
+    }
+
+    #[test]
+    fn test_compositor_display_state_parsing() {
+        assert_eq!(hyprland_vrr_mode(2), Some("fullscreen"));
+        assert_eq!(hyprland_vrr_mode(7), None);
+
+        let kscreen = serde_json::json!({ "outputs": [
+            { "name": "DP-1", "enabled": true, "vrrPolicy": 2 },
+            { "name": "HDMI-A-1", "enabled": false, "vrrPolicy": 0 },
+        ]});
+        assert_eq!(
+            common_vrr_mode(&kde_vrr_modes(&kscreen)).as_deref(),
+            Some("fullscreen")
+        );
+        assert_eq!(common_vrr_mode(&["on", "off"]).as_deref(), Some("mixed"));
+        assert_eq!(common_vrr_mode(&[]), None);
+
+        assert!(kwin_allow_tearing("[Wayland]\nAllowTearing=false\n"));
+        assert!(!kwin_allow_tearing("[Compositing]\nAllowTearing=false\n"));
+    }
+
+    #[test]
+    fn test_hyprland_color_info() {
+        let output = serde_json::json!({
+            "name": "DP-1",
+            "currentFormat": "XBGR2101010",
+            "colorManagementPreset": "hdr"
+        });
+        let info = hyprland_color_info(&output);
+        assert_eq!(info.bit_depth, Some(10));
+        assert_eq!(info.color_format.as_deref(), Some("XBGR2101010"));
+        assert_eq!(info.hdr_active, Some(true));
+
+        // Older Hyprland without the fields reports nothing rather than guessing
+        let info = hyprland_color_info(&serde_json::json!({ "name": "HDMI-A-1" }));
+        assert_eq!(info.bit_depth, None);
+        assert_eq!(info.hdr_active, None);
+    }
+
+    #[test]
+    fn test_hdr_capability_unknown_unless_active() {
+        let hdr = serde_json::json!({ "name": "DP-1", "colorManagementPreset": "hdr" });
+        let sdr = serde_json::json!({ "name": "DP-2", "currentFormat": "XRGB8888" });
+        assert_eq!(output_hdr_capability(Compositor::Hyprland, &hdr), Some(true));
+        // An SDR output may still support HDR
+        assert_eq!(output_hdr_capability(Compositor::Hyprland, &sdr), None);
+        assert_eq!(
+            output_hdr_capability(Compositor::Sway, &serde_json::json!({ "hdr": false })),
+            None
+        );
+    }
+
+    #[test]
+    fn test_integer_scale_resolutions() {
+        assert_eq!(
+            integer_scale_resolutions(3840, 2160),
+            vec![(1920, 1080), (1280, 720), (960, 540), (768, 432), (640, 360)]
+        );
+        assert_eq!(integer_scale_resolutions(2560, 1440), vec![(1280, 720), (640, 360)]);
+        assert_eq!(integer_scale_factor((2560, 1440), (1280, 720)), Some(2));
+        assert_eq!(integer_scale_factor((2560, 1440), (1920, 1080)), None);
+        assert_eq!(integer_scale_factor((2560, 1440), (1280, 480)), None);
+    }
+}
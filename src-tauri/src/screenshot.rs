@@ -0,0 +1,117 @@
+//! Screenshots of the focused monitor for comparing profiles side by side
+//!
+//! Captures are named after the active profile, so two runs with different
+//! DLSS presets or vkBasalt effects end up next to each other in one folder.
+
+use crate::profiles::profile_stem;
+use crate::screen::{self, Compositor};
+use crate::system::is_tool_available;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Capture command for a compositor, trying tools in order of preference.
+/// `monitor` limits the capture to one output where the tool supports it.
+fn screenshot_cmd(
+    compositor: Compositor,
+    monitor: Option<&str>,
+    path: &str,
+    available: impl Fn(&str) -> bool,
+) -> Result<Vec<String>, String> {
+    let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<String>>();
+    let candidates: Vec<Vec<String>> = match compositor {
+        // wlroots screencopy
+        Compositor::Hyprland | Compositor::Sway => match monitor {
+            Some(monitor) => vec![args(&["grim", "-o", monitor, path])],
+            None => vec![args(&["grim", path])],
+        },
+        // -b background, -n no notification, -m current monitor
+        Compositor::Kde => vec![args(&["spectacle", "-b", "-n", "-m", "-o", path])],
+        Compositor::Gnome => vec![args(&["gnome-screenshot", "-f", path])],
+        Compositor::X11 => vec![args(&["maim", path]), args(&["scrot", "-o", path])],
+        // Steam's own screenshot key is the only capture path in Game Mode
+        Compositor::GamescopeSession | Compositor::Unknown => Vec::new(),
+    };
+
+    let tools: Vec<String> = candidates.iter().map(|c| c[0].clone()).collect();
+    candidates
+        .into_iter()
+        .find(|cmd| available(&cmd[0]))
+        .ok_or_else(|| {
+            if tools.is_empty() {
+                format!(
+                    "Screenshots not supported for {}",
+                    screen::compositor_name(compositor)
+                )
+            } else {
+                format!("No screenshot tool installed (need {})", tools.join(" or "))
+            }
+        })
+}
+
+/// Screenshot the focused monitor to `output_path` (PNG)
+pub fn capture_screenshot(output_path: &Path) -> Result<(), String> {
+    let compositor = screen::detect_compositor();
+    let monitor = screen::get_focused_monitor().map(|m| m.name);
+    let path = output_path.to_string_lossy();
+    let cmd = screenshot_cmd(compositor, monitor.as_deref(), &path, is_tool_available)?;
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let output = Command::new(&cmd[0])
+        .args(&cmd[1..])
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", cmd[0], e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            cmd[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn screenshots_dir() -> PathBuf {
+    dirs::picture_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join("Pictures")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("unvcpfl")
+}
+
+/// `<profile>-<unix time>.png`, using the same name mangling as profile files
+fn profile_capture_name(profile_name: &str, timestamp: u64) -> String {
+    format!("{}-{}.png", profile_stem(profile_name), timestamp)
+}
+
+/// Screenshot into ~/Pictures/unvcpfl named after the profile; returns the path
+pub fn capture_profile_screenshot(profile_name: &str) -> Result<String, String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = screenshots_dir().join(profile_capture_name(profile_name, timestamp));
+    capture_screenshot(&path)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screenshot_tool_selection() {
+        let cmd = screenshot_cmd(Compositor::Hyprland, Some("DP-1"), "/tmp/a.png", |_| true).unwrap();
+        assert_eq!(cmd, vec!["grim", "-o", "DP-1", "/tmp/a.png"]);
+
+        // maim missing on X11: fall back to scrot
+        let cmd = screenshot_cmd(Compositor::X11, None, "/tmp/a.png", |t| t == "scrot").unwrap();
+        assert_eq!(cmd[0], "scrot");
+
+        let err = screenshot_cmd(Compositor::X11, None, "/tmp/a.png", |_| false).unwrap_err();
+        assert_eq!(err, "No screenshot tool installed (need maim or scrot)");
+        assert_eq!(profile_capture_name("Elden Ring", 42), "elden_ring-42.png");
+    }
+}
@@ -0,0 +1,206 @@
+//! GPU telemetry capture for a play session, exported as CSV
+//!
+//! Complements MangoHud logs with the GPU side: temperature, clocks, power and
+//! utilization sampled once a second from NVML. NVML has no frame data, so
+//! fps stays in the MangoHud log. Energy used over the capture goes into the
+//! session energy history when it stops.
+
+use crate::nvidia::{GpuInfo, SessionEnergy, SharedGpuState};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Cap the buffer at 12 hours of samples
+const MAX_SAMPLES: usize = 12 * 60 * 60;
+
+#[derive(Debug, Clone)]
+struct GpuSample {
+    timestamp_ms: u64, // Unix time
+    info: GpuInfo,
+}
+
+type Samples = Arc<Mutex<VecDeque<GpuSample>>>;
+
+struct SessionCapture {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+    samples: Samples,
+}
+
+static SESSION_CAPTURE: Mutex<Option<SessionCapture>> = Mutex::new(None);
+
+/// Samples of the last stopped capture, kept for export
+static LAST_SESSION: Mutex<Option<Vec<GpuSample>>> = Mutex::new(None);
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+async fn capture(
+    gpu_state: SharedGpuState,
+    game: String,
+    samples: Samples,
+    mut stop: oneshot::Receiver<()>,
+) {
+    let started = Instant::now();
+    let energy_start = gpu_state
+        .read()
+        .await
+        .monitor
+        .as_ref()
+        .and_then(|m| m.total_energy_mj());
+
+    let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = &mut stop => break,
+            _ = interval.tick() => {
+                let info = gpu_state
+                    .read()
+                    .await
+                    .monitor
+                    .as_ref()
+                    .and_then(|m| m.get_info(0).ok());
+                if let (Some(info), Ok(mut samples)) = (info, samples.lock()) {
+                    if samples.len() >= MAX_SAMPLES {
+                        samples.pop_front();
+                    }
+                    samples.push_back(GpuSample { timestamp_ms: unix_millis(), info });
+                }
+            }
+        }
+    }
+
+    let mut gpu = gpu_state.write().await;
+    let energy_end = gpu.monitor.as_ref().and_then(|m| m.total_energy_mj());
+    if let (Some(start), Some(end)) = (energy_start, energy_end) {
+        gpu.record_session_energy(SessionEnergy::from_samples(&game, start, end, started));
+    }
+}
+
+/// Start buffering GPU samples for a session; `game` labels its energy record
+pub async fn start_session_capture(
+    gpu_state: SharedGpuState,
+    game: Option<String>,
+) -> Result<(), String> {
+    if gpu_state.read().await.monitor.is_none() {
+        return Err("No NVIDIA GPU detected".to_string());
+    }
+
+    let mut running = SESSION_CAPTURE.lock().map_err(|e| e.to_string())?;
+    if running.is_some() {
+        return Err("Session capture is already running".to_string());
+    }
+
+    let samples: Samples = Arc::new(Mutex::new(VecDeque::new()));
+    let (stop, stop_rx) = oneshot::channel();
+    let game = game.unwrap_or_else(|| "Unknown".to_string());
+    let task = tokio::spawn(capture(gpu_state, game, Arc::clone(&samples), stop_rx));
+    *running = Some(SessionCapture { stop, task, samples });
+    Ok(())
+}
+
+/// Stop the capture; returns the number of samples kept for export
+pub async fn stop_session_capture() -> Result<usize, String> {
+    let running = SESSION_CAPTURE.lock().map_err(|e| e.to_string())?.take();
+    let Some(running) = running else {
+        return Err("No session capture is running".to_string());
+    };
+    running.stop.send(()).ok();
+    running.task.await.map_err(|e| e.to_string())?;
+
+    let samples = std::mem::take(&mut *running.samples.lock().map_err(|e| e.to_string())?);
+    let count = samples.len();
+    *LAST_SESSION.lock().map_err(|e| e.to_string())? = Some(samples.into());
+    Ok(count)
+}
+
+fn samples_to_csv(samples: &[GpuSample]) -> String {
+    const MIB: u64 = 1024 * 1024;
+    let start = samples.first().map(|s| s.timestamp_ms).unwrap_or(0);
+    let mut csv = String::from(
+        "timestamp_ms,elapsed_s,temperature_c,clock_graphics_mhz,clock_memory_mhz,power_draw_w,power_limit_w,utilization_pct,memory_used_mib,fan_speed_pct\n",
+    );
+    for s in samples {
+        let i = &s.info;
+        csv.push_str(&format!(
+            "{},{:.1},{},{},{},{:.1},{:.1},{},{},{}\n",
+            s.timestamp_ms,
+            s.timestamp_ms.saturating_sub(start) as f64 / 1000.0,
+            i.temperature,
+            i.clock_graphics,
+            i.clock_memory,
+            i.power_draw,
+            i.power_limit,
+            i.utilization,
+            i.memory_used / MIB,
+            i.fan_speed.map(|f| f.to_string()).unwrap_or_default(),
+        ));
+    }
+    csv
+}
+
+/// Write the running capture, or else the last stopped one, to `dest` as CSV
+pub fn export_gpu_session_csv(dest: &Path) -> Result<(), String> {
+    let running = SESSION_CAPTURE
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_ref()
+        .and_then(|c| c.samples.lock().ok().map(|s| s.iter().cloned().collect()));
+    let samples = match running {
+        Some(samples) => samples,
+        None => LAST_SESSION
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone()
+            .ok_or("No session captured yet")?,
+    };
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+    std::fs::write(dest, samples_to_csv(&samples))
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_csv_rows() {
+        let info = GpuInfo {
+            index: 0,
+            name: "RTX 4080".to_string(),
+            temperature: 65,
+            power_draw: 250.5,
+            power_limit: 320.0,
+            utilization: 97,
+            encoder_utilization: None,
+            decoder_utilization: None,
+            memory_used: 8192 * 1024 * 1024,
+            memory_total: 16384 * 1024 * 1024,
+            clock_graphics: 2715,
+            clock_memory: 11201,
+            fan_speed: None,
+        };
+        let samples = vec![
+            GpuSample { timestamp_ms: 1_000, info: info.clone() },
+            GpuSample { timestamp_ms: 2_500, info },
+        ];
+
+        let csv = samples_to_csv(&samples);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("timestamp_ms,elapsed_s,"));
+        assert_eq!(lines[2], "2500,1.5,65,2715,11201,250.5,320.0,97,8192,");
+    }
+}
@@ -0,0 +1,250 @@
+//! DXVK state cache and Steam shader cache inspection
+//!
+//! Helps troubleshoot shader stutter and reclaim disk space per game.
+
+use crate::game_settings::find_game_install_path;
+use crate::games::GameDetector;
+use crate::profiles::ProfileManager;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How deep to look for *.dxvk-cache files below the game install dir
+const DXVK_CACHE_SEARCH_DEPTH: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheFile {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// Shader cache locations and sizes for a Steam game
+#[derive(Debug, Clone, Serialize)]
+pub struct ShaderCacheInfo {
+    pub steam_appid: u32,
+    pub dxvk_cache_files: Vec<CacheFile>,    // *.dxvk-cache next to the game or in Steam's cache
+    pub shader_cache_dir: Option<String>,    // steamapps/shadercache/<appid>
+    pub shader_cache_size_bytes: u64,
+    pub total_size_bytes: u64,
+    pub state_cache_enabled: bool,           // False if DXVK_STATE_CACHE disables it for this game
+}
+
+pub fn get_shader_cache_info(manager: &ProfileManager, steam_appid: u32) -> ShaderCacheInfo {
+    let shader_cache_dir = GameDetector::find_shadercache_path(steam_appid);
+    let dxvk_cache_files: Vec<CacheFile> = dxvk_cache_paths(steam_appid)
+        .into_iter()
+        .map(|path| CacheFile {
+            size_bytes: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect();
+
+    let shader_cache_size_bytes = shader_cache_dir.as_deref().map(dir_size).unwrap_or(0);
+    let dxvk_paths: Vec<PathBuf> = dxvk_cache_files
+        .iter()
+        .map(|f| PathBuf::from(&f.path))
+        .collect();
+    let total_size_bytes = total_cache_size(shader_cache_dir.as_deref(), &dxvk_paths);
+
+    ShaderCacheInfo {
+        steam_appid,
+        shader_cache_dir: shader_cache_dir.map(|p| p.to_string_lossy().to_string()),
+        shader_cache_size_bytes,
+        total_size_bytes,
+        state_cache_enabled: state_cache_enabled(manager, steam_appid),
+        dxvk_cache_files,
+    }
+}
+
+/// Delete a game's shader caches; returns the number of bytes freed.
+/// `confirm_size_bytes` is the total_size_bytes the user confirmed; nothing is
+/// deleted when the caches no longer add up to it.
+pub fn clear_shader_cache(steam_appid: u32, confirm_size_bytes: u64) -> Result<u64, String> {
+    clear_caches(
+        GameDetector::find_shadercache_path(steam_appid).as_deref(),
+        &dxvk_cache_paths(steam_appid),
+        confirm_size_bytes,
+    )
+}
+
+/// Size of the shadercache dir plus the DXVK caches outside it
+fn total_cache_size(shader_cache_dir: Option<&Path>, dxvk_caches: &[PathBuf]) -> u64 {
+    // Files inside shadercache are already counted in its size
+    let loose_cache_bytes: u64 = dxvk_caches
+        .iter()
+        .filter(|path| {
+            shader_cache_dir
+                .map(|dir| !path.starts_with(dir))
+                .unwrap_or(true)
+        })
+        .map(|path| fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+        .sum();
+    shader_cache_dir.map(dir_size).unwrap_or(0) + loose_cache_bytes
+}
+
+fn clear_caches(
+    shader_cache_dir: Option<&Path>,
+    dxvk_caches: &[PathBuf],
+    confirm_size_bytes: u64,
+) -> Result<u64, String> {
+    let size = total_cache_size(shader_cache_dir, dxvk_caches);
+    if size != confirm_size_bytes {
+        return Err(format!(
+            "Shader caches are now {} bytes, not the confirmed {}; check them again before clearing",
+            size, confirm_size_bytes
+        ));
+    }
+
+    let mut freed = 0;
+
+    for path in dxvk_caches {
+        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        freed += size;
+    }
+
+    // Steam recreates the directory itself; only clear what's inside
+    if let Some(dir) = shader_cache_dir {
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let size = if path.is_dir() {
+                dir_size(&path)
+            } else {
+                fs::metadata(&path).map(|m| m.len()).unwrap_or(0)
+            };
+            let removed = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            removed.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            freed += size;
+        }
+    }
+
+    Ok(freed)
+}
+
+/// A profile can turn the cache off with DXVK_STATE_CACHE=0/disable; otherwise
+/// whatever the app inherited applies
+fn state_cache_enabled(manager: &ProfileManager, steam_appid: u32) -> bool {
+    let value = manager
+        .list_profiles()
+        .into_iter()
+        .find(|p| p.steam_appid == Some(steam_appid))
+        .and_then(|p| manager.build_env_vars(&p).remove("DXVK_STATE_CACHE"))
+        .or_else(|| std::env::var("DXVK_STATE_CACHE").ok());
+
+    !matches!(value.as_deref(), Some("0") | Some("disable"))
+}
+
+/// *.dxvk-cache files in Steam's shader cache and below the install dir
+fn dxvk_cache_paths(steam_appid: u32) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Some(dir) = GameDetector::find_shadercache_path(steam_appid) {
+        collect_dxvk_caches(&dir, DXVK_CACHE_SEARCH_DEPTH, &mut paths);
+    }
+
+    let home = std::env::var("HOME").unwrap_or_default();
+    let install_dir = PathBuf::from(find_game_install_path(steam_appid, &home));
+    if install_dir.is_dir() {
+        collect_dxvk_caches(&install_dir, DXVK_CACHE_SEARCH_DEPTH, &mut paths);
+    }
+
+    paths
+}
+
+fn collect_dxvk_caches(dir: &Path, depth: usize, paths: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth > 0 {
+                collect_dxvk_caches(&path, depth - 1, paths);
+            }
+        } else if path.extension().map(|e| e == "dxvk-cache").unwrap_or(false) {
+            paths.push(path);
+        }
+    }
+}
+
+pub(crate) fn dir_size(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                dir_size(&path)
+            } else {
+                entry.metadata().map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(path: &Path, size: usize) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![0u8; size]).unwrap();
+    }
+
+    #[test]
+    fn test_dxvk_cache_search_depth() {
+        let root = std::env::temp_dir().join("unvcpfl-test-dxvk-depth");
+        fs::remove_dir_all(&root).ok();
+        write_file(&root.join("game.dxvk-cache"), 1);
+        write_file(&root.join("a/b/c/deep.dxvk-cache"), 1);
+        write_file(&root.join("a/b/c/d/too_deep.dxvk-cache"), 1);
+        write_file(&root.join("a/game.exe"), 1);
+
+        let mut paths = Vec::new();
+        collect_dxvk_caches(&root, DXVK_CACHE_SEARCH_DEPTH, &mut paths);
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                root.join("a/b/c/deep.dxvk-cache"),
+                root.join("game.dxvk-cache")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clear_caches_counts_freed_bytes_once() {
+        let root = std::env::temp_dir().join("unvcpfl-test-clear-caches");
+        fs::remove_dir_all(&root).ok();
+        let shader_cache = root.join("shadercache/292030");
+        write_file(
+            &shader_cache.join("fozpipelinesv6/steam_pipeline_cache.foz"),
+            100,
+        );
+        write_file(
+            &shader_cache.join("DXVK_state_cache/witcher3.dxvk-cache"),
+            20,
+        );
+        let install_dir = root.join("common/The Witcher 3");
+        write_file(&install_dir.join("bin/witcher3.dxvk-cache"), 5);
+        write_file(&install_dir.join("bin/witcher3.exe"), 1000);
+
+        let mut caches = Vec::new();
+        collect_dxvk_caches(&shader_cache, DXVK_CACHE_SEARCH_DEPTH, &mut caches);
+        collect_dxvk_caches(&install_dir, DXVK_CACHE_SEARCH_DEPTH, &mut caches);
+        assert_eq!(dir_size(&shader_cache), 120);
+        assert_eq!(total_cache_size(Some(&shader_cache), &caches), 125);
+
+        assert!(clear_caches(Some(&shader_cache), &caches, 124).is_err());
+        assert!(install_dir.join("bin/witcher3.dxvk-cache").exists());
+
+        assert_eq!(clear_caches(Some(&shader_cache), &caches, 125), Ok(125));
+        assert!(shader_cache.is_dir());
+        assert_eq!(fs::read_dir(&shader_cache).unwrap().count(), 0);
+        assert!(!install_dir.join("bin/witcher3.dxvk-cache").exists());
+        assert!(install_dir.join("bin/witcher3.exe").exists());
+    }
+}
@@ -0,0 +1,300 @@
+//! Turn pasted Steam launch options into a profile
+//!
+//! The inverse of what the wrapper script does at launch: env assignments and
+//! wrappers before `%command%` map onto profile fields where one exists,
+//! everything after it becomes the game's arguments.
+
+use crate::profiles::GameProfile;
+
+/// Split a launch options line into words, honoring shell quoting
+pub(crate) fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.extend(chars.next());
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// `KEY=value` with a valid env var name
+fn parse_assignment(word: &str) -> Option<(&str, &str)> {
+    let (key, value) = word.split_once('=')?;
+    let valid = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then_some((key, value))
+}
+
+/// Map one env var onto a first-class field; false leaves it for custom_env
+fn apply_env(profile: &mut GameProfile, key: &str, value: &str) -> bool {
+    let on = value == "1";
+    match key {
+        "PROTON_DLSS_UPGRADE" if on => profile.dlss.upgrade = true,
+        "PROTON_DLSS_INDICATOR" if on => profile.dlss.indicator = true,
+        "PROTON_ENABLE_NGX_UPDATER" if on => profile.dlss.ngx_updater = true,
+        "DXVK_NVAPI_DRS_NGX_DLSS_SR_OVERRIDE" if value == "on" => profile.dlss.sr_override = true,
+        "DXVK_NVAPI_DRS_NGX_DLSS_RR_OVERRIDE" if value == "on" => profile.dlss.rr_override = true,
+        "DXVK_NVAPI_DRS_NGX_DLSS_FG_OVERRIDE" if value == "on" => profile.dlss.fg_override = true,
+        "DXVK_NVAPI_DRS_NGX_DLSS_SR_OVERRIDE_RENDER_PRESET_SELECTION" => {
+            profile.dlss.sr_preset = Some(value.to_string())
+        }
+        "DXVK_NVAPI_DRS_NGX_DLSS_RR_OVERRIDE_RENDER_PRESET_SELECTION" => {
+            profile.dlss.rr_preset = Some(value.to_string())
+        }
+        "DXVK_NVAPI_DRS_NGX_DLSSG_MULTI_FRAME_COUNT" => {
+            profile.dlss.fg_multi_frame = Some(value.to_string())
+        }
+        "DXVK_HUD" => profile.dxvk.hud = Some(value.to_string()),
+        "DXVK_ENABLE_NVAPI" if on => profile.dxvk.nvapi = true,
+        "DXVK_ASYNC" if on => profile.dxvk.async_compile = true,
+        "VKD3D_CONFIG" => return apply_vkd3d_config(profile, value),
+        "VKD3D_FRAME_RATE" => match value.parse() {
+            Ok(fps) => profile.vkd3d.frame_rate = fps,
+            Err(_) => return false,
+        },
+        "DXVK_FRAME_RATE" => match value.parse() {
+            Ok(fps) => {
+                profile.wrappers.frame_limiter.enabled = true;
+                profile.wrappers.frame_limiter.target_fps = Some(fps);
+            }
+            Err(_) => return false,
+        },
+        "__GL_SYNC_TO_VBLANK" if on || value == "0" => {
+            profile.nvidia.vsync = Some(if on { "on" } else { "off" }.to_string())
+        }
+        "__NV_PRIME_RENDER_OFFLOAD" if on => profile.nvidia.prime = true,
+        // Set alongside __NV_PRIME_RENDER_OFFLOAD by the prime toggle
+        "__VK_LAYER_NV_optimus" if value == "NVIDIA_only" => profile.nvidia.prime = true,
+        "__GLX_VENDOR_LIBRARY_NAME" if value == "nvidia" => profile.nvidia.prime = true,
+        // Keeping a game on the iGPU
+        "__VK_LAYER_NV_optimus" if value == "non_NVIDIA_only" => {
+            profile.nvidia.offload_target = Some("igpu".to_string())
+        }
+        "DRI_PRIME" if value == "0" => profile.nvidia.offload_target = Some("igpu".to_string()),
+        "NVPRESENT_ENABLE_SMOOTH_MOTION" if on => profile.nvidia.smooth_motion = true,
+        "__GL_MaxFramesAllowed" => match value.parse() {
+            Ok(frames) => profile.nvidia.max_frames_allowed = Some(frames),
+            Err(_) => return false,
+        },
+        "__GL_SHADER_DISK_CACHE" if on || value == "0" => profile.nvidia.shader_disk_cache = Some(on),
+        "__GL_SHADER_DISK_CACHE_PATH" => {
+            profile.nvidia.shader_disk_cache_path = Some(value.to_string())
+        }
+        "__GL_GSYNC_ALLOWED" if on || value == "0" => profile.nvidia.gsync_allowed = Some(on),
+        "PROTON_VERB" => profile.proton.verb = Some(value.to_string()),
+        "PROTON_NO_FSYNC" if on => profile.proton.sync_mode = Some("esync".to_string()),
+        "PROTON_NO_ESYNC" if on => profile.proton.sync_mode = Some("fsync".to_string()),
+        "WINEFSYNC_FUTEX2" if on => profile.proton.sync_mode = Some("ntsync".to_string()),
+        "PROTON_ENABLE_WAYLAND" if on => profile.proton.enable_wayland = true,
+        "PROTON_ENABLE_HDR" if on => profile.proton.enable_hdr = true,
+        "WINE_FULLSCREEN_INTEGER_SCALING" if on => profile.proton.integer_scaling = true,
+        "MANGOHUD" if on => profile.wrappers.mangohud.enabled = true,
+        "ENABLE_VKBASALT" if on => profile.vkbasalt.enabled = true,
+        _ => return false,
+    }
+    true
+}
+
+/// VKD3D_CONFIG flags onto vkd3d fields, only if every flag has a field
+fn apply_vkd3d_config(profile: &mut GameProfile, value: &str) -> bool {
+    let flags: Vec<&str> = value.split(',').filter(|f| !f.is_empty()).collect();
+    let known = ["nodxr", "dxr", "dxr12", "force_static_cbv", "single_queue", "no_upload_hvv"];
+    if !flags.iter().all(|f| known.contains(f)) {
+        return false;
+    }
+
+    let vkd3d = &mut profile.vkd3d;
+    for flag in flags {
+        match flag {
+            "nodxr" => vkd3d.no_dxr = true,
+            "dxr" => vkd3d.force_dxr = true,
+            "dxr12" => vkd3d.dxr12 = true,
+            "force_static_cbv" => vkd3d.force_static_cbv = true,
+            "single_queue" => vkd3d.single_queue = true,
+            _ => vkd3d.no_upload_hvv = true,
+        }
+    }
+    true
+}
+
+/// gamescope flags up to `--`; unrecognized ones go to extra_args
+fn apply_gamescope_args(profile: &mut GameProfile, args: &[String]) {
+    let gs = &mut profile.wrappers.gamescope;
+    gs.enabled = true;
+
+    let mut args = args.iter().peekable();
+    while let Some(arg) = args.next() {
+        let mut number = || args.next().and_then(|v| v.parse::<u32>().ok());
+        match arg.as_str() {
+            "-W" | "--output-width" => gs.width = number(),
+            "-H" | "--output-height" => gs.height = number(),
+            "-w" | "--nested-width" => gs.internal_width = number(),
+            "-h" | "--nested-height" => gs.internal_height = number(),
+            "-r" | "--nested-refresh" => gs.framelimit = number(),
+            "-o" | "--nested-unfocused-refresh" => gs.unfocused_framelimit = number(),
+            "--fsr-sharpness" | "--sharpness" => gs.fsr_sharpness = number(),
+            "-F" | "--filter" => gs.upscale_filter = args.next().cloned(),
+            "-S" | "--scaler" => gs.scaling_mode = args.next().cloned(),
+            "-f" | "--fullscreen" => gs.fullscreen = true,
+            "-b" | "--borderless" => gs.borderless = true,
+            "--adaptive-sync" => gs.vrr = true,
+            "--mangoapp" => gs.mangoapp = true,
+            "--hdr-enabled" => gs.hdr = true,
+            _ => {
+                gs.extra_args.push(arg.clone());
+                // Keep an unknown flag's value with it
+                if let Some(value) = args.next_if(|v| !v.starts_with('-')) {
+                    gs.extra_args.push(value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Build a profile from a Steam launch options line such as
+/// `DXVK_ASYNC=1 gamemoderun mangohud %command% -dx11`.
+/// Anything that can't be represented is listed in the description.
+pub fn import_steam_launch_options(line: &str) -> GameProfile {
+    let mut profile = GameProfile::default();
+    let words = split_words(line);
+
+    // Without %command% Steam passes the whole line to the game as arguments
+    let Some(split) = words.iter().position(|w| w == "%command%") else {
+        profile.custom_args = (!words.is_empty()).then(|| words.join(" "));
+        return profile;
+    };
+    let (prefix, args) = (&words[..split], &words[split + 1..]);
+    if !args.is_empty() {
+        profile.custom_args = Some(args.join(" "));
+    }
+
+    let mut skipped: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < prefix.len() {
+        let word = prefix[i].as_str();
+        i += 1;
+
+        if let Some((key, value)) = parse_assignment(word) {
+            if !apply_env(&mut profile, key, value) {
+                profile.custom_env.insert(key.to_string(), value.to_string());
+            }
+            continue;
+        }
+
+        match word {
+            "env" => {}
+            "gamemoderun" => profile.wrappers.gamemode = true,
+            "mangohud" => profile.wrappers.mangohud.enabled = true,
+            "game-performance" => profile.wrappers.game_performance = true,
+            "dlss-swapper" => profile.wrappers.dlss_swapper = true,
+            "prime-run" => profile.nvidia.prime = true,
+            "gamescope" => {
+                let end = prefix[i..]
+                    .iter()
+                    .position(|w| w == "--")
+                    .map(|p| i + p)
+                    .unwrap_or(prefix.len());
+                apply_gamescope_args(&mut profile, &prefix[i..end]);
+                i = (end + 1).min(prefix.len());
+            }
+            "nice" if prefix.get(i).map(String::as_str) == Some("-n") => {
+                profile.cpu.nice = prefix.get(i + 1).and_then(|n| n.parse().ok());
+                i += 2;
+            }
+            "ionice" => {
+                while let Some(arg) = prefix.get(i).filter(|a| a.starts_with('-')) {
+                    i += 1;
+                    if arg == "-c" {
+                        profile.cpu.ionice_class = prefix.get(i).and_then(|c| c.parse().ok());
+                        i += 1;
+                    }
+                }
+            }
+            // Unknown wrapper: its arguments can't be told apart from later words
+            _ => {
+                skipped.push(prefix[i - 1..].join(" "));
+                break;
+            }
+        }
+    }
+
+    profile.description = Some(if skipped.is_empty() {
+        "Imported from Steam launch options".to_string()
+    } else {
+        format!("Imported from Steam launch options; not imported: {}", skipped.join(" "))
+    });
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_maps_wrappers_and_env() {
+        let profile = import_steam_launch_options(
+            "PROTON_ENABLE_WAYLAND=1 VKD3D_CONFIG=dxr12 WINEDLLOVERRIDES=\"dinput8=n,b\" \
+             gamescope -W 2560 -H 1440 -f --force-grab-cursor -- gamemoderun mangohud %command% -dx11 -skipintro",
+        );
+
+        assert!(profile.proton.enable_wayland);
+        assert!(profile.vkd3d.dxr12);
+        assert_eq!(profile.custom_env["WINEDLLOVERRIDES"], "dinput8=n,b");
+        assert!(profile.wrappers.gamescope.enabled);
+        assert_eq!(profile.wrappers.gamescope.width, Some(2560));
+        assert!(profile.wrappers.gamescope.fullscreen);
+        assert_eq!(profile.wrappers.gamescope.extra_args, vec!["--force-grab-cursor"]);
+        assert!(profile.wrappers.gamemode);
+        assert!(profile.wrappers.mangohud.enabled);
+        assert_eq!(profile.custom_args.as_deref(), Some("-dx11 -skipintro"));
+        assert_eq!(profile.description.as_deref(), Some("Imported from Steam launch options"));
+    }
+
+    #[test]
+    fn test_import_keeps_unknown_wrapper_in_description() {
+        let profile = import_steam_launch_options("strangle 60 %command%");
+        assert_eq!(
+            profile.description.as_deref(),
+            Some("Imported from Steam launch options; not imported: strangle 60")
+        );
+
+        // No %command%: Steam appends everything to the game's arguments
+        let profile = import_steam_launch_options("-novid -high");
+        assert_eq!(profile.custom_args.as_deref(), Some("-novid -high"));
+        assert!(profile.custom_env.is_empty());
+    }
+}
@@ -0,0 +1,443 @@
+//! System environment probing (kernel, NVIDIA modules, sync primitives, shell env)
+//!
+//! Used to gate kernel-dependent toggles in the UI and for diagnostics.
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// NVIDIA kernel modules we care about
+const NVIDIA_MODULES: &[&str] = &["nvidia", "nvidia_modeset", "nvidia_drm", "nvidia_uvm"];
+
+/// Kernel and driver environment
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub kernel_version: String,
+    pub nvidia_driver_version: Option<String>,
+    pub nvidia_modules_loaded: Vec<String>,   // Subset of NVIDIA_MODULES currently loaded
+    pub nvidia_kernel_module: Option<String>, // "open" or "proprietary"
+    pub ntsync_available: bool,               // /dev/ntsync exists (kernel 6.14+ or backport)
+    pub mangohud_layer_installed: bool,       // Vulkan layer manifest found, not just the binary
+    pub vulkan_icds: Vec<String>,             // ICD manifests the Vulkan loader would load, in order
+    pub nvidia_icd_first: bool,               // NVIDIA's ICD is present and loaded before any other
+    pub steam_deck: bool,                     // Steam Deck hardware or SteamOS
+}
+
+/// Probe the running system
+pub fn get_system_info() -> SystemInfo {
+    let vulkan_icds = get_active_vulkan_icd();
+    SystemInfo {
+        kernel_version: kernel_version(),
+        nvidia_driver_version: nvidia_driver_version(),
+        nvidia_modules_loaded: loaded_nvidia_modules(),
+        nvidia_kernel_module: get_kernel_module_type(),
+        ntsync_available: Path::new("/dev/ntsync").exists(),
+        mangohud_layer_installed: check_mangohud_layer(),
+        nvidia_icd_first: vulkan_icds.first().map(|f| is_nvidia_icd(f)).unwrap_or(false),
+        vulkan_icds,
+        steam_deck: is_steam_deck(),
+    }
+}
+
+/// DMI product names of the Steam Deck LCD and OLED models
+const STEAM_DECK_PRODUCTS: &[&str] = &["Jupiter", "Galileo"];
+
+fn is_deck_hardware(board_vendor: &str, product_name: &str) -> bool {
+    board_vendor.trim() == "Valve" && STEAM_DECK_PRODUCTS.contains(&product_name.trim())
+}
+
+/// `ID=steamos` in /etc/os-release
+fn is_steamos(os_release: &str) -> bool {
+    os_release
+        .lines()
+        .filter_map(|line| line.strip_prefix("ID="))
+        .any(|id| id.trim().trim_matches('"') == "steamos")
+}
+
+/// Running on a Steam Deck or under SteamOS (which also ships on other handhelds)
+pub fn is_steam_deck() -> bool {
+    let dmi = |field: &str| {
+        fs::read_to_string(format!("/sys/class/dmi/id/{}", field)).unwrap_or_default()
+    };
+    is_deck_hardware(&dmi("board_vendor"), &dmi("product_name"))
+        || fs::read_to_string("/etc/os-release")
+            .map(|content| is_steamos(&content))
+            .unwrap_or(false)
+}
+
+/// Kernel release as reported by `uname -r`
+pub fn kernel_version() -> String {
+    Command::new("uname")
+        .arg("-r")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Read the loaded NVIDIA kernel module version
+pub fn nvidia_driver_version() -> Option<String> {
+    fs::read_to_string("/sys/module/nvidia/version")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Whether the open or proprietary NVIDIA kernel module is loaded.
+/// None when the nvidia module isn't loaded or can't be told apart.
+pub fn get_kernel_module_type() -> Option<String> {
+    let version = fs::read_to_string("/proc/driver/nvidia/version").ok();
+    let taint = fs::read_to_string("/sys/module/nvidia/taint").ok();
+    kernel_module_type(version.as_deref(), taint.as_deref()).map(String::from)
+}
+
+/// The driver banner names the open module explicitly; failing that, only the
+/// proprietary module taints the kernel with 'P' (the open one is MIT/GPL)
+fn kernel_module_type(version: Option<&str>, taint: Option<&str>) -> Option<&'static str> {
+    if let Some(banner) = version.and_then(|v| v.lines().next()) {
+        if banner.contains("Open Kernel Module") {
+            return Some("open");
+        }
+        if banner.contains("Kernel Module") {
+            return Some("proprietary");
+        }
+    }
+
+    taint.map(|t| if t.contains('P') { "proprietary" } else { "open" })
+}
+
+/// Env var prefixes unvcpfl sets from profiles
+const MANAGED_ENV_PREFIXES: &[&str] = &[
+    "DXVK_",
+    "VKD3D_",
+    "PROTON_",
+    "WINEFSYNC",
+    "WINE_FULLSCREEN_",
+    "__GL_",
+    "__NV_",
+    "__GLX_VENDOR_LIBRARY_NAME",
+    "__VK_LAYER_NV_",
+    "NVPRESENT_",
+    "MANGOHUD",
+    "ENABLE_VKBASALT",
+    "VKBASALT_",
+    "SDL_VIDEODRIVER",
+];
+
+/// Find managed env vars exported from shell startup files or environment.d,
+/// which can silently override profile settings. Read-only.
+/// Each entry reads like `~/.bashrc:12: DXVK_HUD=fps`.
+pub fn scan_shell_env_conflicts() -> Vec<String> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = [".bashrc", ".bash_profile", ".zshrc", ".zshenv", ".profile"]
+        .iter()
+        .map(|f| home.join(f))
+        .collect();
+    let mut env_d: Vec<PathBuf> = fs::read_dir(home.join(".config/environment.d"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|e| e == "conf").unwrap_or(false))
+        .collect();
+    env_d.sort();
+    files.extend(env_d);
+
+    files
+        .iter()
+        .filter_map(|path| Some((path, fs::read_to_string(path).ok()?)))
+        .flat_map(|(path, content)| {
+            let display = path
+                .strip_prefix(&home)
+                .map(|p| format!("~/{}", p.display()))
+                .unwrap_or_else(|_| path.display().to_string());
+            find_managed_exports(&content)
+                .into_iter()
+                .map(move |(line, assignment)| format!("{}:{}: {}", display, line, assignment))
+        })
+        .collect()
+}
+
+/// (line number, `KEY=value`) for managed vars assigned in shell/environment.d syntax
+fn find_managed_exports(content: &str) -> Vec<(usize, String)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.trim();
+            if line.starts_with('#') {
+                return None;
+            }
+            let assignment = line.strip_prefix("export ").unwrap_or(line).trim();
+            let (key, _) = assignment.split_once('=')?;
+            let managed = MANAGED_ENV_PREFIXES.iter().any(|p| key.starts_with(p))
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            managed.then(|| (i + 1, assignment.to_string()))
+        })
+        .collect()
+}
+
+/// Directories the Vulkan loader searches for `subdir` (e.g. "icd.d"), in order:
+/// XDG config dirs, /etc, then XDG data dirs
+fn vulkan_search_dirs(subdir: &str) -> Vec<PathBuf> {
+    let xdg_list = |var: &str, default: &str| -> Vec<PathBuf> {
+        std::env::var(var)
+            .unwrap_or_else(|_| default.to_string())
+            .split(':')
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .collect()
+    };
+
+    let mut roots: Vec<PathBuf> = Vec::new();
+    roots.extend(dirs::config_dir());
+    roots.extend(xdg_list("XDG_CONFIG_DIRS", "/etc/xdg"));
+    roots.push(PathBuf::from("/etc"));
+    roots.extend(dirs::data_dir());
+    roots.extend(xdg_list("XDG_DATA_DIRS", "/usr/local/share:/usr/share"));
+
+    roots
+        .into_iter()
+        .map(|root| root.join("vulkan").join(subdir))
+        .collect()
+}
+
+/// JSON manifests in a directory, sorted like the loader reads them
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|e| e == "json").unwrap_or(false))
+        .collect();
+    files.sort();
+    files
+}
+
+/// MangoHud ships VkLayer_MangoHud.json (older) or MangoHud.<arch>.json
+fn is_mangohud_layer_file(name: &str) -> bool {
+    name.ends_with(".json")
+        && (name.starts_with("VkLayer_MangoHud") || name.starts_with("MangoHud."))
+}
+
+/// Whether MangoHud's Vulkan layer is installed. `mangohud` can be on PATH
+/// with the layer missing, and then the overlay silently never shows.
+pub fn check_mangohud_layer() -> bool {
+    vulkan_search_dirs("implicit_layer.d")
+        .iter()
+        .flat_map(|dir| json_files(dir))
+        .any(|f| is_mangohud_layer_file(&f.file_name().unwrap_or_default().to_string_lossy()))
+}
+
+/// Files and directories from a VK_DRIVER_FILES / VK_ICD_FILENAMES value
+fn icd_override_files(value: &str) -> Vec<PathBuf> {
+    value
+        .split(':')
+        .filter(|p| !p.is_empty())
+        .flat_map(|p| {
+            let path = PathBuf::from(p);
+            if path.is_dir() {
+                json_files(&path)
+            } else {
+                vec![path]
+            }
+        })
+        .collect()
+}
+
+/// Vulkan ICD manifests the loader would load, in load order. An override
+/// in VK_DRIVER_FILES (or the older VK_ICD_FILENAMES) replaces the search.
+pub fn get_active_vulkan_icd() -> Vec<String> {
+    let files = match std::env::var("VK_DRIVER_FILES").or_else(|_| std::env::var("VK_ICD_FILENAMES")) {
+        Ok(value) => icd_override_files(&value),
+        Err(_) => {
+            let mut files: Vec<PathBuf> = std::env::var("VK_ADD_DRIVER_FILES")
+                .map(|v| icd_override_files(&v))
+                .unwrap_or_default();
+            files.extend(vulkan_search_dirs("icd.d").iter().flat_map(|dir| json_files(dir)));
+            files
+        }
+    };
+
+    let mut seen = HashSet::new();
+    files
+        .into_iter()
+        .filter(|f| seen.insert(fs::canonicalize(f).unwrap_or_else(|_| f.clone())))
+        .map(|f| f.to_string_lossy().to_string())
+        .collect()
+}
+
+fn is_nvidia_icd(path: &str) -> bool {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase().contains("nvidia"))
+        .unwrap_or(false)
+}
+
+/// stdout and stderr of `gamescope <arg>`; gamescope prints help and version to stderr
+fn gamescope_output(arg: &str) -> Option<String> {
+    let output = Command::new("gamescope").arg(arg).output().ok()?;
+    Some(format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+/// Installed gamescope version, e.g. "3.16.2"
+pub fn gamescope_version() -> Option<String> {
+    let text = gamescope_output("--version")?;
+    Regex::new(r"gamescope version (\S+)")
+        .ok()?
+        .captures(&text)
+        .map(|c| c[1].to_string())
+}
+
+/// First gamescope release with --hdr-enabled
+const MIN_GAMESCOPE_HDR_VERSION: (u32, u32) = (3, 12);
+
+/// (major, minor) from "3.16.2" or "3.14.24-1"; git builds like "b5b5e2b" give None
+fn major_minor(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim_start_matches('v').split(['.', '-']);
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+fn gamescope_hdr_supported(help: &str, version: Option<&str>) -> bool {
+    let new_enough = version
+        .and_then(major_minor)
+        .map(|v| v >= MIN_GAMESCOPE_HDR_VERSION)
+        .unwrap_or(true);
+    help.contains("--hdr-enabled") && new_enough
+}
+
+/// Whether the installed gamescope accepts --hdr-enabled. Older builds ignore
+/// unknown flags, so HDR would silently stay off.
+pub fn check_gamescope_hdr_support() -> bool {
+    gamescope_output("--help")
+        .map(|help| gamescope_hdr_supported(&help, gamescope_version().as_deref()))
+        .unwrap_or(false)
+}
+
+/// Whether an executable with this name is on PATH
+pub fn is_tool_available(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+/// (pid, command name) of every process visible in /proc
+pub fn list_processes() -> Vec<(u32, String)> {
+    fs::read_dir("/proc")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let pid = e.file_name().to_str()?.parse().ok()?;
+            let comm = fs::read_to_string(e.path().join("comm")).ok()?;
+            Some((pid, comm.trim_end().to_string()))
+        })
+        .collect()
+}
+
+/// NVIDIA modules listed in /proc/modules
+fn loaded_nvidia_modules() -> Vec<String> {
+    let content = fs::read_to_string("/proc/modules").unwrap_or_default();
+    let loaded: Vec<&str> = content
+        .lines()
+        .filter_map(|l| l.split_whitespace().next())
+        .collect();
+
+    NVIDIA_MODULES
+        .iter()
+        .filter(|m| loaded.contains(m))
+        .map(|m| m.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernel_module_type() {
+        let open = "NVRM version: NVIDIA UNIX Open Kernel Module for x86_64  560.35.03  Release Build";
+        let proprietary = "NVRM version: NVIDIA UNIX x86_64 Kernel Module  550.120  Fri Sep 13 2024";
+
+        assert_eq!(kernel_module_type(Some(open), None), Some("open"));
+        assert_eq!(kernel_module_type(Some(proprietary), None), Some("proprietary"));
+        assert_eq!(kernel_module_type(None, Some("POE\n")), Some("proprietary"));
+        assert_eq!(kernel_module_type(None, Some("OE\n")), Some("open"));
+        assert_eq!(kernel_module_type(None, None), None);
+    }
+
+    #[test]
+    fn test_steam_deck_detection() {
+        assert!(is_deck_hardware("Valve\n", "Jupiter\n"));
+        assert!(is_deck_hardware("Valve", "Galileo"));
+        assert!(!is_deck_hardware("ASUSTeK COMPUTER INC.", "Jupiter"));
+
+        assert!(is_steamos("NAME=\"SteamOS\"\nID=steamos\nID_LIKE=arch\n"));
+        assert!(!is_steamos("NAME=\"Arch Linux\"\nID=arch\n"));
+        // ID_LIKE alone doesn't count
+        assert!(!is_steamos("ID=holoiso\nID_LIKE=steamos\n"));
+    }
+
+    #[test]
+    fn test_find_managed_exports() {
+        let bashrc = "# export DXVK_HUD=full\nexport PATH=$PATH:~/bin\nexport DXVK_HUD=fps\nPROTON_LOG=1\n";
+
+        assert_eq!(
+            find_managed_exports(bashrc),
+            vec![(3, "DXVK_HUD=fps".to_string()), (4, "PROTON_LOG=1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_gamescope_hdr_support() {
+        let help = "  --hdr-enabled                  enable HDR output (needs Gamescope WSI layer enabled for support from clients)\n";
+        assert!(gamescope_hdr_supported(help, Some("3.16.2")));
+        assert!(gamescope_hdr_supported(help, None));
+        assert!(!gamescope_hdr_supported(help, Some("3.11.51")));
+        assert!(!gamescope_hdr_supported("  -f, --fullscreen\n", Some("3.16.2")));
+        assert_eq!(major_minor("3.14.24-1"), Some((3, 14)));
+        assert_eq!(major_minor("b5b5e2b"), None);
+    }
+
+    #[test]
+    fn test_mangohud_layer_file_names() {
+        assert!(is_mangohud_layer_file("VkLayer_MangoHud.json"));
+        assert!(is_mangohud_layer_file("MangoHud.x86_64.json"));
+        assert!(!is_mangohud_layer_file("VkLayer_MESA_device_select.json"));
+        assert!(!is_mangohud_layer_file("MangoHud.x86_64.json.bak"));
+    }
+
+    #[test]
+    fn test_icd_override_expands_directories() {
+        let dir = std::env::temp_dir().join("unvcpfl-test-icd");
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        for file in ["nvidia_icd.json", "intel_icd.x86_64.json", "README"] {
+            fs::write(dir.join(file), "{}").unwrap();
+        }
+
+        let value = format!("/opt/custom_icd.json:{}", dir.display());
+        let files: Vec<String> = icd_override_files(&value)
+            .iter()
+            .map(|f| f.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(files, vec!["custom_icd.json", "intel_icd.x86_64.json", "nvidia_icd.json"]);
+        assert!(is_nvidia_icd("/usr/share/vulkan/icd.d/nvidia_icd.json"));
+        assert!(!is_nvidia_icd("/usr/share/vulkan/icd.d/intel_icd.x86_64.json"));
+    }
+}
@@ -0,0 +1,192 @@
+//! Minimal parser for Valve's text KeyValues (VDF) format
+//!
+//! Handles quoted and unquoted tokens, escape sequences, `//` comments and
+//! platform conditionals like `[$WIN32]` (which are skipped).
+
+/// A parsed VDF node: either a string value or a nested section
+#[derive(Debug, Clone, PartialEq)]
+pub enum VdfValue {
+    Str(String),
+    Section(Vec<(String, VdfValue)>),
+}
+
+impl VdfValue {
+    /// Look up a child by key (case-insensitive, as Steam does)
+    pub fn get(&self, key: &str) -> Option<&VdfValue> {
+        match self {
+            VdfValue::Section(entries) => entries
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            VdfValue::Str(_) => None,
+        }
+    }
+
+    /// Follow a path of keys
+    pub fn get_path(&self, path: &[&str]) -> Option<&VdfValue> {
+        path.iter().try_fold(self, |node, key| node.get(key))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            VdfValue::Str(s) => Some(s),
+            VdfValue::Section(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '{' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            '}' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '/' => {
+                // `//` comment runs to end of line
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+            }
+            '[' => {
+                // Platform conditional, e.g. [$WIN32]
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut s = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(other) => s.push(other),
+                            None => break,
+                        },
+                        c => s.push(c),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            _ => {
+                let mut s = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '{' || c == '}' || c == '"' {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Str(s));
+            }
+        }
+    }
+
+    tokens
+}
+
+fn parse_section(tokens: &mut impl Iterator<Item = Token>) -> Vec<(String, VdfValue)> {
+    let mut entries = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        let key = match token {
+            Token::Str(key) => key,
+            Token::Close => break,
+            // Stray brace without a key; skip it
+            Token::Open => continue,
+        };
+
+        match tokens.next() {
+            Some(Token::Str(value)) => entries.push((key, VdfValue::Str(value))),
+            Some(Token::Open) => entries.push((key, VdfValue::Section(parse_section(tokens)))),
+            Some(Token::Close) | None => break,
+        }
+    }
+
+    entries
+}
+
+/// Parse VDF text into a root section. Malformed input yields whatever parsed cleanly.
+pub fn parse(input: &str) -> VdfValue {
+    let mut tokens = tokenize(input).into_iter();
+    VdfValue::Section(parse_section(&mut tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compat_tool_mapping() {
+        let input = r#"
+"InstallConfigStore"
+{
+	"Software"
+	{
+		"valve"
+		{
+			"Steam"
+			{
+				// per-game overrides
+				"CompatToolMapping"
+				{
+					"1091500"
+					{
+						"name"		"GE-Proton9-20"
+						"config"		""
+						"priority"		"250"
+					}
+				}
+				"Path"		"C:\\Games\\Steam" [$WIN32]
+			}
+		}
+	}
+}
+"#;
+        let root = parse(input);
+        let name = root
+            .get_path(&[
+                "InstallConfigStore",
+                "Software",
+                "Valve",
+                "Steam",
+                "CompatToolMapping",
+                "1091500",
+                "name",
+            ])
+            .and_then(|v| v.as_str());
+        assert_eq!(name, Some("GE-Proton9-20"));
+
+        let path = root
+            .get_path(&["InstallConfigStore", "Software", "Valve", "Steam", "Path"])
+            .and_then(|v| v.as_str());
+        assert_eq!(path, Some("C:\\Games\\Steam"));
+    }
+}
@@ -24,6 +24,7 @@ const createDefaultProfile = (game: Game | null): GameProfile => ({
     name: game?.name || "Global Settings",
     description: null,
     is_template: false,
+    min_gpu_generation: null,
     executable_match: game?.executable || null,
     steam_appid: game?.source === "Steam" ? parseInt(game.id) : null,
     dlss: {
@@ -41,6 +42,14 @@ const createDefaultProfile = (game: Game | null): GameProfile => ({
         hud: null,
         nvapi: true,
         async_compile: true,
+        hud_fps: false,
+        hud_frametimes: false,
+        hud_gpuload: false,
+        hud_memory: false,
+        hud_devinfo: false,
+        hud_version: false,
+        hud_api: false,
+        hud_compiler: false,
     },
     vkd3d: {
         no_dxr: false,
@@ -56,6 +65,12 @@ const createDefaultProfile = (game: Game | null): GameProfile => ({
         triple_buffer: false,
         prime: false,
         smooth_motion: false,
+        max_frames_allowed: null,
+        shader_disk_cache: null,
+        shader_disk_cache_path: null,
+        powermizer_mode: null,
+        gsync_allowed: null,
+        offload_target: null,
     },
     proton: {
         verb: "waitforexitandrun",
@@ -63,6 +78,10 @@ const createDefaultProfile = (game: Game | null): GameProfile => ({
         enable_wayland: false,
         enable_hdr: false,
         integer_scaling: false,
+        use_umu: false,
+        version: null,
+        force_display_server: null,
+        ld_preload: [],
     },
     wrappers: {
         mangohud: {
@@ -84,13 +103,16 @@ const createDefaultProfile = (game: Game | null): GameProfile => ({
             dsr_width: null,
             dsr_height: null,
             upscale_filter: null,
+            scaling_mode: null,
             fsr_sharpness: null,
             fullscreen: true,
             borderless: false,
             vrr: false,
             framelimit: null,
+            unfocused_framelimit: null,
             mangoapp: false,
             hdr: false,
+            extra_args: [],
         },
         frame_limiter: {
             enabled: false,
@@ -99,12 +121,31 @@ const createDefaultProfile = (game: Game | null): GameProfile => ({
         },
         lact_profile: null,
         lact_restore_after_exit: true,
+        inhibit_idle: false,
     },
     screen: {
         target_monitor: null,
         fullscreen_on_target: false,
         disable_other_monitors: false,
         restore_monitors_after_exit: true,
+        target_workspace: null,
+        night_mode: false,
+        night_temperature: null,
+        night_gamma: null,
+        allow_tearing: false,
+    },
+    audio: {
+        target_sink: null,
+        restore_sink_after_exit: true,
+    },
+    vkbasalt: {
+        enabled: false,
+        effects: [],
+        cas_sharpness: null,
+    },
+    cpu: {
+        nice: null,
+        ionice_class: null,
     },
     custom_env: {},
     custom_args: null,
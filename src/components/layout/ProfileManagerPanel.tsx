@@ -34,6 +34,7 @@ export function ProfileManagerPanel({ onSelectTemplate }: ProfileManagerPanelPro
             name: newProfileName.trim(),
             description: newProfileDesc.trim() || null,
             is_template: true,
+            min_gpu_generation: null,
             executable_match: null,
             steam_appid: null,
             dlss: {
@@ -51,6 +52,14 @@ export function ProfileManagerPanel({ onSelectTemplate }: ProfileManagerPanelPro
                 hud: null,
                 nvapi: true,
                 async_compile: true,
+                hud_fps: false,
+                hud_frametimes: false,
+                hud_gpuload: false,
+                hud_memory: false,
+                hud_devinfo: false,
+                hud_version: false,
+                hud_api: false,
+                hud_compiler: false,
             },
             vkd3d: {
                 no_dxr: false,
@@ -66,6 +75,12 @@ export function ProfileManagerPanel({ onSelectTemplate }: ProfileManagerPanelPro
                 triple_buffer: false,
                 prime: false,
                 smooth_motion: false,
+                max_frames_allowed: null,
+                shader_disk_cache: null,
+                shader_disk_cache_path: null,
+                powermizer_mode: null,
+                gsync_allowed: null,
+                offload_target: null,
             },
             proton: {
                 verb: "waitforexitandrun",
@@ -73,6 +88,10 @@ export function ProfileManagerPanel({ onSelectTemplate }: ProfileManagerPanelPro
                 enable_wayland: false,
                 enable_hdr: false,
                 integer_scaling: false,
+                use_umu: false,
+                version: null,
+                force_display_server: null,
+                ld_preload: [],
             },
             wrappers: {
                 mangohud: {
@@ -94,13 +113,16 @@ export function ProfileManagerPanel({ onSelectTemplate }: ProfileManagerPanelPro
                     dsr_width: null,
                     dsr_height: null,
                     upscale_filter: null,
+                    scaling_mode: null,
                     fsr_sharpness: null,
                     fullscreen: true,
                     borderless: false,
                     vrr: false,
                     framelimit: null,
+                    unfocused_framelimit: null,
                     mangoapp: false,
                     hdr: false,
+                    extra_args: [],
                 },
                 frame_limiter: {
                     enabled: false,
@@ -109,6 +131,7 @@ export function ProfileManagerPanel({ onSelectTemplate }: ProfileManagerPanelPro
                 },
                 lact_profile: null,
                 lact_restore_after_exit: true,
+                inhibit_idle: false,
             },
             custom_env: {},
             custom_args: null,
@@ -117,6 +140,24 @@ export function ProfileManagerPanel({ onSelectTemplate }: ProfileManagerPanelPro
                 fullscreen_on_target: false,
                 disable_other_monitors: false,
                 restore_monitors_after_exit: true,
+                target_workspace: null,
+                night_mode: false,
+                night_temperature: null,
+                night_gamma: null,
+                allow_tearing: false,
+            },
+            audio: {
+                target_sink: null,
+                restore_sink_after_exit: true,
+            },
+            vkbasalt: {
+                enabled: false,
+                effects: [],
+                cas_sharpness: null,
+            },
+            cpu: {
+                nice: null,
+                ionice_class: null,
             },
         };
 
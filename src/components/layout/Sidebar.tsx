@@ -1,5 +1,5 @@
 import { useEffect, useState, useMemo, useCallback } from "react";
-import { Game, GameProfile, detectGames, listProfiles, listTemplateProfiles, saveProfile, deleteProfile } from "@/lib/api";
+import { Game, GameProfile, detectGames, listProfiles, listTemplateProfiles, reconcileProfiles, saveProfile, deleteProfile } from "@/lib/api";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { GpuMonitor } from "./GpuMonitor";
 import { Search, RefreshCw, Settings2, Monitor, Plus, MoreVertical, Pencil, Trash2, Layers } from "lucide-react";
@@ -63,6 +63,8 @@ export function Sidebar({ selectedGame, selectedProfile, onSelectGame, onSelectP
     const loadGames = useCallback(async () => {
         setLoading(true);
         try {
+            // Hand-edited profile names would otherwise leave stale or duplicate files
+            await reconcileProfiles().catch(e => console.error("Failed to reconcile profiles:", e));
             const [detected, savedProfiles, templates] = await Promise.all([
                 detectGames(),
                 listProfiles(),
@@ -115,6 +117,7 @@ export function Sidebar({ selectedGame, selectedProfile, onSelectGame, onSelectP
             name: newProfileName.trim(),
             description: null,
             is_template: true,
+            min_gpu_generation: null,
             executable_match: null,
             steam_appid: null,
             dlss: {
@@ -132,6 +135,14 @@ export function Sidebar({ selectedGame, selectedProfile, onSelectGame, onSelectP
                 hud: null,
                 nvapi: true,
                 async_compile: true,
+                hud_fps: false,
+                hud_frametimes: false,
+                hud_gpuload: false,
+                hud_memory: false,
+                hud_devinfo: false,
+                hud_version: false,
+                hud_api: false,
+                hud_compiler: false,
             },
             vkd3d: {
                 no_dxr: false,
@@ -147,6 +158,12 @@ export function Sidebar({ selectedGame, selectedProfile, onSelectGame, onSelectP
                 triple_buffer: false,
                 prime: false,
                 smooth_motion: false,
+                max_frames_allowed: null,
+                shader_disk_cache: null,
+                shader_disk_cache_path: null,
+                powermizer_mode: null,
+                gsync_allowed: null,
+                offload_target: null,
             },
             proton: {
                 verb: "waitforexitandrun",
@@ -154,6 +171,10 @@ export function Sidebar({ selectedGame, selectedProfile, onSelectGame, onSelectP
                 enable_wayland: false,
                 enable_hdr: false,
                 integer_scaling: false,
+                use_umu: false,
+                version: null,
+                force_display_server: null,
+                ld_preload: [],
             },
             wrappers: {
                 mangohud: {
@@ -175,13 +196,16 @@ export function Sidebar({ selectedGame, selectedProfile, onSelectGame, onSelectP
                     dsr_width: null,
                     dsr_height: null,
                     upscale_filter: null,
+                    scaling_mode: null,
                     fsr_sharpness: null,
                     fullscreen: true,
                     borderless: false,
                     vrr: false,
                     framelimit: null,
+                    unfocused_framelimit: null,
                     mangoapp: false,
                     hdr: false,
+                    extra_args: [],
                 },
                 frame_limiter: {
                     enabled: false,
@@ -190,6 +214,7 @@ export function Sidebar({ selectedGame, selectedProfile, onSelectGame, onSelectP
                 },
                 lact_profile: null,
                 lact_restore_after_exit: true,
+                inhibit_idle: false,
             },
             custom_env: {},
             custom_args: null,
@@ -198,6 +223,24 @@ export function Sidebar({ selectedGame, selectedProfile, onSelectGame, onSelectP
                 fullscreen_on_target: false,
                 disable_other_monitors: false,
                 restore_monitors_after_exit: true,
+                target_workspace: null,
+                night_mode: false,
+                night_temperature: null,
+                night_gamma: null,
+                allow_tearing: false,
+            },
+            audio: {
+                target_sink: null,
+                restore_sink_after_exit: true,
+            },
+            vkbasalt: {
+                enabled: false,
+                effects: [],
+                cas_sharpness: null,
+            },
+            cpu: {
+                nice: null,
+                ionice_class: null,
             },
         };
 
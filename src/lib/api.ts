@@ -2,11 +2,14 @@ import { invoke } from "@tauri-apps/api/core";
 
 // Types matching Rust structs
 export interface GpuInfo {
+    index: number; // NVML device index
     name: string;
     temperature: number;
     power_draw: number;
     power_limit: number;
     utilization: number;
+    encoder_utilization: number | null; // NVENC, percent
+    decoder_utilization: number | null; // NVDEC, percent
     memory_used: number;
     memory_total: number;
     clock_graphics: number;
@@ -14,7 +17,37 @@ export interface GpuInfo {
     fan_speed: number | null;
 }
 
-export type GameSource = "Steam" | "Lutris" | "Heroic" | "Faugus";
+export interface GpuAdvancedInfo {
+    ecc_enabled: boolean | null;
+    ecc_pending: boolean | null;
+    compute_mode: string | null;
+}
+
+export interface ResizableBarStatus {
+    bar1_total: number;  // Bytes of VRAM the CPU can map
+    vram_total: number;
+    enabled: boolean;
+}
+
+export interface NvmlQueryTiming {
+    query: string;
+    ms: number;
+}
+
+export interface NvmlLatency {
+    queries: NvmlQueryTiming[];
+    get_info_ms: number;
+    slow: boolean;
+}
+
+export interface SessionEnergy {
+    game: string;
+    energy_mj: number;
+    kwh: number;
+    duration_secs: number;
+}
+
+export type GameSource = "Steam" | "Lutris" | "Heroic" | "Faugus" | "Wine";
 
 export interface Game {
     id: string;
@@ -41,6 +74,14 @@ export interface DxvkSettings {
     hud: string | null;
     nvapi: boolean;
     async_compile: boolean;
+    hud_fps: boolean;
+    hud_frametimes: boolean;
+    hud_gpuload: boolean;
+    hud_memory: boolean;
+    hud_devinfo: boolean;
+    hud_version: boolean;
+    hud_api: boolean;
+    hud_compiler: boolean;
 }
 
 export interface Vkd3dSettings {
@@ -58,6 +99,12 @@ export interface NvidiaSettings {
     triple_buffer: boolean;
     prime: boolean;
     smooth_motion: boolean;
+    max_frames_allowed: number | null;
+    shader_disk_cache: boolean | null;
+    shader_disk_cache_path: string | null;
+    powermizer_mode: PowerMizerMode | null;
+    gsync_allowed: boolean | null;  // Per-game __GL_GSYNC_ALLOWED
+    offload_target: "igpu" | "nvidia" | null;  // igpu: DRI_PRIME=0 and NVIDIA hidden; overrides prime
 }
 
 export interface ProtonSettings {
@@ -66,6 +113,10 @@ export interface ProtonSettings {
     enable_wayland: boolean;
     enable_hdr: boolean;
     integer_scaling: boolean;
+    use_umu: boolean;
+    version: string | null;  // Proton build for umu-run (PROTONPATH)
+    force_display_server: string | null;  // "x11", "wayland"
+    ld_preload: string[];  // Libraries joined into LD_PRELOAD
 }
 
 export interface FrameLimiterSettings {
@@ -84,13 +135,16 @@ export interface GamescopeSettings {
     dsr_width: number | null;
     dsr_height: number | null;
     upscale_filter: string | null;
+    scaling_mode: string | null; // "auto" | "integer" | "fit" | "fill" | "stretch"
     fsr_sharpness: number | null;
     fullscreen: boolean;
     borderless: boolean;
     vrr: boolean;
-    framelimit: number | null;
+    framelimit: number | null;            // gamescope -r (nested refresh cap)
+    unfocused_framelimit: number | null;  // gamescope -o (cap while unfocused)
     mangoapp: boolean;
     hdr: boolean;
+    extra_args: string[];
 }
 
 export interface MangoHudSettings {
@@ -109,6 +163,7 @@ export interface WrapperSettings {
     frame_limiter: FrameLimiterSettings;
     lact_profile: string | null;
     lact_restore_after_exit: boolean;
+    inhibit_idle: boolean;
 }
 
 export interface ScreenSettings {
@@ -116,6 +171,37 @@ export interface ScreenSettings {
     fullscreen_on_target: boolean;
     disable_other_monitors: boolean;
     restore_monitors_after_exit: boolean;
+    target_workspace: string | null;
+    night_mode: boolean;
+    night_temperature: number | null;  // Kelvin, 1000-6500
+    night_gamma: number | null;        // 0.5-2.0
+    allow_tearing: boolean;            // Hyprland "immediate" rule while the game runs
+}
+
+export interface AudioSettings {
+    target_sink: string | null;
+    restore_sink_after_exit: boolean;
+}
+
+export interface DockProfile {
+    screen: ScreenSettings;
+    audio: AudioSettings;
+}
+
+export interface DockProfiles {
+    docked: DockProfile;
+    undocked: DockProfile;
+}
+
+export interface VkBasaltSettings {
+    enabled: boolean;
+    effects: string[];  // "cas", "dls", "fxaa", "smaa", "lut"
+    cas_sharpness: number | null;
+}
+
+export interface CpuSettings {
+    nice: number | null;          // -20 to 19
+    ionice_class: number | null;  // 0 none, 1 realtime, 2 best-effort, 3 idle
 }
 
 export interface Monitor {
@@ -132,10 +218,31 @@ export interface Monitor {
     focused: boolean;
 }
 
+export interface HdrSupport {
+    driver_version: string | null;
+    driver_supported: boolean;
+    compositor_supported: boolean;
+    // Monitor name -> HDR can be enabled (null: unknown); null when outputs can't be listed
+    monitors: Record<string, boolean | null> | null;
+}
+
+export interface ColorInfo {
+    name: string;
+    bit_depth: number | null;
+    color_format: string | null;
+    hdr_active: boolean | null;
+}
+
+export interface MonitorLayout {
+    name: string;
+    monitors: Monitor[];
+}
+
 export interface GameProfile {
     name: string;
     description: string | null;
     is_template: boolean;
+    min_gpu_generation: string | null; // "pascal" | "turing" | "ampere" | "ada" | "blackwell"
     executable_match: string | null;
     steam_appid: number | null;
     dlss: DlssSettings;
@@ -145,19 +252,102 @@ export interface GameProfile {
     proton: ProtonSettings;
     wrappers: WrapperSettings;
     screen: ScreenSettings;
+    audio: AudioSettings;
+    vkbasalt: VkBasaltSettings;
+    cpu: CpuSettings;
     custom_env: Record<string, string>;
     custom_args: string | null;
 }
 
+export interface ProfileLockfile {
+    profile: GameProfile;
+    generated_at: number;  // Unix seconds
+    proton_version: string | null;
+    proton_experimental_branch: string | null; // "default" or "bleeding-edge"
+    dxvk_version: string | null;
+    vkd3d_version: string | null;
+    nvidia_driver_version: string | null;
+    gamescope_version: string | null;
+}
+
+export interface ReconcileReport {
+    renamed: string[];             // "old.toml -> new.toml"
+    removed_duplicates: string[];
+    skipped: string[];             // Target name taken by a non-profile file
+}
+
+export interface FieldDiff {
+    field: string;
+    value: unknown;
+    baseline: unknown;
+}
+
 // GPU Commands
 export async function getGpuInfo(): Promise<GpuInfo | null> {
     return invoke<GpuInfo | null>("get_gpu_info");
 }
 
+export async function getGpuInfoByIndex(index: number): Promise<GpuInfo | null> {
+    return invoke<GpuInfo | null>("get_gpu_info_by_index", { index });
+}
+
+// One entry per NVIDIA GPU that answered; empty without NVML
+export async function getAllGpuInfo(): Promise<GpuInfo[]> {
+    return invoke<GpuInfo[]>("get_all_gpu_info");
+}
+
+export async function getGpuDeviceCount(): Promise<number> {
+    return invoke<number>("get_gpu_device_count");
+}
+
+export async function getGpuAdvancedInfo(): Promise<GpuAdvancedInfo | null> {
+    return invoke<GpuAdvancedInfo | null>("get_gpu_advanced_info");
+}
+
+export async function getResizableBarStatus(): Promise<ResizableBarStatus | null> {
+    return invoke<ResizableBarStatus | null>("get_resizable_bar_status");
+}
+
+export async function measureNvmlLatency(): Promise<NvmlLatency | null> {
+    return invoke<NvmlLatency | null>("measure_nvml_latency");
+}
+
+// GPU energy used by the last finished session
+export async function getLastSessionEnergy(): Promise<SessionEnergy | null> {
+    return invoke<SessionEnergy | null>("get_last_session_energy");
+}
+
+// Finished sessions (test launches and captures), oldest first
+export async function getSessionEnergyHistory(): Promise<SessionEnergy[]> {
+    return invoke<SessionEnergy[]>("get_session_energy_history");
+}
+
+export async function lockGpuClocks(minMhz: number, maxMhz: number): Promise<void> {
+    return invoke<void>("lock_gpu_clocks", { minMhz, maxMhz });
+}
+
+export async function unlockGpuClocks(): Promise<void> {
+    return invoke<void>("unlock_gpu_clocks");
+}
+
+export interface PanicRestoreReport {
+    restored: string[];
+    failed: string[]; // "step: reason"
+}
+
+// Rescue after a game leaves a bad display/GPU state; safe to call any time
+export async function panicRestore(): Promise<PanicRestoreReport> {
+    return invoke<PanicRestoreReport>("panic_restore");
+}
+
 export async function getGpuName(): Promise<string> {
     return invoke<string>("get_gpu_name");
 }
 
+export async function generateDebugReport(): Promise<string> {
+    return invoke<string>("generate_debug_report");
+}
+
 // Game Detection Commands
 export async function detectGames(): Promise<Game[]> {
     return invoke<Game[]>("detect_games");
@@ -175,6 +365,80 @@ export async function detectHeroicGames(): Promise<Game[]> {
     return invoke<Game[]>("detect_heroic_games");
 }
 
+export async function getGameProtonOverride(steamAppid: number): Promise<string | null> {
+    return invoke<string | null>("get_game_proton_override", { steamAppid });
+}
+
+// "default" or "bleeding-edge" when the game runs on Proton Experimental
+export async function getProtonExperimentalBranch(steamAppid: number): Promise<string | null> {
+    return invoke<string | null>("get_proton_experimental_branch", { steamAppid });
+}
+
+export interface SteamUser {
+    id: string; // userdata/<id>
+    persona_name: string | null;
+}
+
+export interface AppConfig {
+    config_version: number; // Stored data migrations already run
+    steam_user: string | null; // null = most recently used account
+}
+
+export async function listSteamUsers(): Promise<SteamUser[]> {
+    return invoke<SteamUser[]>("list_steam_users");
+}
+
+export async function getAppConfig(): Promise<AppConfig> {
+    return invoke<AppConfig>("get_app_config");
+}
+
+// Steam account whose userdata (recent games, etc.) is read; null = most recently used
+export async function setSteamUser(id: string | null): Promise<void> {
+    return invoke<void>("set_steam_user", { id });
+}
+
+// Most recently played Steam app IDs, newest first
+export async function getRecentAppids(limit: number): Promise<number[]> {
+    return invoke<number[]>("get_recent_appids", { limit });
+}
+
+export interface PrefixInfo {
+    path: string;
+    source: string;          // "Wine", "Games", "Lutris", "Bottles", "Faugus"
+    arch: string | null;     // "win64" | "win32"
+    version: string | null;
+}
+
+export interface OrphanPrefix {
+    steam_appid: number;
+    path: string;
+    size_bytes: number;
+}
+
+// Wine prefixes outside Steam's compatdata
+export async function listWinePrefixes(): Promise<PrefixInfo[]> {
+    return invoke<PrefixInfo[]>("list_wine_prefixes");
+}
+
+// Prefixes no launcher knows about, as games a profile can be created for
+export async function detectOrphanPrefixes(): Promise<Game[]> {
+    return invoke<Game[]>("detect_orphan_prefixes");
+}
+
+export async function listOrphanedCompatdata(): Promise<OrphanPrefix[]> {
+    return invoke<OrphanPrefix[]>("list_orphaned_compatdata");
+}
+
+// Also deletes saves kept in the prefix. Pass the listed path and the size_bytes
+// the user confirmed; the backend refuses if the prefix no longer matches it
+export async function deleteCompatdata(
+    steamAppid: number,
+    path: string,
+    confirmSizeBytes: number,
+): Promise<number> {
+    return invoke<number>("delete_compatdata", { steamAppid, path, confirmSizeBytes });
+}
+
 // Profile Management Commands
 export async function listProfiles(): Promise<GameProfile[]> {
     return invoke<GameProfile[]>("list_profiles");
@@ -196,18 +460,82 @@ export async function deleteProfile(name: string): Promise<void> {
     return invoke<void>("delete_profile", { name });
 }
 
+export async function listProfileVersions(name: string): Promise<number[]> {
+    return invoke<number[]>("list_profile_versions", { name });
+}
+
+export async function restoreProfileVersion(name: string, timestamp: number): Promise<GameProfile> {
+    return invoke<GameProfile>("restore_profile_version", { name, timestamp });
+}
+
 export async function duplicateProfile(sourceName: string, newName: string): Promise<void> {
     return invoke<void>("duplicate_profile", { sourceName, newName });
 }
 
+export async function exportProfileLockfile(name: string): Promise<ProfileLockfile> {
+    return invoke<ProfileLockfile>("export_profile_lockfile", { name });
+}
+
+export async function reconcileProfiles(): Promise<ReconcileReport> {
+    return invoke<ReconcileReport>("reconcile_profiles");
+}
+
+// Profile name -> whether its bound game is installed (false for unbound profiles)
+export async function checkProfilesBoundStatus(): Promise<Record<string, boolean>> {
+    return invoke<Record<string, boolean>>("check_profiles_bound_status");
+}
+
+export interface DuplicateBinding {
+    steam_appid: number | null;
+    executable: string | null;
+    profiles: string[];
+}
+
+// Games claimed by more than one (non-template) profile
+export async function findDuplicateBindings(): Promise<DuplicateBinding[]> {
+    return invoke<DuplicateBinding[]>("find_duplicate_bindings");
+}
+
+export async function listOrphanedProfiles(): Promise<GameProfile[]> {
+    return invoke<GameProfile[]>("list_orphaned_profiles");
+}
+
+// Delete the listed orphaned profiles, as confirmed by the user; refuses when no games are detected
+export async function pruneOrphanedProfiles(names: string[]): Promise<string[]> {
+    return invoke<string[]>("prune_orphaned_profiles", { names });
+}
+
+export async function diffFromDefault(name: string, baseline?: string): Promise<FieldDiff[]> {
+    return invoke<FieldDiff[]>("diff_from_default", { name, baseline: baseline ?? null });
+}
+
 export async function listTemplateProfiles(): Promise<GameProfile[]> {
     return invoke<GameProfile[]>("list_template_profiles");
 }
 
+// Templates whose min_gpu_generation the detected GPU meets
+export async function listApplicableTemplates(): Promise<GameProfile[]> {
+    return invoke<GameProfile[]>("list_applicable_templates");
+}
+
 export async function applyTemplate(templateName: string, gameName: string): Promise<GameProfile> {
     return invoke<GameProfile>("apply_template", { templateName, gameName });
 }
 
+export async function makeSafe(name: string): Promise<GameProfile> {
+    return invoke<GameProfile>("make_safe", { name });
+}
+
+// Parses only; name and save the returned profile to keep it
+export async function importSteamLaunchOptions(line: string): Promise<GameProfile> {
+    return invoke<GameProfile>("import_steam_launch_options", { line });
+}
+
+// Lutris game YAML (system.env, gamemode/mangohud, prefix_command) for a saved profile
+export async function exportProfileToLutris(name: string): Promise<string> {
+    return invoke<string>("export_profile_to_lutris", { name });
+}
+
 export async function buildEnvVars(profile: GameProfile): Promise<Record<string, string>> {
     return invoke<Record<string, string>>("build_env_vars", { profile });
 }
@@ -216,6 +544,108 @@ export async function buildWrapperCmd(profile: GameProfile): Promise<string[]> {
     return invoke<string[]>("build_wrapper_cmd", { profile });
 }
 
+// Compact profile overview for library cards
+export interface ProfileSummary {
+    name: string;
+    game: string | null;  // Installed game the profile is bound to
+    is_template: boolean;
+    badges: string[];  // "DLSS", "Frame Gen", "RT", "HDR", "Gamescope", "MangoHud", ...
+    frame_cap: number | null;
+    sync_mode: string | null;  // "esync", "fsync", "ntsync"; null for Proton's default
+    warning_count: number;
+}
+
+export async function getProfileSummary(name: string): Promise<ProfileSummary> {
+    return invoke<ProfileSummary>("get_profile_summary", { name });
+}
+
+export async function listProfileSummaries(): Promise<ProfileSummary[]> {
+    return invoke<ProfileSummary[]>("list_profile_summaries");
+}
+
+// Executable names of the profile's Steam or Faugus game, for filling executable_match
+export async function suggestExecutableMatches(profileName: string): Promise<string[]> {
+    return invoke<string[]>("suggest_executable_matches", { profileName });
+}
+
+export async function checkProfileWarnings(profile: GameProfile): Promise<string[]> {
+    return invoke<string[]>("check_profile_warnings", { profile });
+}
+
+// Warnings when the gamescope output doesn't fit the monitor's native resolution
+export async function validateGamescopeResolution(profile: GameProfile, monitorName: string): Promise<string[]> {
+    return invoke<string[]>("validate_gamescope_resolution", { profile, monitorName });
+}
+
+// Pre-launch warnings: DLSS overrides on a game without DLSS, gamescope output vs. monitor, missing wrappers
+export async function precheckLaunch(profile: GameProfile, steamAppid?: number): Promise<string[]> {
+    return invoke<string[]>("precheck_launch", { profile, steamAppid: steamAppid ?? null });
+}
+
+// Frame caps (DXVK, VKD3D, MangoHud, gamescope -r) that disagree; also part of checkProfileWarnings
+export async function detectFramecapConflicts(profile: GameProfile): Promise<string[]> {
+    return invoke<string[]>("detect_framecap_conflicts", { profile });
+}
+
+// "32" or "64" from the PE header; null when the file isn't a Windows executable
+export async function detectExeBitness(path: string): Promise<"32" | "64" | null> {
+    return invoke<"32" | "64" | null>("detect_exe_bitness", { path });
+}
+
+// fieldPath is dotted, e.g. "wrappers.gamemode" or "dlss.upgrade"; must name a boolean field
+export async function setProfileFlag(name: string, fieldPath: string, value: boolean): Promise<GameProfile> {
+    return invoke<GameProfile>("set_profile_flag", { name, fieldPath, value });
+}
+
+// Values from the profile file that wouldn't survive a load/save cycle; empty means safe
+export async function verifyProfileRoundtrip(name: string): Promise<string[]> {
+    return invoke<string[]>("verify_profile_roundtrip", { name });
+}
+
+// Sorted `export KEY=VALUE` lines grouped by category, for pasting into a shell
+export async function buildEnvExportBlock(profile: GameProfile): Promise<string> {
+    return invoke<string>("build_env_export_block", { profile });
+}
+
+// Env var -> profile field that produced it, e.g. "wrappers.frame_limiter.target_fps"
+export async function traceEnvSources(profile: GameProfile): Promise<Record<string, string>> {
+    return invoke<Record<string, string>>("trace_env_sources", { profile });
+}
+
+export interface EffectiveEnvVar {
+    value: string;
+    proton_default: string | null;
+    origin: "Proton" | "Profile" | "OverridesProton" | "SameAsProton" | "ForcedByProton";
+}
+
+export interface EffectiveGraphicsEnv {
+    proton: string | null;        // Proton build the defaults were read from; null = built-in list
+    vars: Record<string, EffectiveEnvVar>;
+}
+
+// DXVK/VKD3D env after layering the profile over Proton's own defaults
+export async function computeEffectiveGraphicsEnv(
+    profile: GameProfile,
+    steamAppid?: number
+): Promise<EffectiveGraphicsEnv> {
+    return invoke<EffectiveGraphicsEnv>("compute_effective_graphics_env", {
+        profile,
+        steamAppid: steamAppid ?? null,
+    });
+}
+
+export interface CustomEnvNote {
+    key: string;
+    kind: "managed" | "known" | "unknown";
+    field: string | null;         // First-class profile field to migrate to
+    description: string | null;
+}
+
+// Classify custom_env keys: covered by a profile field, documented, or unknown
+export async function analyzeCustomEnv(profile: GameProfile): Promise<CustomEnvNote[]> {
+    return invoke<CustomEnvNote[]>("analyze_custom_env", { profile });
+}
+
 // LACT Integration
 export async function isLactAvailable(): Promise<boolean> {
     return invoke<boolean>("is_lact_available");
@@ -225,6 +655,11 @@ export async function getLactProfiles(): Promise<string[]> {
     return invoke<string[]>("get_lact_profiles");
 }
 
+// Audio
+export async function listAudioSinks(): Promise<string[]> {
+    return invoke<string[]>("list_audio_sinks");
+}
+
 // Screen Configuration Commands
 export async function detectCompositor(): Promise<string> {
     return invoke<string>("detect_compositor");
@@ -238,6 +673,10 @@ export async function listMonitors(): Promise<Monitor[]> {
     return invoke<Monitor[]>("list_monitors");
 }
 
+export async function getFocusedMonitor(): Promise<Monitor | null> {
+    return invoke<Monitor | null>("get_focused_monitor");
+}
+
 export async function isScreenConfigSupported(): Promise<boolean> {
     return invoke<boolean>("is_screen_config_supported");
 }
@@ -254,10 +693,169 @@ export async function setGameMonitorRule(windowClass: string, monitorName: strin
     return invoke<void>("set_game_monitor_rule", { windowClass, monitorName });
 }
 
+// The app's own window class (Sway: app_id), found by pid in the compositor's client list
+export async function getSelfWindowClass(): Promise<string> {
+    return invoke<string>("get_self_window_class");
+}
+
+// Hyprland windowrulev2 (e.g. "float") or Sway command (e.g. "floating enable") for the app's own window
+export async function setSelfWindowRule(rule: string): Promise<void> {
+    return invoke<void>("set_self_window_rule", { rule });
+}
+
+export async function setGameWorkspaceRule(windowClass: string, workspace: string, persist: boolean): Promise<void> {
+    return invoke<void>("set_game_workspace_rule", { windowClass, workspace, persist });
+}
+
+export async function removeGameWorkspaceRule(windowClass: string): Promise<void> {
+    return invoke<void>("remove_game_workspace_rule", { windowClass });
+}
+
+// Hyprland "immediate" rule, kept in the rules file; errors on other compositors
+export async function setGameTearingRule(windowClass: string, enabled: boolean): Promise<void> {
+    return invoke<void>("set_game_tearing_rule", { windowClass, enabled });
+}
+
+export async function applyScreenSettings(profile: GameProfile): Promise<void> {
+    return invoke<void>("apply_screen_settings", { profile });
+}
+
+export async function previewScreenCommands(profile: GameProfile): Promise<string[]> {
+    return invoke<string[]>("preview_screen_commands", { profile });
+}
+
 export async function getMonitorConfigs(): Promise<Record<string, string>> {
     return invoke<Record<string, string>>("get_monitor_configs");
 }
 
+// Frame limiter target a few frames under the monitor's refresh rate (margin defaults to 3)
+export async function suggestFrameCap(monitorName: string, margin?: number): Promise<number> {
+    return invoke<number>("suggest_frame_cap", { monitorName, margin: margin ?? null });
+}
+
+// Per-monitor night colors (wlsunset; Hyprland/Sway only)
+export async function setMonitorGamma(name: string, gamma: number): Promise<void> {
+    return invoke<void>("set_monitor_gamma", { name, gamma });
+}
+
+export async function setMonitorTemperature(name: string, kelvin: number): Promise<void> {
+    return invoke<void>("set_monitor_temperature", { name, kelvin });
+}
+
+export async function resetMonitorColor(name: string): Promise<void> {
+    return invoke<void>("reset_monitor_color", { name });
+}
+
+export async function getHdrSupport(): Promise<HdrSupport> {
+    return invoke<HdrSupport>("get_hdr_support");
+}
+
+export interface CompositorDisplayState {
+    compositor: string;
+    vrr_mode: "off" | "on" | "fullscreen" | "fullscreen-game" | "mixed" | null;
+    allow_tearing: boolean | null;
+    hdr_available: boolean;
+}
+
+// Global VRR/tearing/HDR state per-game overrides build on; null where the compositor doesn't say
+export async function getCompositorDisplayState(): Promise<CompositorDisplayState> {
+    return invoke<CompositorDisplayState>("get_compositor_display_state");
+}
+
+export async function getMonitorColorInfo(name: string): Promise<ColorInfo> {
+    return invoke<ColorInfo>("get_monitor_color_info", { name });
+}
+
+export async function suggestIntegerScaleResolutions(monitor: string): Promise<string[]> {
+    return invoke<string[]>("suggest_integer_scale_resolutions", { monitor });
+}
+
+export async function captureScreenshot(outputPath: string): Promise<void> {
+    return invoke<void>("capture_screenshot", { outputPath });
+}
+
+// Saved as ~/Pictures/unvcpfl/<profile>-<time>.png; returns the path
+export async function captureProfileScreenshot(profileName: string): Promise<string> {
+    return invoke<string>("capture_profile_screenshot", { profileName });
+}
+
+export async function getDockProfiles(): Promise<DockProfiles> {
+    return invoke<DockProfiles>("get_dock_profiles");
+}
+
+export async function saveDockProfiles(profiles: DockProfiles): Promise<void> {
+    return invoke<void>("save_dock_profiles", { profiles });
+}
+
+export async function isDocked(): Promise<boolean> {
+    return invoke<boolean>("is_docked");
+}
+
+export async function applyDockProfile(): Promise<void> {
+    return invoke<void>("apply_dock_profile");
+}
+
+export async function applyUndockedProfile(): Promise<void> {
+    return invoke<void>("apply_undocked_profile");
+}
+
+export async function saveMonitorLayout(name: string): Promise<void> {
+    return invoke<void>("save_monitor_layout", { name });
+}
+
+export async function listMonitorLayouts(): Promise<MonitorLayout[]> {
+    return invoke<MonitorLayout[]>("list_monitor_layouts");
+}
+
+export async function applyMonitorLayout(name: string): Promise<void> {
+    return invoke<void>("apply_monitor_layout", { name });
+}
+
+export async function setDesktopGamemode(enabled: boolean): Promise<void> {
+    return invoke<void>("set_desktop_gamemode", { enabled });
+}
+
+export interface RestoreReport {
+    restored: number;
+    skipped: string[];
+    warnings: string[];
+}
+
+// Full config backup (profiles, history, layouts, generated configs) as .tar.gz
+export async function createFullBackup(dest: string): Promise<void> {
+    return invoke<void>("create_full_backup", { dest });
+}
+
+export async function restoreFullBackup(src: string, skipMachineSpecific: boolean): Promise<RestoreReport> {
+    return invoke<RestoreReport>("restore_full_backup", { src, skipMachineSpecific });
+}
+
+// Uncompressed bytes of a game's PCGamingWiki save and config locations
+export async function estimateSaveBackupSize(steamAppid: number): Promise<number> {
+    return invoke<number>("estimate_save_backup_size", { steamAppid });
+}
+
+// Fails without writing anything when dest's drive lacks room for the estimate plus a margin
+export async function backupGameSaves(steamAppid: number, dest: string): Promise<void> {
+    return invoke<void>("backup_game_saves", { steamAppid, dest });
+}
+
+export type PowerMizerMode = "auto" | "max" | "adaptive";
+
+// NVIDIA PowerMizer preferred mode (nvidia-settings)
+export async function getPowermizerMode(): Promise<PowerMizerMode> {
+    return invoke<PowerMizerMode>("get_powermizer_mode");
+}
+
+export async function setPowermizerMode(mode: PowerMizerMode): Promise<void> {
+    return invoke<void>("set_powermizer_mode", { mode });
+}
+
+// Driver-wide VRR (AllowVRR); unlike nvidia.gsync_allowed this affects every app
+export async function setGsyncAllowed(enabled: boolean): Promise<void> {
+    return invoke<void>("set_gsync_allowed", { enabled });
+}
+
 // PCGamingWiki Game Data Paths Types
 export interface GamePath {
     platform: string;
@@ -278,6 +876,72 @@ export async function getGameDataPaths(steamAppid: number): Promise<GameDataPath
     return invoke<GameDataPaths>("get_game_data_paths", { steamAppid });
 }
 
+// Last-used resolution from the game's config or prefix registry, [width, height]
+export async function detectGameResolution(steamAppid: number): Promise<[number, number] | null> {
+    return invoke<[number, number] | null>("detect_game_resolution", { steamAppid });
+}
+
+// Graphics APIs from PCGamingWiki, e.g. ["Direct3D 11", "Vulkan"]; empty when unknown
+export async function detectGameGraphicsApi(steamAppid: number): Promise<string[]> {
+    return invoke<string[]>("detect_game_graphics_api", { steamAppid });
+}
+
+// Winetricks verbs (vcrun2019, d3dx9_43, ...) named in PCGamingWiki fixes or implied by middleware
+export async function suggestWinetricks(steamAppid: number): Promise<string[]> {
+    return invoke<string[]>("suggest_winetricks", { steamAppid });
+}
+
+// Installs verbs into the game's prefix with protontricks (or winetricks); returns its output
+export async function applyWinetricks(steamAppid: number, verbs: string[]): Promise<string> {
+    return invoke<string>("apply_winetricks", { steamAppid, verbs });
+}
+
+export interface LaunchOptionTip {
+    options: string;
+    count: number;
+}
+
+export interface ProtonTips {
+    steam_appid: number;
+    tier: string | null;
+    total_reports: number;
+    launch_options: LaunchOptionTip[];
+    suggested_env: Record<string, string>;
+    error: string | null;
+}
+
+// ProtonDB Commands
+export async function fetchProtondbTips(steamAppid: number): Promise<ProtonTips> {
+    return invoke<ProtonTips>("fetch_protondb_tips", { steamAppid });
+}
+
+export interface CacheFile {
+    path: string;
+    size_bytes: number;
+}
+
+export interface ShaderCacheInfo {
+    steam_appid: number;
+    dxvk_cache_files: CacheFile[];
+    shader_cache_dir: string | null;
+    shader_cache_size_bytes: number;
+    total_size_bytes: number;
+    state_cache_enabled: boolean;
+}
+
+export async function getShaderCacheInfo(steamAppid: number): Promise<ShaderCacheInfo> {
+    return invoke<ShaderCacheInfo>("get_shader_cache_info", { steamAppid });
+}
+
+// Returns bytes freed. Pass the total_size_bytes the user confirmed;
+// the backend refuses if the caches no longer match it
+export async function clearShaderCache(
+    steamAppid: number,
+    confirmSizeBytes: number,
+): Promise<number> {
+    return invoke<number>("clear_shader_cache", { steamAppid, confirmSizeBytes });
+}
+
 export async function openGamePath(path: string, inEditor: boolean): Promise<void> {
     return invoke<void>("open_game_path", { path, inEditor });
 }
@@ -287,10 +951,167 @@ export async function getHostname(): Promise<string> {
     return invoke<string>("get_hostname");
 }
 
+export interface SystemInfo {
+    kernel_version: string;
+    nvidia_driver_version: string | null;
+    nvidia_modules_loaded: string[];
+    nvidia_kernel_module: "open" | "proprietary" | null;
+    ntsync_available: boolean;
+    mangohud_layer_installed: boolean;
+    vulkan_icds: string[];
+    nvidia_icd_first: boolean;
+    steam_deck: boolean;
+}
+
+export async function getSystemInfo(): Promise<SystemInfo> {
+    return invoke<SystemInfo>("get_system_info");
+}
+
+export async function checkMangohudLayer(): Promise<boolean> {
+    return invoke<boolean>("check_mangohud_layer");
+}
+
+// Whether the installed gamescope accepts --hdr-enabled (false when gamescope is missing)
+export async function checkGamescopeHdrSupport(): Promise<boolean> {
+    return invoke<boolean>("check_gamescope_hdr_support");
+}
+
+// Frame limit settings from ~/.config/MangoHud/<exe>.conf (or wine-<exe>.conf), for import
+export async function getGameMangohudOverride(exeName: string): Promise<MangoHudSettings | null> {
+    return invoke<MangoHudSettings | null>("get_game_mangohud_override", { exeName });
+}
+
+export async function getActiveVulkanIcd(): Promise<string[]> {
+    return invoke<string[]>("get_active_vulkan_icd");
+}
+
+export async function getKernelModuleType(): Promise<"open" | "proprietary" | null> {
+    return invoke<"open" | "proprietary" | null>("get_kernel_module_type");
+}
+
+// Managed env vars exported from shell startup files, e.g. "~/.bashrc:12: DXVK_HUD=fps"
+export async function scanShellEnvConflicts(): Promise<string[]> {
+    return invoke<string[]>("scan_shell_env_conflicts");
+}
+
 export async function createDesktopEntry(game: Game, profile: GameProfile): Promise<string> {
     return invoke<string>("create_desktop_entry", { game, profile });
 }
 
+export interface LaunchPreview {
+    command: string;
+    steam_cold_start: boolean;  // Steam isn't running, so the first launch starts it
+}
+
+// Switch to a game's LACT profile only while it has focus (Hyprland)
+export async function startFocusFollow(): Promise<void> {
+    return invoke<void>("start_focus_follow");
+}
+
+export async function stopFocusFollow(): Promise<void> {
+    return invoke<void>("stop_focus_follow");
+}
+
+export async function startMetricsServer(port: number): Promise<void> {
+    return invoke<void>("start_metrics_server", { port });
+}
+
+export async function stopMetricsServer(): Promise<void> {
+    return invoke<void>("stop_metrics_server");
+}
+
+// GPU telemetry for a play session, sampled once a second
+// Energy used is added to the session energy history when it stops
+export async function startSessionCapture(game?: string): Promise<void> {
+    return invoke<void>("start_session_capture", { game });
+}
+
+// Returns the number of samples captured
+export async function stopSessionCapture(): Promise<number> {
+    return invoke<number>("stop_session_capture");
+}
+
+// Writes the running (or last stopped) capture as CSV
+export async function exportGpuSessionCsv(dest: string): Promise<void> {
+    return invoke<void>("export_gpu_session_csv", { dest });
+}
+
+// Time-of-day GPU settings; `end` before `start` wraps past midnight
+export interface GpuScheduleEntry {
+    start: string; // "HH:MM"
+    end: string;
+    power_limit_watts?: number;
+    lact_profile?: string;
+}
+
+export interface GpuSchedule {
+    enabled: boolean;
+    entries: GpuScheduleEntry[];
+    default_lact_profile?: string;
+}
+
+export async function getGpuSchedule(): Promise<GpuSchedule> {
+    return invoke<GpuSchedule>("get_gpu_schedule");
+}
+
+export async function setGpuSchedule(schedule: GpuSchedule): Promise<void> {
+    return invoke<void>("set_gpu_schedule", { schedule });
+}
+
+// Why the running schedule last failed to apply (e.g. power limit needs root)
+export async function getGpuScheduleError(): Promise<string | null> {
+    return invoke<string | null>("get_gpu_schedule_error");
+}
+
+export async function isSteamRunning(): Promise<boolean> {
+    return invoke<boolean>("is_steam_running");
+}
+
+export async function previewLaunch(game: Game, profile: GameProfile): Promise<LaunchPreview> {
+    return invoke<LaunchPreview>("preview_launch", { game, profile });
+}
+
+export interface GenericLaunchCommand {
+    argv: string[];        // env, wrappers, then umu-run or wine with the exe
+    pre_launch: string[];  // Shell commands to run first, e.g. the LACT profile switch
+}
+
+// Launch command for running any Windows exe with a profile
+export async function buildGenericLaunchCommand(exePath: string, profile: GameProfile): Promise<GenericLaunchCommand> {
+    return invoke<GenericLaunchCommand>("build_generic_launch_command", { exePath, profile });
+}
+
+export interface TestLaunchResult {
+    success: boolean;
+    exit_code: number | null;
+    stderr: string;
+    message: string;
+}
+
+// Settings applied to a game started outside the app, and the ones that need a relaunch
+export interface RunningGameResult {
+    applied: string[];  // Profile fields, e.g. "screen.target_monitor"
+    skipped: string[];  // "field: reason"
+}
+
+export async function applyToRunningGame(windowClass: string, profileName: string): Promise<RunningGameResult> {
+    return invoke<RunningGameResult>("apply_to_running_game", { windowClass, profileName });
+}
+
+export async function testLaunch(
+    game: Game,
+    profile: GameProfile,
+    timeoutSecs?: number,
+    lockClocksMhz?: [number, number],
+): Promise<TestLaunchResult> {
+    return invoke<TestLaunchResult>("test_launch", {
+        game,
+        profile,
+        timeoutSecs: timeoutSecs ?? null,
+        lockClocksMhz: lockClocksMhz ?? null,
+    });
+}
+
 // Utility functions
 export function formatBytes(bytes: number): string {
     if (bytes === 0) return "0 B";